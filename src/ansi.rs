@@ -0,0 +1,318 @@
+/// ANSI escape sequence handling for task output.
+///
+/// Task output (especially in PTY mode) contains SGR escape sequences for
+/// colors and text attributes. This module turns such a line into plain-text
+/// segments paired with ratatui styles, so the log pane can render them while
+/// selection and copying keep working on the plain text underneath.
+use ratatui::style::{Color, Modifier, Style};
+
+/// Escape character that introduces every ANSI sequence.
+const ESC: char = '\x1b';
+
+/// Tab stops are placed every this many columns.
+const TAB_WIDTH: usize = 8;
+
+/// Parses a line containing ANSI escape sequences into styled plain-text segments.
+///
+/// SGR sequences (`ESC [ ... m`) update the style of the text that follows;
+/// all other escape sequences and control characters are dropped, and tabs are
+/// expanded to spaces. Concatenating the segment texts yields `plain_text(line)`.
+///
+/// The returned styles only contain what the escapes set, so callers can patch
+/// them onto a base style.
+pub fn parse_line(line: &str) -> Vec<(String, Style)> {
+    let mut segments: Vec<(String, Style)> = Vec::new();
+    let mut style = Style::default();
+    let mut text = String::new();
+    let mut column = 0;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            ESC => match chars.next() {
+                // Control Sequence Introducer: parameters up to a final byte
+                Some('[') => {
+                    let mut params = String::new();
+                    let mut final_byte = None;
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            final_byte = Some(c);
+                            break;
+                        }
+                        params.push(c);
+                    }
+
+                    if final_byte == Some('m') {
+                        let new_style = apply_sgr(style, &params);
+                        if new_style != style && !text.is_empty() {
+                            segments.push((std::mem::take(&mut text), style));
+                        }
+                        style = new_style;
+                    }
+                }
+                // Operating System Command: terminated by BEL or ST (ESC \)
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == ESC {
+                            chars.next_if_eq(&'\\');
+                            break;
+                        }
+                    }
+                }
+                // Any other escape is a single character we don't interpret
+                _ => {}
+            },
+            '\t' => {
+                let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                text.extend(std::iter::repeat_n(' ', spaces));
+                column += spaces;
+            }
+            c if c.is_control() => {}
+            c => {
+                text.push(c);
+                column += unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+            }
+        }
+    }
+
+    if !text.is_empty() {
+        segments.push((text, style));
+    }
+
+    segments
+}
+
+/// Returns the text of a line as displayed, without any escape sequences.
+///
+/// This is the text that selections and copying operate on.
+pub fn plain_text(line: &str) -> String {
+    // Fast path: most lines contain neither escapes nor control characters
+    if !line.chars().any(|c| c.is_control()) {
+        return line.to_string();
+    }
+
+    parse_line(line).into_iter().map(|(text, _)| text).collect()
+}
+
+/// Applies the parameters of an SGR sequence to the given style.
+fn apply_sgr(mut style: Style, params: &str) -> Style {
+    let params: Vec<&str> = params.split(';').collect();
+
+    // An empty parameter means 0 (so `ESC [ m` is a reset)
+    let codes: Vec<Option<u16>> = params
+        .iter()
+        .map(|p| {
+            if p.is_empty() {
+                Some(0)
+            } else {
+                p.parse().ok()
+            }
+        })
+        .collect();
+
+    let mut i = 0;
+    while i < params.len() {
+        if params[i].contains(':') {
+            // Colon-separated sub-parameters form one self-contained code (e.g. `38:2::r:g:b`)
+            let sub: Vec<Option<u16>> = params[i].split(':').map(|p| p.parse().ok()).collect();
+            if let Some(code) = sub[0] {
+                style = apply_code(style, code, &sub[1..], true).0;
+            }
+        } else if let Some(code) = codes[i] {
+            // Semicolon-separated extended colors consume the codes that follow them
+            let (new_style, consumed) = apply_code(style, code, &codes[i + 1..], false);
+            style = new_style;
+            i += consumed;
+        }
+        i += 1;
+    }
+
+    style
+}
+
+/// Applies a single SGR code with its arguments.
+///
+/// Returns the new style and how many of `args` were consumed.
+fn apply_code(mut style: Style, code: u16, args: &[Option<u16>], colon: bool) -> (Style, usize) {
+    let mut consumed = 0;
+
+    match code {
+        0 => style = Style::default(),
+        1 => style = style.add_modifier(Modifier::BOLD),
+        2 => style = style.add_modifier(Modifier::DIM),
+        3 => style = style.add_modifier(Modifier::ITALIC),
+        4 => style = style.add_modifier(Modifier::UNDERLINED),
+        5 => style = style.add_modifier(Modifier::SLOW_BLINK),
+        6 => style = style.add_modifier(Modifier::RAPID_BLINK),
+        7 => style = style.add_modifier(Modifier::REVERSED),
+        8 => style = style.add_modifier(Modifier::HIDDEN),
+        9 => style = style.add_modifier(Modifier::CROSSED_OUT),
+        21 | 22 => style = style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+        23 => style = style.remove_modifier(Modifier::ITALIC),
+        24 => style = style.remove_modifier(Modifier::UNDERLINED),
+        25 => style = style.remove_modifier(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK),
+        27 => style = style.remove_modifier(Modifier::REVERSED),
+        28 => style = style.remove_modifier(Modifier::HIDDEN),
+        29 => style = style.remove_modifier(Modifier::CROSSED_OUT),
+        30..=37 => style.fg = Some(basic_color(code - 30)),
+        38 => {
+            let (color, used) = extended_color(args, colon);
+            style.fg = color.or(style.fg);
+            consumed = used;
+        }
+        39 => style.fg = None,
+        40..=47 => style.bg = Some(basic_color(code - 40)),
+        48 => {
+            let (color, used) = extended_color(args, colon);
+            style.bg = color.or(style.bg);
+            consumed = used;
+        }
+        49 => style.bg = None,
+        90..=97 => style.fg = Some(basic_color(code - 90 + 8)),
+        100..=107 => style.bg = Some(basic_color(code - 100 + 8)),
+        _ => {}
+    }
+
+    (style, consumed)
+}
+
+/// Parses the arguments of an extended color (`38;...` / `48;...`).
+///
+/// Returns the color (if valid) and how many arguments were consumed.
+fn extended_color(args: &[Option<u16>], colon: bool) -> (Option<Color>, usize) {
+    let component = |idx: usize| {
+        args.get(idx)
+            .copied()
+            .flatten()
+            .and_then(|n| u8::try_from(n).ok())
+    };
+
+    match args.first() {
+        // 256-color palette: 5;n
+        Some(Some(5)) => {
+            let color = component(1).map(|n| match n {
+                0..=15 => basic_color(n as u16),
+                n => Color::Indexed(n),
+            });
+            (color, 2.min(args.len()))
+        }
+        // Truecolor: 2;r;g;b (the colon form may insert a colorspace id: 2:id:r:g:b)
+        Some(Some(2)) => {
+            let first = if colon && args.len() >= 5 { 2 } else { 1 };
+            let color = match (component(first), component(first + 1), component(first + 2)) {
+                (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r, g, b)),
+                _ => None,
+            };
+            (color, (first + 3).min(args.len()))
+        }
+        _ => (None, 0),
+    }
+}
+
+/// Maps one of the 16 standard terminal colors to a ratatui color.
+fn basic_color(index: u16) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_line_is_single_unstyled_segment() {
+        let segments = parse_line("cargo build");
+        assert_eq!(
+            segments,
+            vec![("cargo build".to_string(), Style::default())]
+        );
+    }
+
+    #[test]
+    fn test_basic_colors_and_reset() {
+        let segments = parse_line("\x1b[31merror\x1b[0m: failed");
+        assert_eq!(
+            segments,
+            vec![
+                ("error".to_string(), Style::default().fg(Color::Red)),
+                (": failed".to_string(), Style::default()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_combined_attributes() {
+        let segments = parse_line("\x1b[1;4;92mok\x1b[22m!");
+        let bold = Style::default()
+            .fg(Color::LightGreen)
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+        assert_eq!(segments[0], ("ok".to_string(), bold));
+        assert_eq!(
+            segments[1],
+            (
+                "!".to_string(),
+                bold.remove_modifier(Modifier::BOLD | Modifier::DIM)
+            )
+        );
+    }
+
+    #[test]
+    fn test_256_and_truecolor() {
+        let segments = parse_line("\x1b[38;5;208ma\x1b[48;2;10;20;30mb\x1b[38:2::1:2:3mc");
+        assert_eq!(segments[0].1.fg, Some(Color::Indexed(208)));
+        assert_eq!(segments[1].1.bg, Some(Color::Rgb(10, 20, 30)));
+        assert_eq!(segments[2].1.fg, Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(segments[2].1.bg, Some(Color::Rgb(10, 20, 30)));
+    }
+
+    #[test]
+    fn test_italic_and_reverse() {
+        let segments = parse_line("\x1b[3;7mx\x1b[23;27my");
+        assert_eq!(
+            segments[0].1,
+            Style::default().add_modifier(Modifier::ITALIC | Modifier::REVERSED)
+        );
+        assert!(!segments[1].1.add_modifier.contains(Modifier::ITALIC));
+        assert!(!segments[1].1.add_modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn test_non_sgr_sequences_are_dropped() {
+        // Cursor movement, OSC hyperlinks and stray control characters
+        let line = "\x1b[2K\x1b]8;;https://example.com\x07link\x1b]8;;\x1b\\\x07 done";
+        assert_eq!(plain_text(line), "link done");
+    }
+
+    #[test]
+    fn test_tabs_expand_to_tab_stops() {
+        assert_eq!(plain_text("a\tb"), "a       b");
+        assert_eq!(plain_text("\x1b[1mab\x1b[0m\tc"), "ab      c");
+    }
+
+    #[test]
+    fn test_plain_text_matches_segments() {
+        let line = "\x1b[33mwarning\x1b[0m: unused\x1b[1m variable\x1b[0m";
+        let joined: String = parse_line(line).into_iter().map(|(t, _)| t).collect();
+        assert_eq!(plain_text(line), joined);
+        assert_eq!(joined, "warning: unused variable");
+    }
+}
@@ -1,6 +1,14 @@
 /// Core application data structures and state management for Taskpad.
-use std::collections::HashMap;
-use std::time::SystemTime;
+use crate::ansi;
+use crate::log_buffer::LogBuffer;
+use crate::process::{ExecMode, LogEvent, PtySize, RunOptions, StopPolicy};
+use crate::tasks::registry::{Discovery, SourceDiagnostic, SourceOutcome};
+use color_eyre::eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
 use unicode_width::UnicodeWidthChar;
 
 /// Maximum number of log lines to store per task to prevent unbounded memory growth.
@@ -9,6 +17,12 @@ const MAX_LOG_LINES_PER_TASK: usize = 10_000;
 /// Maximum number of history entries to store to prevent unbounded memory growth.
 const MAX_HISTORY_ENTRIES: usize = 100;
 
+/// How long tasks added by a reload are highlighted in the task list.
+const FLASH_DURATION: Duration = Duration::from_secs(3);
+
+/// Maximum number of added or removed tasks named in the reload message.
+const MAX_CHANGED_NAMES: usize = 3;
+
 /// Returns the display width of a string, accounting for character widths.
 /// Wide characters (e.g., CJK, emoji) count as 2 columns.
 pub fn str_display_width(s: &str) -> usize {
@@ -74,41 +88,243 @@ pub enum FocusedPane {
     Tasks,
     /// History pane is focused
     History,
+    /// Log pane is focused: keys are typed into the selected task's terminal
+    Logs,
 }
 
 /// Task runner type.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TaskRunner {
     /// Just command runner
+    #[default]
     Just,
     /// Make build tool
     Make,
+    /// package.json scripts, run through the project's package manager
+    Package(PackageManager),
+    /// Cargo aliases, binaries and examples
+    Cargo,
+    /// Task (go-task) tasks from a Taskfile
+    GoTask,
+    /// mise tasks from `mise.toml` and task directories
+    Mise,
+    /// Python project scripts, tox environments and nox sessions
+    Python(PythonTool),
+    /// docker compose services
+    Compose,
+    /// VS Code tasks from `.vscode/tasks.json`
+    VsCode,
+    /// Executables in a script directory like `scripts/`
+    Script,
+    /// Tasks defined in the Taskpad config
+    Custom,
+    /// Tasks found by an external `taskpad-source-<name>` executable (named by the source)
+    External(String),
 }
 
 impl TaskRunner {
     /// Returns the display prefix for this runner
-    pub fn prefix(&self) -> &str {
-        match self {
+    pub fn prefix(&self) -> Cow<'_, str> {
+        let prefix = match self {
+            TaskRunner::External(name) => return Cow::Owned(format!("[{}]", name)),
             TaskRunner::Just => "[just]",
             TaskRunner::Make => "[make]",
+            TaskRunner::Cargo => "[cargo]",
+            TaskRunner::GoTask => "[task]",
+            TaskRunner::Mise => "[mise]",
+            TaskRunner::Compose => "[compose]",
+            TaskRunner::VsCode => "[vscode]",
+            TaskRunner::Script => "[script]",
+            TaskRunner::Custom => "[custom]",
+            TaskRunner::Python(tool) => match tool {
+                PythonTool::Poetry => "[poetry]",
+                PythonTool::Pdm => "[pdm]",
+                PythonTool::Hatch => "[hatch]",
+                PythonTool::Uv => "[uv]",
+                PythonTool::Tox => "[tox]",
+                PythonTool::Nox => "[nox]",
+            },
+            TaskRunner::Package(manager) => match manager {
+                PackageManager::Npm => "[npm]",
+                PackageManager::Pnpm => "[pnpm]",
+                PackageManager::Yarn => "[yarn]",
+                PackageManager::Bun => "[bun]",
+            },
+        };
+        Cow::Borrowed(prefix)
+    }
+
+    /// Returns true if parameters are passed as `NAME=value` rather than by position
+    pub fn passes_named_args(&self) -> bool {
+        matches!(self, TaskRunner::GoTask)
+    }
+}
+
+/// JavaScript package manager running package.json scripts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PackageManager {
+    /// npm (`package-lock.json`)
+    #[default]
+    Npm,
+    /// pnpm (`pnpm-lock.yaml`)
+    Pnpm,
+    /// Yarn (`yarn.lock`)
+    Yarn,
+    /// Bun (`bun.lockb`)
+    Bun,
+}
+
+impl PackageManager {
+    /// Returns the command name for this package manager
+    pub fn command(&self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Pnpm => "pnpm",
+            PackageManager::Yarn => "yarn",
+            PackageManager::Bun => "bun",
         }
     }
+}
+
+/// Python tool running a project's scripts, environments or sessions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum PythonTool {
+    /// Poetry (`[tool.poetry.scripts]`)
+    Poetry,
+    /// PDM (`[tool.pdm.scripts]`)
+    Pdm,
+    /// Hatch (`[tool.hatch.envs.<env>.scripts]`)
+    Hatch,
+    /// uv, running `[project.scripts]` of projects without another tool
+    Uv,
+    /// tox environments (`tox.ini`)
+    Tox,
+    /// nox sessions (`noxfile.py`)
+    Nox,
+}
+
+impl PythonTool {
+    /// Returns the command name for this tool
+    pub fn command(&self) -> &'static str {
+        match self {
+            PythonTool::Poetry => "poetry",
+            PythonTool::Pdm => "pdm",
+            PythonTool::Hatch => "hatch",
+            PythonTool::Uv => "uv",
+            PythonTool::Tox => "tox",
+            PythonTool::Nox => "nox",
+        }
+    }
+}
+
+/// How many arguments a task parameter accepts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParamKind {
+    /// Exactly one argument
+    #[default]
+    Singular,
+    /// Zero or more arguments (`*param`)
+    Star,
+    /// One or more arguments (`+param`)
+    Plus,
+}
 
-    /// Returns the command name for this runner
-    pub fn command(&self) -> &str {
+/// Default value of a task parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ParamDefault {
+    /// A literal value (e.g. `env="staging"`)
+    Literal(String),
+    /// An expression the task runner evaluates when the argument is omitted
+    /// (e.g. a variable or `` `git describe` ``), as written
+    Expression(String),
+}
+
+impl ParamDefault {
+    /// Returns the value if the default is a literal
+    pub fn literal(&self) -> Option<&str> {
         match self {
-            TaskRunner::Just => "just",
-            TaskRunner::Make => "make",
+            ParamDefault::Literal(value) => Some(value),
+            ParamDefault::Expression(_) => None,
         }
     }
 }
 
+/// A parameter of a task (e.g. of a just recipe).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskParam {
+    /// Parameter name
+    pub name: String,
+    /// Default value, if the parameter has one
+    pub default: Option<ParamDefault>,
+    /// Number of arguments the parameter accepts
+    pub kind: ParamKind,
+    /// Whether the parameter is exported as an environment variable (`$param`)
+    pub exported: bool,
+}
+
+impl TaskParam {
+    /// Returns true if a value must be given for this parameter
+    pub fn is_required(&self) -> bool {
+        self.default.is_none() && self.kind != ParamKind::Star
+    }
+
+    /// Returns the expression the parameter defaults to, if its default isn't a literal
+    pub fn default_expression(&self) -> Option<&str> {
+        match &self.default {
+            Some(ParamDefault::Expression(expression)) => Some(expression),
+            _ => None,
+        }
+    }
+
+    /// Returns true if `value` (as entered in the arguments form) leaves the
+    /// parameter at its default: the literal default itself, or nothing for an
+    /// expression default or an optional variadic parameter
+    fn is_default_value(&self, value: &str) -> bool {
+        match &self.default {
+            Some(ParamDefault::Literal(default)) => default == value,
+            Some(ParamDefault::Expression(_)) | None => value.trim().is_empty(),
+        }
+    }
+
+    /// Returns the parameter as written in a recipe signature (e.g. `*$flags` or `env="staging"`)
+    pub fn signature(&self) -> String {
+        let mut signature = match self.kind {
+            ParamKind::Singular => String::new(),
+            ParamKind::Star => "*".to_string(),
+            ParamKind::Plus => "+".to_string(),
+        };
+        if self.exported {
+            signature.push('$');
+        }
+        signature.push_str(&self.name);
+        match &self.default {
+            Some(ParamDefault::Literal(default)) if default.contains('"') => {
+                signature.push_str(&format!("='{}'", default));
+            }
+            Some(ParamDefault::Literal(default)) => {
+                signature.push_str(&format!("=\"{}\"", default));
+            }
+            Some(ParamDefault::Expression(expression))
+                if expression.contains(char::is_whitespace) && !expression.starts_with('(') =>
+            {
+                signature.push_str(&format!("=({})", expression));
+            }
+            Some(ParamDefault::Expression(expression)) => {
+                signature.push_str(&format!("={}", expression));
+            }
+            None => {}
+        }
+        signature
+    }
+}
+
 /// Represents a task that can be executed.
 ///
 /// Supports both Just recipes and Make targets.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Task {
-    /// Stable identifier for the task
+    /// Position of the task in the discovered list. It changes when tasks are
+    /// reloaded, so state kept across reloads is keyed by `Task::key` instead.
     pub id: usize,
     /// User-facing name (recipe/target name)
     pub name: String,
@@ -116,6 +332,140 @@ pub struct Task {
     pub description: Option<String>,
     /// The task runner that executes this task
     pub runner: TaskRunner,
+    /// File the task is defined in (e.g. the justfile, or the package.json of a
+    /// workspace package), if known
+    pub source: Option<PathBuf>,
+    /// Parameters the task accepts, in order
+    pub params: Vec<TaskParam>,
+    /// Groups the task belongs to (e.g. from just's `[group]` attribute)
+    pub groups: Vec<String>,
+    /// Alternative names the task can be invoked by
+    pub aliases: Vec<String>,
+    /// Whether the task is private (hidden from the list unless hidden tasks are shown)
+    pub private: bool,
+    /// Other attributes as written in the task definition (e.g. `confirm`, `no-cd`)
+    pub attributes: Vec<String>,
+    /// Tasks that run before this one (with their arguments, if any)
+    pub dependencies: Vec<String>,
+    /// Lines of the task's body (the commands it runs), if known
+    pub body: Vec<String>,
+    /// Path of the module the task belongs to (e.g. `["docker", "db"]` for `docker::db::migrate`)
+    pub module_path: Vec<String>,
+    /// Whether the task builds a file rather than running a command (e.g. a non-phony Make target)
+    pub file_target: bool,
+    /// Directory the task runs in, if not the current directory (e.g. a workspace package)
+    pub cwd: Option<PathBuf>,
+    /// Environment variables set for the task (e.g. `options.env` of a VS Code task
+    /// or `env` of a custom task)
+    pub env: Vec<(String, String)>,
+    /// Arguments that run the task, for runners that don't take the task name
+    /// (e.g. `["run", "--bin", "server"]` for cargo or `["run", "build"]` for mise)
+    pub command: Vec<String>,
+}
+
+impl Task {
+    /// Returns the name without the module path (e.g. `migrate` for `docker::db::migrate`
+    /// or `docker:db:migrate`)
+    pub fn short_name(&self) -> &str {
+        let mut name = self.name.as_str();
+        for module in &self.module_path {
+            name = name
+                .strip_prefix(module.as_str())
+                .and_then(|rest| rest.strip_prefix("::").or_else(|| rest.strip_prefix(':')))
+                .unwrap_or(name);
+        }
+        name
+    }
+
+    /// Returns true if the task is only listed when hidden tasks are shown
+    pub fn is_hidden(&self) -> bool {
+        self.private || self.file_target
+    }
+
+    /// Returns the key identifying the task across reloads
+    pub fn key(&self) -> TaskKey {
+        TaskKey {
+            runner: self.runner.clone(),
+            source: self.source.clone(),
+            name: self.name.clone(),
+        }
+    }
+
+    /// Returns true if the task is the one identified by `key`
+    pub fn has_key(&self, key: &TaskKey) -> bool {
+        self.runner == key.runner && self.source == key.source && self.name == key.name
+    }
+}
+
+/// Identifies a task across reloads: unlike `Task::id`, it doesn't change when
+/// tasks are added above it, and a `just build` and a `make build` (or the
+/// `build` scripts of two workspace packages) have different keys.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TaskKey {
+    /// The task runner that executes the task
+    pub runner: TaskRunner,
+    /// File the task is defined in, if known
+    pub source: Option<PathBuf>,
+    /// Qualified name of the task (e.g. `docker::db::migrate`)
+    pub name: String,
+}
+
+/// Identifies a module of the task list. Modules are scoped to their runner, so
+/// a just `mod docker` and a Taskfile `docker:` namespace are listed (and
+/// expanded) separately.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ModuleKey {
+    /// The task runner whose tasks the module groups
+    pub runner: TaskRunner,
+    /// Path of the module from the top level
+    pub path: Vec<String>,
+}
+
+impl ModuleKey {
+    /// Returns the key of the module at `depth` levels into the task's module path
+    fn of(task: &Task, depth: usize) -> Self {
+        Self {
+            runner: task.runner.clone(),
+            path: task.module_path[..depth].to_vec(),
+        }
+    }
+
+    /// Returns the key of the module containing this one, if it isn't top level
+    fn parent(&self) -> Option<Self> {
+        (self.path.len() > 1).then(|| Self {
+            runner: self.runner.clone(),
+            path: self.path[..self.path.len() - 1].to_vec(),
+        })
+    }
+
+    /// Returns true if the task belongs to the module or one of its submodules
+    pub fn contains(&self, task: &Task) -> bool {
+        task.runner == self.runner && task.module_path.starts_with(&self.path)
+    }
+}
+
+/// A row of the task list: either a module heading or a task.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskRow {
+    /// A module (e.g. a just `mod`) grouping the rows below it
+    Module {
+        /// The module the row stands for
+        module: ModuleKey,
+        /// Whether the module's tasks are shown below it
+        expanded: bool,
+    },
+    /// A task, given by its index into `AppState::tasks`
+    Task(usize),
+}
+
+impl TaskRow {
+    /// Returns the nesting depth of the row, given the tasks it may refer to
+    pub fn depth(&self, tasks: &[Task]) -> usize {
+        match self {
+            TaskRow::Module { module, .. } => module.path.len() - 1,
+            TaskRow::Task(index) => tasks.get(*index).map_or(0, |t| t.module_path.len()),
+        }
+    }
 }
 
 /// Status of a task execution.
@@ -127,6 +477,10 @@ pub enum TaskStatus {
     Success(i32),
     /// Task failed with the given exit code
     Failed(i32),
+    /// Task was stopped by the user and exited
+    Cancelled,
+    /// Task was terminated by the given signal
+    Killed(i32),
 }
 
 impl TaskStatus {
@@ -136,34 +490,47 @@ impl TaskStatus {
             TaskStatus::Running => "Running".to_string(),
             TaskStatus::Success(code) => format!("Success (exit={})", code),
             TaskStatus::Failed(code) => format!("Failed (exit={})", code),
+            TaskStatus::Cancelled => "Cancelled".to_string(),
+            TaskStatus::Killed(signal) => format!("Killed (signal={})", signal),
         }
     }
 }
 
+/// Identifier of a single task run.
+pub type RunId = usize;
+
 /// Represents a task that is currently running or was recently run.
 #[derive(Debug)]
 pub struct RunningTask {
+    /// Identifier of this run
+    pub id: RunId,
     /// The task being executed
     pub task: Task,
     /// Current status of the execution
     pub status: TaskStatus,
-    /// Log lines (stdout and stderr combined)
-    pub log_lines: Vec<String>,
+    /// Output of this run (stdout and stderr combined)
+    pub log: LogBuffer,
 }
 
 impl RunningTask {
     /// Creates a new RunningTask with empty logs and Running status
-    pub fn new(task: Task) -> Self {
+    pub fn new(id: RunId, task: Task) -> Self {
         Self {
+            id,
             task,
             status: TaskStatus::Running,
-            log_lines: Vec::new(),
+            log: LogBuffer::new(MAX_LOG_LINES_PER_TASK),
         }
     }
 
     /// Appends a log line to the task's output
-    pub fn append_log(&mut self, line: String) {
-        self.log_lines.push(line);
+    pub fn append_log(&mut self, line: &str) {
+        self.log.push_line(line);
+    }
+
+    /// Appends raw terminal output to the task's output
+    pub fn append_output(&mut self, output: &str) {
+        self.log.write(output);
     }
 
     /// Updates the task's status
@@ -171,9 +538,14 @@ impl RunningTask {
         self.status = status;
     }
 
+    /// Returns true if this run has not finished yet
+    pub fn is_running(&self) -> bool {
+        self.status == TaskStatus::Running
+    }
+
     /// Clears all log lines
     pub fn clear_logs(&mut self) {
-        self.log_lines.clear();
+        self.log.clear();
     }
 }
 
@@ -188,15 +560,173 @@ pub enum DragScrollDirection {
 #[derive(Debug, Clone)]
 pub struct HistoryEntry {
     /// The task that was executed
-    pub task_name: String,
-    /// The runner used
-    pub runner: TaskRunner,
+    pub task: TaskKey,
     /// When the task was executed
     pub timestamp: SystemTime,
     /// Final status of the task
     pub status: TaskStatus,
 }
 
+/// Form for entering the arguments of a task with parameters before running it.
+#[derive(Debug, Clone)]
+pub struct ArgsForm {
+    /// The task to run once the form is submitted
+    pub task: Task,
+    /// Entered value of each parameter (pre-filled with literal defaults)
+    pub values: Vec<String>,
+    /// Index of the focused field
+    pub focused: usize,
+}
+
+impl ArgsForm {
+    /// Creates a form for the given task with every field set to its literal
+    /// default. Fields of parameters defaulting to an expression start empty
+    /// (the expression is shown as a placeholder), since the task runner
+    /// evaluates it only when the argument is omitted.
+    pub fn new(task: Task) -> Self {
+        let values = task
+            .params
+            .iter()
+            .map(|param| {
+                param
+                    .default
+                    .as_ref()
+                    .and_then(ParamDefault::literal)
+                    .unwrap_or_default()
+                    .to_string()
+            })
+            .collect();
+        Self {
+            task,
+            values,
+            focused: 0,
+        }
+    }
+
+    /// Moves focus to the next field (wrapping around)
+    pub fn focus_next(&mut self) {
+        if !self.values.is_empty() {
+            self.focused = (self.focused + 1) % self.values.len();
+        }
+    }
+
+    /// Moves focus to the previous field (wrapping around)
+    pub fn focus_previous(&mut self) {
+        if !self.values.is_empty() {
+            self.focused = (self.focused + self.values.len() - 1) % self.values.len();
+        }
+    }
+
+    /// Appends a character to the focused field
+    pub fn insert_char(&mut self, ch: char) {
+        if let Some(value) = self.values.get_mut(self.focused) {
+            value.push(ch);
+        }
+    }
+
+    /// Removes the last character of the focused field
+    pub fn delete_char(&mut self) {
+        if let Some(value) = self.values.get_mut(self.focused) {
+            value.pop();
+        }
+    }
+
+    /// Builds the command-line arguments from the entered values.
+    ///
+    /// Variadic parameters are split into separate arguments (quotes group words).
+    /// Trailing parameters left at their default are omitted so the task runner
+    /// evaluates the default itself. For runners taking `NAME=value` arguments,
+    /// every parameter left at its default is omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns a message naming the first required parameter without a value,
+    /// or the first parameter defaulting to an expression that was left empty
+    /// although a later argument is passed (its default can't be passed by
+    /// position, so it needs an explicit value).
+    pub fn args(&self) -> Result<Vec<String>, String> {
+        let params = &self.task.params;
+
+        if let Some(param) = params
+            .iter()
+            .zip(&self.values)
+            .find(|(param, value)| param.is_required() && value.trim().is_empty())
+            .map(|(param, _)| param)
+        {
+            return Err(format!("A value for '{}' is required", param.name));
+        }
+
+        if self.task.runner.passes_named_args() {
+            return Ok(params
+                .iter()
+                .zip(&self.values)
+                .filter(|(param, value)| !param.is_default_value(value))
+                .map(|(param, value)| format!("{}={}", param.name, value))
+                .collect());
+        }
+
+        // Everything up to the last parameter that differs from its default is passed
+        let Some(last) = params
+            .iter()
+            .zip(&self.values)
+            .rposition(|(param, value)| !param.is_default_value(value))
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut args = Vec::new();
+        for (param, value) in params.iter().zip(&self.values).take(last + 1) {
+            if let Some(expression) = param.default_expression() {
+                if value.trim().is_empty() {
+                    return Err(format!(
+                        "'{}' defaults to {}; enter a value to pass the arguments after it",
+                        param.name, expression
+                    ));
+                }
+            }
+            match param.kind {
+                ParamKind::Singular => args.push(value.clone()),
+                ParamKind::Star | ParamKind::Plus => args.extend(split_args(value)),
+            }
+        }
+        Ok(args)
+    }
+}
+
+/// Splits a string into whitespace-separated words, honoring single and double quotes.
+pub fn split_args(input: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote = None;
+
+    for ch in input.chars() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(ch);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if in_word {
+        words.push(word);
+    }
+    words
+}
+
 /// Main application state.
 ///
 /// This structure holds all state needed to render the UI and handle events.
@@ -205,14 +735,23 @@ pub struct HistoryEntry {
 pub struct AppState {
     /// List of all discovered tasks
     pub tasks: Vec<Task>,
-    /// Index of the currently selected task in the list
+    /// Index of the currently selected row in the task list (into `task_rows`)
     pub selected_index: usize,
-    /// The currently running or last run task (if any)
-    pub running_task: Option<RunningTask>,
-    /// Log history for each task (keyed by task ID)
-    pub task_logs: HashMap<usize, Vec<String>>,
-    /// Text selections for each task (keyed by task ID)
-    pub task_selections: HashMap<usize, LogSelection>,
+    /// Rows shown in the task list (modules and the tasks of expanded modules)
+    pub task_rows: Vec<TaskRow>,
+    /// Modules whose tasks are shown in the list
+    pub expanded_modules: HashSet<ModuleKey>,
+    /// Whether hidden tasks (e.g. private recipes) are shown in the list
+    pub show_hidden: bool,
+    /// Running and finished runs, shown as tabs over the log pane (one per task)
+    pub runs: Vec<RunningTask>,
+    /// Identifier assigned to the next started run
+    pub next_run_id: RunId,
+    /// Output of the last run of each task whose tab was closed (tasks with a
+    /// tab show the output of that run)
+    pub task_logs: HashMap<TaskKey, LogBuffer>,
+    /// Text selections for each task
+    pub task_selections: HashMap<TaskKey, LogSelection>,
     /// Temporary status message for errors, hints, etc.
     pub message: Option<String>,
     /// Flag to indicate the user wants to quit
@@ -245,15 +784,41 @@ pub struct AppState {
     pub selected_history_index: Option<usize>,
     /// Logs stored per history entry (keyed by history entry index in reversed order)
     pub history_logs: HashMap<usize, Vec<String>>,
+    /// Grace periods used when stopping a running task
+    pub stop_policy: StopPolicy,
+    /// Global execution mode (pseudo-terminal or piped)
+    pub exec_mode: ExecMode,
+    /// Per-task execution mode overrides
+    pub exec_mode_overrides: HashMap<TaskKey, ExecMode>,
+    /// Inner size of the log pane, used as the terminal size of PTY tasks
+    pub log_pane_size: PtySize,
+    /// Whether ANSI colors and styles in task output are rendered (otherwise stripped)
+    pub render_ansi: bool,
+    /// Arguments form shown before running a task with parameters
+    pub args_form: Option<ArgsForm>,
+    /// How discovery went for each task source
+    pub diagnostics: Vec<SourceDiagnostic>,
+    /// Whether tasks are being discovered in the background
+    pub discovering: bool,
+    /// Tasks added by the last reload, highlighted until `flash_until`
+    pub flashed_tasks: HashSet<TaskKey>,
+    /// When the highlight of added tasks ends
+    pub flash_until: Option<Instant>,
+    /// Whether the task source diagnostics popup is shown
+    pub show_diagnostics: bool,
 }
 
 impl AppState {
     /// Creates a new AppState with the given list of tasks
     pub fn new(tasks: Vec<Task>) -> Self {
-        Self {
+        let mut app = Self {
             tasks,
             selected_index: 0,
-            running_task: None,
+            task_rows: Vec::new(),
+            expanded_modules: HashSet::new(),
+            show_hidden: false,
+            runs: Vec::new(),
+            next_run_id: 0,
             task_logs: HashMap::new(),
             task_selections: HashMap::new(),
             message: None,
@@ -272,39 +837,179 @@ impl AppState {
             focused_pane: FocusedPane::Tasks,
             selected_history_index: None,
             history_logs: HashMap::new(),
+            stop_policy: StopPolicy::default(),
+            exec_mode: ExecMode::default(),
+            exec_mode_overrides: HashMap::new(),
+            log_pane_size: PtySize::default(),
+            render_ansi: true,
+            args_form: None,
+            diagnostics: Vec::new(),
+            discovering: false,
+            flashed_tasks: HashSet::new(),
+            flash_until: None,
+            show_diagnostics: false,
+        };
+        app.update_task_rows();
+        app
+    }
+
+    /// Returns the currently selected task, if any (None if a module is selected)
+    pub fn selected_task(&self) -> Option<&Task> {
+        match self.task_rows.get(self.selected_index) {
+            Some(TaskRow::Task(index)) => self.tasks.get(*index),
+            _ => None,
         }
     }
 
-    /// Creates an AppState with an error message (used when task discovery fails)
-    pub fn with_error(message: String) -> Self {
-        Self {
-            tasks: Vec::new(),
-            selected_index: 0,
-            running_task: None,
-            task_logs: HashMap::new(),
-            task_selections: HashMap::new(),
-            message: Some(message),
-            quitting: false,
-            task_scroll_offset: 0,
-            show_info: false,
-            info_scroll_offset: 0,
-            history_scroll_offset: 0,
-            log_scroll_offset: 0,
-            log_auto_scroll: true,
-            is_selecting: false,
-            drag_scroll_direction: None,
-            last_drag_position: None,
-            show_history: false,
-            task_history: Vec::new(),
-            focused_pane: FocusedPane::Tasks,
-            selected_history_index: None,
-            history_logs: HashMap::new(),
+    /// Recomputes the rows of the task list
+    fn update_task_rows(&mut self) {
+        let mut rows = Vec::new();
+        self.push_module_rows(None, &mut rows);
+        self.task_rows = rows;
+        self.selected_index = self
+            .selected_index
+            .min(self.task_rows.len().saturating_sub(1));
+    }
+
+    /// Appends the rows of `module` (or of the top level): its tasks and
+    /// submodules, in the order their first task appears
+    fn push_module_rows(&self, module: Option<&ModuleKey>, rows: &mut Vec<TaskRow>) {
+        let depth = module.map_or(0, |m| m.path.len());
+        let mut submodules: Vec<ModuleKey> = Vec::new();
+
+        for (index, task) in self.tasks.iter().enumerate() {
+            if !module.is_none_or(|m| m.contains(task)) || (task.is_hidden() && !self.show_hidden) {
+                continue;
+            }
+
+            if task.module_path.len() == depth {
+                rows.push(TaskRow::Task(index));
+                continue;
+            }
+
+            let submodule = ModuleKey::of(task, depth + 1);
+            if submodules.contains(&submodule) {
+                continue;
+            }
+            submodules.push(submodule.clone());
+
+            let expanded = self.expanded_modules.contains(&submodule);
+            rows.push(TaskRow::Module {
+                module: submodule.clone(),
+                expanded,
+            });
+            if expanded {
+                self.push_module_rows(Some(&submodule), rows);
+            }
         }
     }
 
-    /// Returns the currently selected task, if any
-    pub fn selected_task(&self) -> Option<&Task> {
-        self.tasks.get(self.selected_index)
+    /// Returns true if a task of the given row (or of the module's tasks) is running
+    pub fn is_row_running(&self, row: &TaskRow) -> bool {
+        match row {
+            TaskRow::Task(index) => self.tasks.get(*index).is_some_and(|t| self.is_running(t)),
+            TaskRow::Module { module, .. } => self
+                .runs
+                .iter()
+                .any(|run| run.is_running() && module.contains(&run.task)),
+        }
+    }
+
+    /// Moves the selection to the task with the given key, expanding its modules.
+    ///
+    /// Returns false if the task is not shown in the list (e.g. it is hidden)
+    /// or doesn't exist anymore.
+    fn select_task_by_key(&mut self, key: &TaskKey) -> bool {
+        let Some(task) = self.tasks.iter().find(|t| t.has_key(key)) else {
+            return false;
+        };
+
+        for depth in 1..=task.module_path.len() {
+            self.expanded_modules.insert(ModuleKey::of(task, depth));
+        }
+        self.update_task_rows();
+
+        let position = self.task_rows.iter().position(|row| match row {
+            TaskRow::Task(index) => self.tasks[*index].has_key(key),
+            TaskRow::Module { .. } => false,
+        });
+        if let Some(pos) = position {
+            self.selected_index = pos;
+        }
+        position.is_some()
+    }
+
+    /// Expands or collapses the module at the selected row.
+    ///
+    /// Collapsing from a task selects and collapses the module containing it.
+    pub fn set_selected_module_expanded(&mut self, expand: bool) {
+        let module = match self.task_rows.get(self.selected_index) {
+            Some(TaskRow::Module { module, expanded }) => {
+                if !expand && !*expanded {
+                    // Already collapsed: collapse the parent module instead
+                    module.parent()
+                } else {
+                    Some(module.clone())
+                }
+            }
+            Some(TaskRow::Task(index)) if !expand => {
+                let task = &self.tasks[*index];
+                (!task.module_path.is_empty()).then(|| ModuleKey::of(task, task.module_path.len()))
+            }
+            _ => return,
+        };
+        let Some(module) = module else {
+            return;
+        };
+
+        if expand {
+            self.expanded_modules.insert(module.clone());
+        } else {
+            self.expanded_modules.remove(&module);
+        }
+        self.update_task_rows();
+
+        // Keep the (possibly new) module row selected
+        if let Some(pos) = self
+            .task_rows
+            .iter()
+            .position(|row| matches!(row, TaskRow::Module { module: m, .. } if *m == module))
+        {
+            self.selected_index = pos;
+        }
+    }
+
+    /// Toggles the module at the selected row between expanded and collapsed.
+    ///
+    /// Returns false if the selected row is not a module.
+    pub fn toggle_selected_module(&mut self) -> bool {
+        match self.task_rows.get(self.selected_index) {
+            Some(TaskRow::Module { expanded, .. }) => {
+                let expand = !*expanded;
+                self.set_selected_module_expanded(expand);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Toggles whether hidden tasks are shown in the list, keeping the selection
+    pub fn toggle_hidden_tasks(&mut self) {
+        let selected = self.selected_task().map(Task::key);
+
+        self.show_hidden = !self.show_hidden;
+        self.update_task_rows();
+
+        if !selected.is_some_and(|key| self.select_task_by_key(&key)) {
+            self.selected_index = 0;
+        }
+
+        let hidden = self.tasks.iter().filter(|t| t.is_hidden()).count();
+        self.message = Some(if self.show_hidden {
+            format!("Showing {} hidden tasks", hidden)
+        } else {
+            format!("Hiding {} hidden tasks", hidden)
+        });
     }
 
     /// Moves selection up by one, if not already at the top
@@ -316,109 +1021,287 @@ impl AppState {
 
     /// Moves selection down by one, if not already at the bottom
     pub fn move_selection_down(&mut self) {
-        if self.selected_index < self.tasks.len().saturating_sub(1) {
+        if self.selected_index < self.task_rows.len().saturating_sub(1) {
             self.selected_index += 1;
         }
     }
 
-    /// Returns true if a task is currently running
-    pub fn is_task_running(&self) -> bool {
-        matches!(
-            self.running_task.as_ref().map(|t| &t.status),
-            Some(TaskStatus::Running)
-        )
+    /// Returns the number of runs that have not finished yet
+    pub fn running_count(&self) -> usize {
+        self.runs.iter().filter(|run| run.is_running()).count()
     }
 
-    /// Starts running a task
-    pub fn start_task(&mut self, task: Task) {
-        self.running_task = Some(RunningTask::new(task));
-        self.message = None;
+    /// Returns the run (running or finished) belonging to the given task, if any
+    pub fn run_for_task(&self, key: &TaskKey) -> Option<&RunningTask> {
+        self.runs.iter().find(|run| run.task.has_key(key))
     }
 
-    /// Appends a log line to the currently running task
-    pub fn append_log(&mut self, line: String) {
-        if let Some(ref mut running) = self.running_task {
-            // Append to the task-specific log history
-            let logs = self.task_logs.entry(running.task.id).or_default();
-            logs.push(line.clone());
+    /// Returns true if the given task currently has a run in progress
+    pub fn is_running(&self, task: &Task) -> bool {
+        self.run_for_task(&task.key())
+            .map(RunningTask::is_running)
+            .unwrap_or(false)
+    }
 
-            // Enforce per-task log limit to prevent unbounded memory growth
-            if logs.len() > MAX_LOG_LINES_PER_TASK {
-                let excess = logs.len() - MAX_LOG_LINES_PER_TASK;
-                logs.drain(0..excess);
-            }
+    /// Returns the run shown in the log pane (the selected task's run), if any
+    pub fn active_run(&self) -> Option<&RunningTask> {
+        self.run_for_task(&self.selected_task()?.key())
+    }
+
+    /// Returns the run summarized in the status bar: the active run if there is
+    /// one, otherwise the most recently started run
+    pub fn status_run(&self) -> Option<&RunningTask> {
+        self.active_run()
+            .or_else(|| self.runs.iter().max_by_key(|run| run.id))
+    }
 
-            // Also append to the running task for compatibility
-            running.append_log(line);
+    /// Returns the execution mode used for the given task (its override, if any,
+    /// otherwise the global mode)
+    pub fn exec_mode_for(&self, task: &Task) -> ExecMode {
+        self.exec_mode_overrides
+            .get(&task.key())
+            .copied()
+            .unwrap_or(self.exec_mode)
+    }
+
+    /// Returns the options used to start the given task
+    pub fn run_options_for(&self, task: &Task) -> RunOptions {
+        RunOptions {
+            exec_mode: self.exec_mode_for(task),
+            stop_policy: self.stop_policy,
+            pty_size: self.log_pane_size,
         }
     }
 
-    /// Updates the status of the currently running task
-    pub fn update_task_status(&mut self, status: TaskStatus) {
-        // First, extract the data we need for history (if applicable)
-        let history_data = if !matches!(status, TaskStatus::Running) {
-            self.running_task
-                .as_ref()
-                .map(|running| (running.task.name.clone(), running.task.runner.clone()))
-        } else {
-            None
+    /// Toggles the global execution mode
+    pub fn toggle_exec_mode(&mut self) {
+        self.exec_mode = self.exec_mode.toggled();
+        self.message = Some(format!("Execution mode: {}", self.exec_mode.label()));
+    }
+
+    /// Toggles the execution mode of the selected task.
+    ///
+    /// An override that ends up matching the global mode is dropped, so the task
+    /// follows later global changes again.
+    pub fn toggle_selected_task_exec_mode(&mut self) {
+        let Some(task) = self.selected_task() else {
+            return;
         };
 
-        // Update the running task status
-        if let Some(ref mut running) = self.running_task {
-            running.set_status(status.clone());
-            self.message = Some(status.display_string());
+        let key = task.key();
+        let name = task.name.clone();
+        let mode = self.exec_mode_for(task).toggled();
+        if mode == self.exec_mode {
+            self.exec_mode_overrides.remove(&key);
+        } else {
+            self.exec_mode_overrides.insert(key, mode);
+        }
+
+        self.message = Some(format!("Execution mode for {}: {}", name, mode.label()));
+    }
+
+    /// Starts running a task and returns the identifier of the new run.
+    ///
+    /// A finished run of the same task is replaced so each task has at most one tab.
+    pub fn start_task(&mut self, task: Task) -> RunId {
+        let id = self.next_run_id;
+        self.next_run_id += 1;
+
+        let key = task.key();
+        self.task_logs.remove(&key);
+        if let Some(pos) = self.runs.iter().position(|run| run.task.has_key(&key)) {
+            self.runs[pos] = RunningTask::new(id, task);
+        } else {
+            self.runs.push(RunningTask::new(id, task));
+        }
+
+        self.message = None;
+        id
+    }
+
+    /// Appends a log line to the given run
+    pub fn append_log(&mut self, run_id: RunId, line: String) {
+        if let Some(running) = self.runs.iter_mut().find(|run| run.id == run_id) {
+            running.append_log(&line);
         }
+    }
+
+    /// Appends raw terminal output (which may redraw earlier lines) to the given run
+    pub fn append_output(&mut self, run_id: RunId, output: &str) {
+        if let Some(running) = self.runs.iter_mut().find(|run| run.id == run_id) {
+            running.append_output(output);
+        }
+    }
+
+    /// Appends a line or terminal output received from the given run's process
+    pub fn append_log_event(&mut self, run_id: RunId, event: LogEvent) {
+        match event {
+            LogEvent::Line(line) => self.append_log(run_id, line),
+            LogEvent::Output(output) => self.append_output(run_id, &output),
+        }
+    }
+
+    /// Updates the status of the given run
+    pub fn update_task_status(&mut self, run_id: RunId, status: TaskStatus) {
+        let Some(running) = self.runs.iter_mut().find(|run| run.id == run_id) else {
+            return;
+        };
+
+        running.set_status(status.clone());
+        self.message = Some(format!(
+            "{} {}: {}",
+            running.task.runner.prefix(),
+            running.task.name,
+            status.display_string()
+        ));
 
         // Add to history when task completes (success or failure)
-        if let Some((task_name, runner)) = history_data {
-            self.add_to_history(task_name, runner, status);
+        if !matches!(status, TaskStatus::Running) {
+            let task = running.task.key();
+            let logs = running.log.lines().to_vec();
+
+            self.add_to_history(task, status);
 
             // Store logs for this history entry
-            if let Some(running) = &self.running_task {
-                let logs = running.log_lines.clone();
-                self.store_history_logs(logs);
-            }
+            self.store_history_logs(logs);
         }
     }
 
     /// Clears all task logs
     pub fn clear_logs(&mut self) {
         self.task_logs.clear();
-        if let Some(ref mut running) = self.running_task {
+        for running in &mut self.runs {
             running.clear_logs();
         }
     }
 
-    /// Gets the logs for the currently selected task
-    pub fn selected_task_logs(&self) -> Option<&Vec<String>> {
-        if let Some(task) = self.selected_task() {
-            self.task_logs.get(&task.id)
-        } else {
-            None
+    /// Selects the task of the next run tab (wrapping around)
+    pub fn select_next_run(&mut self) {
+        self.select_run_relative(1);
+    }
+
+    /// Selects the task of the previous run tab (wrapping around)
+    pub fn select_previous_run(&mut self) {
+        self.select_run_relative(self.runs.len().saturating_sub(1));
+    }
+
+    /// Moves the active tab by `step` positions and selects the run's task
+    fn select_run_relative(&mut self, step: usize) {
+        if self.runs.is_empty() {
+            return;
+        }
+
+        let next = match self.active_run() {
+            Some(active) => {
+                let pos = self
+                    .runs
+                    .iter()
+                    .position(|run| run.id == active.id)
+                    .unwrap_or(0);
+                (pos + step) % self.runs.len()
+            }
+            None => 0,
+        };
+
+        self.select_run(next);
+    }
+
+    /// Selects the task belonging to the run tab at the given position
+    pub fn select_run(&mut self, tab_index: usize) {
+        let Some(key) = self.runs.get(tab_index).map(|run| run.task.key()) else {
+            return;
+        };
+
+        if self.select_task_by_key(&key) {
+            self.focus_tasks();
+            self.scroll_logs_to_bottom();
         }
     }
 
-    /// Reloads tasks from the discovery function
+    /// Closes the active run tab if its run has finished. The task keeps
+    /// showing the run's output.
+    ///
+    /// Returns false if there is no active tab or its run is still in progress.
+    pub fn close_active_run(&mut self) -> bool {
+        let Some(pos) = self
+            .active_run()
+            .filter(|run| !run.is_running())
+            .and_then(|active| self.runs.iter().position(|run| run.id == active.id))
+        else {
+            return false;
+        };
+
+        let run = self.runs.remove(pos);
+        self.task_logs.insert(run.task.key(), run.log);
+        true
+    }
+
+    /// Gets the logs for the currently selected task: those of its run, or of
+    /// its last run if the tab was closed
+    pub fn selected_task_logs(&self) -> Option<&[String]> {
+        let key = self.selected_task()?.key();
+        match self.run_for_task(&key) {
+            Some(run) => Some(run.log.lines()),
+            None => self.task_logs.get(&key).map(LogBuffer::lines),
+        }
+    }
+
+    /// Reloads tasks from the discovery function.
+    ///
+    /// State kept per task (logs, text selections, execution mode overrides and
+    /// runs) is keyed by `Task::key`, so it carries over to the tasks that
+    /// still exist and the selection stays on the same task. Added tasks are
+    /// highlighted for a moment, and the message names the added and removed
+    /// tasks.
     pub fn reload_tasks(&mut self, new_tasks: Vec<Task>) {
-        // Try to preserve the selection by task name
-        let selected_name = self.selected_task().map(|t| t.name.clone());
+        let old_keys: HashSet<TaskKey> = self.tasks.iter().map(Task::key).collect();
+        let new_keys: HashSet<TaskKey> = new_tasks.iter().map(Task::key).collect();
+        let added: Vec<&Task> = new_tasks
+            .iter()
+            .filter(|task| !old_keys.contains(&task.key()))
+            .collect();
+        let removed: Vec<&str> = self
+            .tasks
+            .iter()
+            .filter(|task| !new_keys.contains(&task.key()))
+            .map(|task| task.name.as_str())
+            .collect();
+        let message = reload_message(
+            &added.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(),
+            &removed,
+        );
+
+        if !self.tasks.is_empty() && !added.is_empty() {
+            self.flashed_tasks = added.iter().map(|t| t.key()).collect();
+            self.flash_until = Some(Instant::now() + FLASH_DURATION);
+        }
+
+        // Runs of removed tasks keep their output
+        let kept: HashSet<TaskKey> = new_keys
+            .into_iter()
+            .chain(self.runs.iter().map(|run| run.task.key()))
+            .collect();
+        self.task_logs.retain(|key, _| kept.contains(key));
+        self.task_selections.retain(|key, _| kept.contains(key));
+        self.exec_mode_overrides.retain(|key, _| kept.contains(key));
+
+        let selected = self.selected_task().map(Task::key);
 
         self.tasks = new_tasks;
+        self.update_task_rows();
 
-        // Try to find the previously selected task by name
-        if let Some(name) = selected_name {
-            if let Some(pos) = self.tasks.iter().position(|t| t.name == name) {
-                self.selected_index = pos;
-            } else {
-                // If not found, reset to first item
-                self.selected_index = 0;
-            }
-        } else {
+        if !selected.is_some_and(|key| self.select_task_by_key(&key)) {
+            // If not found, reset to first item
             self.selected_index = 0;
         }
 
-        self.message = Some("Tasks reloaded".to_string());
+        self.message = Some(message);
+    }
+
+    /// Returns true if the task was added by the last reload and is still highlighted
+    pub fn is_flashing(&self, task: &Task) -> bool {
+        self.flashed_tasks.contains(&task.key())
+            && self.flash_until.is_some_and(|until| Instant::now() < until)
     }
 
     /// Adjusts scroll offset to ensure the selected item is visible
@@ -456,6 +1339,96 @@ impl AppState {
         self.show_info = !self.show_info;
     }
 
+    /// Marks tasks as being discovered in the background
+    pub fn start_discovery(&mut self) {
+        self.discovering = true;
+        self.diagnostics.clear();
+        self.message = Some("Discovering tasks...".to_string());
+    }
+
+    /// Updates the diagnostic of a source while tasks are being discovered
+    pub fn update_diagnostic(&mut self, diagnostic: SourceDiagnostic) {
+        match self
+            .diagnostics
+            .iter_mut()
+            .find(|d| d.source == diagnostic.source)
+        {
+            Some(existing) => *existing = diagnostic,
+            None => self.diagnostics.push(diagnostic),
+        }
+
+        let pending = self
+            .diagnostics
+            .iter()
+            .filter(|d| d.outcome == SourceOutcome::Discovering)
+            .count();
+        if pending > 0 {
+            self.message = Some(format!("Discovering tasks... ({} source(s) left)", pending));
+        }
+    }
+
+    /// Shows the tasks found by a background discovery, or its error
+    pub fn finish_discovery(&mut self, result: Result<Discovery>) {
+        self.discovering = false;
+        match result {
+            Ok(discovery) => {
+                let reloaded = !self.tasks.is_empty();
+                self.reload_tasks(discovery.tasks);
+                if !reloaded {
+                    self.message = None;
+                }
+                self.set_diagnostics(discovery.diagnostics);
+            }
+            Err(e) if self.tasks.is_empty() => {
+                self.message = Some(e.to_string());
+            }
+            Err(e) => {
+                self.message = Some(format!("Failed to reload tasks: {}", e));
+            }
+        }
+    }
+
+    /// Stores the diagnostics of the last discovery, pointing to them if a
+    /// source failed
+    pub fn set_diagnostics(&mut self, diagnostics: Vec<SourceDiagnostic>) {
+        let failed = diagnostics
+            .iter()
+            .filter(|d| matches!(d.outcome, SourceOutcome::Failed(_)))
+            .count();
+        if failed > 0 {
+            self.message = Some(format!(
+                "{} task source(s) failed; press D for details",
+                failed
+            ));
+        }
+        self.diagnostics = diagnostics;
+    }
+
+    /// Toggles the task source diagnostics popup
+    pub fn toggle_diagnostics(&mut self) {
+        self.show_diagnostics = !self.show_diagnostics;
+    }
+
+    /// Opens the arguments form for a task with parameters
+    pub fn open_args_form(&mut self, task: Task) {
+        self.args_form = Some(ArgsForm::new(task));
+    }
+
+    /// Closes the arguments form without running the task
+    pub fn close_args_form(&mut self) {
+        self.args_form = None;
+    }
+
+    /// Toggles between rendering and stripping ANSI styles in the log pane
+    pub fn toggle_render_ansi(&mut self) {
+        self.render_ansi = !self.render_ansi;
+        self.message = Some(if self.render_ansi {
+            "Rendering output colors".to_string()
+        } else {
+            "Stripping output colors".to_string()
+        });
+    }
+
     /// Toggles the history container display
     pub fn toggle_history(&mut self) {
         self.show_history = !self.show_history;
@@ -484,6 +1457,23 @@ impl AppState {
         }
     }
 
+    /// Switches focus to the log pane, typing keys into the selected task's terminal
+    pub fn focus_logs(&mut self) {
+        if let Some(task) = self.selected_task() {
+            self.message = Some(format!(
+                "Typing into {} {} (Esc to stop)",
+                task.runner.prefix(),
+                task.name
+            ));
+            self.focused_pane = FocusedPane::Logs;
+        }
+    }
+
+    /// Returns true if the log pane currently has focus
+    pub fn is_logs_focused(&self) -> bool {
+        self.focused_pane == FocusedPane::Logs
+    }
+
     /// Returns true if History pane currently has focus
     pub fn is_history_focused(&self) -> bool {
         self.focused_pane == FocusedPane::History
@@ -516,16 +1506,15 @@ impl AppState {
     }
 
     /// Gets the logs for the currently selected history entry
-    pub fn get_history_logs(&self) -> Option<&Vec<String>> {
+    pub fn get_history_logs(&self) -> Option<&[String]> {
         let idx = self.selected_history_index?;
-        self.history_logs.get(&idx)
+        self.history_logs.get(&idx).map(Vec::as_slice)
     }
 
     /// Adds a task execution to history, removing oldest entries if limit exceeded
-    pub fn add_to_history(&mut self, task_name: String, runner: TaskRunner, status: TaskStatus) {
+    pub fn add_to_history(&mut self, task: TaskKey, status: TaskStatus) {
         let entry = HistoryEntry {
-            task_name,
-            runner,
+            task,
             timestamp: SystemTime::now(),
             status,
         };
@@ -586,23 +1575,24 @@ impl AppState {
     }
 
     /// Starts running a task and resets log scrolling for new output
-    pub fn start_task_with_scroll_reset(&mut self, task: Task) {
-        self.start_task(task);
+    pub fn start_task_with_scroll_reset(&mut self, task: Task) -> RunId {
+        let run_id = self.start_task(task);
         self.scroll_logs_to_bottom();
+        run_id
     }
 
     /// Gets the selection for the currently selected task
     pub fn current_task_selection(&self) -> Option<&LogSelection> {
         let task = self.selected_task()?;
-        self.task_selections.get(&task.id)
+        self.task_selections.get(&task.key())
     }
 
     /// Starts a text selection at the given position for the current task
     pub fn start_selection(&mut self, pos: LogPosition) {
         if let Some(task) = self.selected_task() {
-            let task_id = task.id;
+            let key = task.key();
             self.task_selections
-                .insert(task_id, LogSelection::new(pos, pos));
+                .insert(key, LogSelection::new(pos, pos));
             self.is_selecting = true;
         }
     }
@@ -610,8 +1600,8 @@ impl AppState {
     /// Updates the selection end position (during drag) for the current task
     pub fn update_selection(&mut self, pos: LogPosition) {
         if let Some(task) = self.selected_task() {
-            let task_id = task.id;
-            if let Some(selection) = self.task_selections.get_mut(&task_id) {
+            let key = task.key();
+            if let Some(selection) = self.task_selections.get_mut(&key) {
                 selection.end = pos;
             }
         }
@@ -626,8 +1616,8 @@ impl AppState {
 
     /// Clears the selection for the current task
     pub fn clear_selection(&mut self) {
-        if let Some(task_id) = self.selected_task().map(|t| t.id) {
-            self.task_selections.remove(&task_id);
+        if let Some(key) = self.selected_task().map(Task::key) {
+            self.task_selections.remove(&key);
         }
         self.is_selecting = false;
     }
@@ -665,7 +1655,7 @@ impl AppState {
     /// Gets the selected text from logs for the current task or history entry
     pub fn get_selected_text(&self) -> Option<String> {
         let task = self.selected_task()?;
-        let selection = self.task_selections.get(&task.id)?;
+        let selection = self.task_selections.get(&task.key())?;
         // Get logs based on focus: history logs if history focused, otherwise current task logs
         let log_lines = if self.is_history_focused() {
             self.get_history_logs()?
@@ -679,6 +1669,13 @@ impl AppState {
             return None;
         }
 
+        // Select on the text as displayed, without escape sequences
+        let log_lines: Vec<String> = log_lines
+            .iter()
+            .take(end.line + 1)
+            .map(|line| ansi::plain_text(line))
+            .collect();
+
         let mut result = String::new();
 
         if start.line == end.line {
@@ -718,6 +1715,32 @@ impl AppState {
     }
 }
 
+/// Describes the tasks added and removed by a reload (e.g.
+/// `Tasks reloaded: 1 added (lint), 2 removed (docs, serve)`).
+fn reload_message(added: &[&str], removed: &[&str]) -> String {
+    let names = |names: &[&str]| {
+        let mut listed = names[..names.len().min(MAX_CHANGED_NAMES)].join(", ");
+        if names.len() > MAX_CHANGED_NAMES {
+            listed.push_str(", ...");
+        }
+        listed
+    };
+
+    let mut changes = Vec::new();
+    if !added.is_empty() {
+        changes.push(format!("{} added ({})", added.len(), names(added)));
+    }
+    if !removed.is_empty() {
+        changes.push(format!("{} removed ({})", removed.len(), names(removed)));
+    }
+
+    if changes.is_empty() {
+        "Tasks reloaded".to_string()
+    } else {
+        format!("Tasks reloaded: {}", changes.join(", "))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -730,18 +1753,21 @@ mod tests {
                 name: "task1".to_string(),
                 description: None,
                 runner: TaskRunner::Just,
+                ..Default::default()
             },
             Task {
                 id: 1,
                 name: "task2".to_string(),
                 description: None,
                 runner: TaskRunner::Just,
+                ..Default::default()
             },
             Task {
                 id: 2,
                 name: "task3".to_string(),
                 description: None,
                 runner: TaskRunner::Just,
+                ..Default::default()
             },
         ];
         let mut app = AppState::new(tasks);
@@ -763,18 +1789,21 @@ mod tests {
                 name: "task1".to_string(),
                 description: None,
                 runner: TaskRunner::Just,
+                ..Default::default()
             },
             Task {
                 id: 1,
                 name: "task2".to_string(),
                 description: None,
                 runner: TaskRunner::Just,
+                ..Default::default()
             },
             Task {
                 id: 2,
                 name: "task3".to_string(),
                 description: None,
                 runner: TaskRunner::Just,
+                ..Default::default()
             },
         ];
         let mut app = AppState::new(tasks);
@@ -791,20 +1820,651 @@ mod tests {
     }
 
     #[test]
-    fn test_is_task_running() {
+    fn test_running_count() {
         let mut app = AppState::new(vec![]);
-        assert!(!app.is_task_running());
+        assert_eq!(app.running_count(), 0);
 
         let task = Task {
             id: 0,
             name: "test".to_string(),
             description: None,
             runner: TaskRunner::Just,
+            ..Default::default()
+        };
+        let run_id = app.start_task(task);
+        assert_eq!(app.running_count(), 1);
+
+        app.update_task_status(run_id, TaskStatus::Success(0));
+        assert_eq!(app.running_count(), 0);
+    }
+
+    #[test]
+    fn test_exec_mode_overrides() {
+        let tasks = vec![
+            Task {
+                id: 0,
+                name: "build".to_string(),
+                description: None,
+                runner: TaskRunner::Just,
+                ..Default::default()
+            },
+            Task {
+                id: 1,
+                name: "test".to_string(),
+                description: None,
+                runner: TaskRunner::Just,
+                ..Default::default()
+            },
+        ];
+        let mut app = AppState::new(tasks.clone());
+        app.exec_mode = ExecMode::Pty;
+
+        app.toggle_selected_task_exec_mode();
+        assert_eq!(app.exec_mode_for(&tasks[0]), ExecMode::Piped);
+        assert_eq!(app.exec_mode_for(&tasks[1]), ExecMode::Pty);
+
+        // The override wins over later global changes
+        app.toggle_exec_mode();
+        assert_eq!(app.exec_mode_for(&tasks[0]), ExecMode::Piped);
+        assert_eq!(app.exec_mode_for(&tasks[1]), ExecMode::Piped);
+
+        // Toggling back to the global mode drops the override
+        app.toggle_selected_task_exec_mode();
+        assert_eq!(app.exec_mode_for(&tasks[0]), ExecMode::Pty);
+        app.toggle_selected_task_exec_mode();
+        assert!(app.exec_mode_overrides.is_empty());
+    }
+
+    #[test]
+    fn test_selected_text_strips_ansi() {
+        let task = Task {
+            id: 0,
+            name: "build".to_string(),
+            description: None,
+            runner: TaskRunner::Just,
+            ..Default::default()
+        };
+        let mut app = AppState::new(vec![task.clone()]);
+        let run = app.start_task(task);
+        app.append_log(run, "\x1b[1;32mCompiling\x1b[0m taskpad".to_string());
+        app.append_log(run, "\x1b[33mwarning\x1b[0m: unused".to_string());
+
+        // Columns refer to the text as displayed, not the raw escape sequences
+        app.task_selections.insert(
+            app.tasks[0].key(),
+            LogSelection::new(LogPosition::new(0, 10), LogPosition::new(1, 7)),
+        );
+        assert_eq!(
+            app.get_selected_text(),
+            Some("taskpad\nwarning".to_string())
+        );
+    }
+
+    fn deploy_task() -> Task {
+        Task {
+            name: "deploy".to_string(),
+            params: vec![
+                TaskParam {
+                    name: "target".to_string(),
+                    ..Default::default()
+                },
+                TaskParam {
+                    name: "env".to_string(),
+                    default: Some(ParamDefault::Literal("staging".to_string())),
+                    ..Default::default()
+                },
+                TaskParam {
+                    name: "flags".to_string(),
+                    kind: ParamKind::Star,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_args_form_prefills_defaults() {
+        let form = ArgsForm::new(deploy_task());
+        assert_eq!(form.values, vec!["", "staging", ""]);
+
+        // The required parameter has no value yet
+        assert_eq!(
+            form.args(),
+            Err("A value for 'target' is required".to_string())
+        );
+    }
+
+    #[test]
+    fn test_args_form_builds_separate_arguments() {
+        let mut form = ArgsForm::new(deploy_task());
+        for ch in "web".chars() {
+            form.insert_char(ch);
+        }
+
+        // Trailing defaults are left to the task runner
+        assert_eq!(form.args(), Ok(vec!["web".to_string()]));
+
+        // Variadic values are split into separate arguments
+        form.focus_previous();
+        for ch in "--fast \"a b\"".chars() {
+            form.insert_char(ch);
+        }
+        assert_eq!(
+            form.args(),
+            Ok(vec![
+                "web".to_string(),
+                "staging".to_string(),
+                "--fast".to_string(),
+                "a b".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_args_form_builds_named_arguments() {
+        let mut task = deploy_task();
+        task.runner = TaskRunner::GoTask;
+        task.params.push(TaskParam {
+            name: "REPLICAS".to_string(),
+            default: Some(ParamDefault::Literal("3".to_string())),
+            ..Default::default()
+        });
+        let mut form = ArgsForm::new(task);
+        for ch in "web".chars() {
+            form.insert_char(ch);
+        }
+
+        // Every parameter left at its default is omitted, not just trailing ones
+        form.focus_previous();
+        form.insert_char('5');
+        assert_eq!(
+            form.args(),
+            Ok(vec!["target=web".to_string(), "REPLICAS=35".to_string()])
+        );
+    }
+
+    fn build_task() -> Task {
+        let param = |name: &str, default: ParamDefault| TaskParam {
+            name: name.to_string(),
+            default: Some(default),
+            ..Default::default()
+        };
+        Task {
+            name: "build".to_string(),
+            params: vec![
+                param("target", ParamDefault::Literal("debug".to_string())),
+                param("mode", ParamDefault::Expression("version".to_string())),
+                param("out", ParamDefault::Literal("dist".to_string())),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_args_form_leaves_expression_defaults_to_the_runner() {
+        let mut form = ArgsForm::new(build_task());
+
+        // Expression defaults are not pre-filled
+        assert_eq!(form.values, vec!["debug", "", "dist"]);
+        assert_eq!(form.args(), Ok(Vec::new()));
+
+        // A later argument can't be passed without a value for the expression
+        form.focused = 2;
+        form.insert_char('2');
+        assert_eq!(
+            form.args(),
+            Err(
+                "'mode' defaults to version; enter a value to pass the arguments after it"
+                    .to_string()
+            )
+        );
+
+        form.focused = 1;
+        form.insert_char('x');
+        assert_eq!(
+            form.args(),
+            Ok(vec![
+                "debug".to_string(),
+                "x".to_string(),
+                "dist2".to_string()
+            ])
+        );
+
+        // Named arguments simply omit it
+        let mut task = build_task();
+        task.runner = TaskRunner::GoTask;
+        let mut form = ArgsForm::new(task);
+        form.focused = 2;
+        form.insert_char('2');
+        assert_eq!(form.args(), Ok(vec!["out=dist2".to_string()]));
+    }
+
+    #[test]
+    fn test_param_signature() {
+        let params = build_task().params;
+        assert_eq!(params[0].signature(), "target=\"debug\"");
+        assert_eq!(params[1].signature(), "mode=version");
+
+        let concatenation = TaskParam {
+            name: "x".to_string(),
+            default: Some(ParamDefault::Expression("\"a\" + \"b\"".to_string())),
+            exported: true,
+            ..Default::default()
+        };
+        assert_eq!(concatenation.signature(), "$x=(\"a\" + \"b\")");
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            split_args("  a  'b c' \"\" d\"e f\""),
+            vec!["a", "b c", "", "de f"]
+        );
+        assert!(split_args("   ").is_empty());
+    }
+
+    fn module_tasks() -> Vec<Task> {
+        let task = |id: usize, name: &str, module_path: &[&str]| Task {
+            id,
+            name: name.to_string(),
+            module_path: module_path.iter().map(|m| m.to_string()).collect(),
+            ..Default::default()
+        };
+        vec![
+            task(0, "build", &[]),
+            task(1, "docker::up", &["docker"]),
+            task(2, "docker::db::migrate", &["docker", "db"]),
+            task(3, "lint", &[]),
+            task(4, "docker::down", &["docker"]),
+        ]
+    }
+
+    /// Returns the key of a module of the just tasks from `module_tasks`
+    fn module(path: &[&str]) -> ModuleKey {
+        ModuleKey {
+            runner: TaskRunner::Just,
+            path: path.iter().map(|m| m.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_module_rows() {
+        let mut app = AppState::new(module_tasks());
+
+        // Modules start collapsed, in the position of their first task
+        assert_eq!(
+            app.task_rows,
+            vec![
+                TaskRow::Task(0),
+                TaskRow::Module {
+                    module: module(&["docker"]),
+                    expanded: false,
+                },
+                TaskRow::Task(3),
+            ]
+        );
+
+        app.selected_index = 1;
+        assert!(app.selected_task().is_none());
+        assert!(app.toggle_selected_module());
+        assert_eq!(
+            app.task_rows,
+            vec![
+                TaskRow::Task(0),
+                TaskRow::Module {
+                    module: module(&["docker"]),
+                    expanded: true,
+                },
+                TaskRow::Task(1),
+                TaskRow::Module {
+                    module: module(&["docker", "db"]),
+                    expanded: false,
+                },
+                TaskRow::Task(4),
+                TaskRow::Task(3),
+            ]
+        );
+
+        // Collapsing from a task collapses (and selects) its module
+        app.selected_index = 4;
+        assert_eq!(app.selected_task().unwrap().short_name(), "down");
+        app.set_selected_module_expanded(false);
+        assert_eq!(app.selected_index, 1);
+        assert_eq!(app.task_rows.len(), 3);
+    }
+
+    #[test]
+    fn test_modules_of_different_runners_are_separate() {
+        let mut tasks = module_tasks();
+        tasks.push(Task {
+            id: 5,
+            name: "docker:up".to_string(),
+            runner: TaskRunner::GoTask,
+            module_path: vec!["docker".to_string()],
+            ..Default::default()
+        });
+        let go_docker = ModuleKey {
+            runner: TaskRunner::GoTask,
+            path: vec!["docker".to_string()],
         };
-        app.start_task(task);
-        assert!(app.is_task_running());
+        let mut app = AppState::new(tasks.clone());
+
+        assert_eq!(
+            app.task_rows,
+            vec![
+                TaskRow::Task(0),
+                TaskRow::Module {
+                    module: module(&["docker"]),
+                    expanded: false,
+                },
+                TaskRow::Task(3),
+                TaskRow::Module {
+                    module: go_docker.clone(),
+                    expanded: false,
+                },
+            ]
+        );
+
+        // Expanding one runner's module leaves the other collapsed
+        app.selected_index = 3;
+        assert!(app.toggle_selected_module());
+        assert_eq!(app.selected_index, 3);
+        assert_eq!(app.task_rows.len(), 5);
+        assert_eq!(app.task_rows[4], TaskRow::Task(5));
+        assert!(app.expanded_modules.contains(&go_docker));
+        assert!(!app.expanded_modules.contains(&module(&["docker"])));
+
+        // A running task only marks its own runner's module
+        app.start_task(tasks[5].clone());
+        assert!(app.is_row_running(&app.task_rows[3].clone()));
+        assert!(!app.is_row_running(&app.task_rows[1].clone()));
+
+        // Collapsing from a task collapses its own runner's module
+        app.selected_index = 4;
+        app.set_selected_module_expanded(false);
+        assert_eq!(app.selected_index, 3);
+        assert!(app.expanded_modules.is_empty());
+    }
 
-        app.update_task_status(TaskStatus::Success(0));
-        assert!(!app.is_task_running());
+    #[test]
+    fn test_selecting_run_expands_modules() {
+        let tasks = module_tasks();
+        let mut app = AppState::new(tasks.clone());
+
+        app.start_task(tasks[2].clone());
+        app.select_run(0);
+
+        assert_eq!(app.selected_task().unwrap().name, "docker::db::migrate");
+        assert!(app.expanded_modules.contains(&module(&["docker"])));
+        assert!(app.expanded_modules.contains(&module(&["docker", "db"])));
+        assert!(app.is_row_running(&TaskRow::Module {
+            module: module(&["docker"]),
+            expanded: true,
+        }));
+    }
+
+    #[test]
+    fn test_concurrent_runs() {
+        let tasks = vec![
+            Task {
+                id: 0,
+                name: "server".to_string(),
+                description: None,
+                runner: TaskRunner::Just,
+                ..Default::default()
+            },
+            Task {
+                id: 1,
+                name: "lint".to_string(),
+                description: None,
+                runner: TaskRunner::Make,
+                ..Default::default()
+            },
+        ];
+        let mut app = AppState::new(tasks.clone());
+
+        let server = app.start_task(tasks[0].clone());
+        let lint = app.start_task(tasks[1].clone());
+        assert_eq!(app.running_count(), 2);
+
+        // Logs are routed to the run they belong to
+        app.append_log(server, "listening".to_string());
+        app.append_log(lint, "ok".to_string());
+        assert_eq!(
+            app.run_for_task(&tasks[0].key()).unwrap().log.lines(),
+            ["listening"]
+        );
+        assert_eq!(
+            app.run_for_task(&tasks[1].key()).unwrap().log.lines(),
+            ["ok"]
+        );
+
+        app.update_task_status(lint, TaskStatus::Success(0));
+        assert!(app.is_running(&tasks[0]));
+        assert!(!app.is_running(&tasks[1]));
+        assert_eq!(app.task_history.len(), 1);
+
+        // Rerunning a task replaces its finished tab
+        let relint = app.start_task(tasks[1].clone());
+        assert_eq!(app.runs.len(), 2);
+        assert_eq!(
+            app.run_for_task(&tasks[1].key()).map(|run| run.id),
+            Some(relint)
+        );
+    }
+
+    #[test]
+    fn test_switch_and_close_run_tabs() {
+        let tasks = vec![
+            Task {
+                id: 0,
+                name: "a".to_string(),
+                description: None,
+                runner: TaskRunner::Just,
+                ..Default::default()
+            },
+            Task {
+                id: 1,
+                name: "b".to_string(),
+                description: None,
+                runner: TaskRunner::Just,
+                ..Default::default()
+            },
+            Task {
+                id: 2,
+                name: "c".to_string(),
+                description: None,
+                runner: TaskRunner::Just,
+                ..Default::default()
+            },
+        ];
+        let mut app = AppState::new(tasks.clone());
+        app.start_task(tasks[2].clone());
+        let b = app.start_task(tasks[1].clone());
+
+        // Task "a" has no run, so switching starts at the first tab
+        app.select_next_run();
+        assert_eq!(app.selected_index, 2);
+        app.select_next_run();
+        assert_eq!(app.selected_index, 1);
+        app.select_previous_run();
+        assert_eq!(app.selected_index, 2);
+
+        // Running tabs cannot be closed
+        app.select_run(1);
+        assert!(!app.close_active_run());
+
+        app.append_log(b, "failed".to_string());
+        app.update_task_status(b, TaskStatus::Failed(1));
+        assert!(app.close_active_run());
+        assert_eq!(app.runs.len(), 1);
+
+        // The task keeps the output of the closed run until it runs again
+        assert_eq!(app.selected_task_logs(), Some(&["failed".to_string()][..]));
+        let b = app.start_task(tasks[1].clone());
+        assert!(app.task_logs.is_empty());
+        app.append_log(b, "passed".to_string());
+        assert_eq!(app.selected_task_logs(), Some(&["passed".to_string()][..]));
+    }
+
+    #[test]
+    fn test_set_diagnostics_reports_failures() {
+        use std::time::Duration;
+
+        let diagnostic = |source: &str, outcome| SourceDiagnostic {
+            source: source.to_string(),
+            description: "",
+            outcome,
+            elapsed: Duration::ZERO,
+            cached: false,
+        };
+        let mut app = AppState::new(vec![]);
+
+        app.set_diagnostics(vec![
+            diagnostic("just", SourceOutcome::Found(3)),
+            diagnostic("make", SourceOutcome::NotDetected),
+        ]);
+        assert_eq!(app.message, None);
+        assert_eq!(app.diagnostics.len(), 2);
+
+        app.set_diagnostics(vec![
+            diagnostic("just", SourceOutcome::Found(3)),
+            diagnostic(
+                "compose",
+                SourceOutcome::Failed("docker not found".to_string()),
+            ),
+        ]);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("1 task source(s) failed; press D for details")
+        );
+    }
+
+    #[test]
+    fn test_background_discovery() {
+        use std::time::Duration;
+
+        let mut app = AppState::new(vec![]);
+        app.start_discovery();
+        assert!(app.discovering);
+
+        let diagnostic = |outcome| SourceDiagnostic {
+            source: "just".to_string(),
+            description: "",
+            outcome,
+            elapsed: Duration::ZERO,
+            cached: false,
+        };
+        app.update_diagnostic(diagnostic(SourceOutcome::Discovering));
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Discovering tasks... (1 source(s) left)")
+        );
+        app.update_diagnostic(diagnostic(SourceOutcome::Found(1)));
+        assert_eq!(app.diagnostics.len(), 1);
+
+        app.finish_discovery(Ok(Discovery {
+            tasks: vec![Task {
+                name: "build".to_string(),
+                ..Default::default()
+            }],
+            diagnostics: vec![diagnostic(SourceOutcome::Found(1))],
+            ..Default::default()
+        }));
+        assert!(!app.discovering);
+        assert_eq!(app.tasks.len(), 1);
+        assert_eq!(app.message, None);
+
+        // A failed reload keeps the tasks
+        app.start_discovery();
+        app.finish_discovery(Err(color_eyre::eyre::eyre!("broken config")));
+        assert_eq!(app.tasks.len(), 1);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Failed to reload tasks: broken config")
+        );
+    }
+
+    #[test]
+    fn test_reload_tasks() {
+        let task = |id: usize, name: &str, runner: TaskRunner| Task {
+            id,
+            name: name.to_string(),
+            runner,
+            ..Default::default()
+        };
+        let just_test = task(2, "test", TaskRunner::Just);
+        let make_test = task(3, "test", TaskRunner::Make);
+        let mut app = AppState::new(vec![
+            task(0, "build", TaskRunner::Just),
+            task(1, "docs", TaskRunner::Just),
+            just_test.clone(),
+            make_test.clone(),
+        ]);
+        app.selected_index = 2;
+        for task in &app.tasks {
+            let mut log = LogBuffer::new(MAX_LOG_LINES_PER_TASK);
+            log.push_line(&task.name);
+            app.task_logs.insert(task.key(), log);
+        }
+        app.exec_mode_overrides
+            .insert(just_test.key(), ExecMode::Pty);
+
+        // "docs" is removed and two tasks are added above "test"
+        app.reload_tasks(vec![
+            task(0, "lint", TaskRunner::Just),
+            task(1, "fmt", TaskRunner::Just),
+            task(2, "build", TaskRunner::Just),
+            task(3, "test", TaskRunner::Just),
+            task(4, "test", TaskRunner::Make),
+        ]);
+        let selected = app.selected_task().unwrap();
+        assert_eq!((selected.id, &selected.runner), (3, &TaskRunner::Just));
+        assert_eq!(app.selected_task_logs(), Some(&["test".to_string()][..]));
+        assert_eq!(app.task_logs.len(), 3);
+        assert!(!app.task_logs.values().any(|log| log.lines() == ["docs"]));
+        assert_eq!(app.exec_mode_for(&app.tasks[3]), ExecMode::Pty);
+        assert_eq!(app.exec_mode_for(&app.tasks[4]), app.exec_mode);
+        assert!(app.is_flashing(&app.tasks[0]));
+        assert!(!app.is_flashing(&app.tasks[3]));
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Tasks reloaded: 2 added (lint, fmt), 1 removed (docs)")
+        );
+    }
+
+    #[test]
+    fn test_task_key() {
+        let package = |source: &str| Task {
+            name: "build".to_string(),
+            runner: TaskRunner::Package(PackageManager::Npm),
+            source: Some(PathBuf::from(source)),
+            ..Default::default()
+        };
+        let web = package("packages/web/package.json");
+        let api = package("packages/api/package.json");
+
+        assert_ne!(web.key(), api.key());
+        assert!(web.has_key(&web.key()));
+        assert!(!web.has_key(&api.key()));
+        assert_ne!(
+            Task {
+                runner: TaskRunner::Make,
+                ..web.clone()
+            }
+            .key(),
+            web.key()
+        );
+    }
+
+    #[test]
+    fn test_reload_message() {
+        assert_eq!(reload_message(&[], &[]), "Tasks reloaded");
+        assert_eq!(
+            reload_message(&["a", "b", "c", "d"], &[]),
+            "Tasks reloaded: 4 added (a, b, c, ...)"
+        );
+        assert_eq!(reload_message(&[], &["x"]), "Tasks reloaded: 1 removed (x)");
     }
 }
@@ -0,0 +1,25 @@
+/// Command line arguments.
+///
+/// By default Taskpad works in the project containing the current directory
+/// (see [`crate::project::find_root`]); the flags choose another project
+/// directory, justfile or Makefile.
+use clap::Parser;
+use std::path::PathBuf;
+
+/// A keyboard-driven TUI task launcher for just, make, npm and more.
+#[derive(Debug, Default, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Project directory to discover and run tasks in, instead of the nearest
+    /// parent directory with a task file or a repository root
+    #[arg(short, long, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+
+    /// Justfile to list recipes from (its recipes run in its directory)
+    #[arg(short = 'f', long, value_name = "FILE")]
+    pub justfile: Option<PathBuf>,
+
+    /// Makefile to list targets from (passed to make with -f)
+    #[arg(long, value_name = "FILE")]
+    pub makefile: Option<PathBuf>,
+}
@@ -0,0 +1,416 @@
+/// Configuration loading.
+///
+/// Taskpad reads a global config file (`config.json5` in the config
+/// directory) and a per-project `.taskpad.json5` in the project directory.
+/// Both are JSON5 and may define custom tasks, external task sources, which
+/// task sources are used and how running tasks are stopped; the project's tasks
+/// replace global tasks of the same name.
+use crate::process::StopPolicy;
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Name of the global config file inside the config directory.
+const GLOBAL_CONFIG_FILE: &str = "config.json5";
+
+/// Name of the per-project config file.
+pub const PROJECT_CONFIG_FILE: &str = ".taskpad.json5";
+
+/// Environment variable overriding the config directory.
+const CONFIG_DIR_VAR: &str = "TASKPAD_CONFIG";
+
+/// Environment variable overriding the data directory.
+const DATA_DIR_VAR: &str = "TASKPAD_DATA";
+
+/// Taskpad configuration, merged from the global and project config files.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// User-defined tasks
+    pub tasks: Vec<CustomTask>,
+    /// External task source executables, in addition to the
+    /// `taskpad-source-<name>` executables on PATH
+    pub external_sources: Vec<PathBuf>,
+    /// Which task sources are used, and in which order
+    pub sources: SourcesConfig,
+    /// Grace periods used when stopping a running task
+    pub stop: StopConfig,
+    /// Directories listed by the scripts source, relative to the project
+    /// directory (`scripts` and `bin` if not set)
+    pub script_dirs: Option<Vec<PathBuf>>,
+    /// Task files chosen on the command line (not read from the config files)
+    #[serde(skip)]
+    pub files: TaskFiles,
+}
+
+/// Script directories listed when `script_dirs` isn't set.
+const DEFAULT_SCRIPT_DIRS: &[&str] = &["scripts", "bin"];
+
+impl Config {
+    /// Returns the directories the scripts source lists, relative to the
+    /// project directory
+    pub fn script_dirs(&self) -> Vec<PathBuf> {
+        match &self.script_dirs {
+            Some(dirs) => dirs.clone(),
+            None => DEFAULT_SCRIPT_DIRS.iter().map(PathBuf::from).collect(),
+        }
+    }
+}
+
+/// Task files chosen on the command line instead of the ones found in the
+/// project directory.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TaskFiles {
+    /// Justfile to read recipes from (`--justfile`)
+    pub justfile: Option<PathBuf>,
+    /// Makefile to read targets from (`--makefile`)
+    pub makefile: Option<PathBuf>,
+}
+
+/// Selection of the task sources, by name (`just`, `make`, `cargo`, ...).
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SourcesConfig {
+    /// Only these sources are used, if set
+    pub enabled: Option<Vec<String>>,
+    /// Sources that are never used
+    pub disabled: Option<Vec<String>>,
+    /// Sources listed first, in this order; the others follow in their
+    /// default order
+    pub order: Option<Vec<String>>,
+}
+
+/// Grace periods used when stopping a running task, in seconds.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StopConfig {
+    /// How long to wait after SIGINT before sending SIGTERM
+    pub interrupt_grace: Option<f64>,
+    /// How long to wait after SIGTERM before sending SIGKILL
+    pub terminate_grace: Option<f64>,
+}
+
+impl StopConfig {
+    /// Returns the stop policy, using the default grace period for each
+    /// setting that isn't set
+    pub fn policy(&self) -> StopPolicy {
+        let default = StopPolicy::default();
+        StopPolicy {
+            interrupt_grace: grace(self.interrupt_grace).unwrap_or(default.interrupt_grace),
+            terminate_grace: grace(self.terminate_grace).unwrap_or(default.terminate_grace),
+        }
+    }
+}
+
+/// Converts a grace period in seconds, if it is set and a valid duration
+/// (`parse_config` rejects the others).
+fn grace(secs: Option<f64>) -> Option<Duration> {
+    secs.and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+}
+
+/// A task defined in a config file.
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CustomTask {
+    /// Name shown in the task list
+    pub name: String,
+    /// Command line run through the shell (`sh -c`, or `cmd /C` on Windows)
+    pub command: Option<String>,
+    /// Program and arguments run directly, without a shell
+    pub argv: Option<Vec<String>>,
+    /// Directory the task runs in (relative to the project directory)
+    pub cwd: Option<PathBuf>,
+    /// Environment variables set for the task
+    pub env: BTreeMap<String, String>,
+    pub description: Option<String>,
+    /// Group the task is listed in
+    pub group: Option<String>,
+}
+
+/// Returns the global config directory: `$TASKPAD_CONFIG`, or the platform's
+/// config directory (e.g. `~/.config/taskpad` on Linux).
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(CONFIG_DIR_VAR) {
+        return Some(PathBuf::from(dir));
+    }
+    directories::ProjectDirs::from("", "", "taskpad").map(|dirs| dirs.config_dir().to_path_buf())
+}
+
+/// Returns the data directory Taskpad keeps its caches in: `$TASKPAD_DATA`, or
+/// the platform's data directory (e.g. `~/.local/share/taskpad` on Linux).
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(dir) = std::env::var_os(DATA_DIR_VAR) {
+        return Some(PathBuf::from(dir));
+    }
+    directories::ProjectDirs::from("", "", "taskpad").map(|dirs| dirs.data_dir().to_path_buf())
+}
+
+/// Loads the global config and the config of the project in the current directory.
+///
+/// Missing config files are skipped.
+///
+/// # Errors
+///
+/// Returns an error naming the file if a config file can't be read or parsed,
+/// or defines an invalid task.
+pub fn load() -> Result<Config> {
+    let global = config_dir().map(|dir| dir.join(GLOBAL_CONFIG_FILE));
+    load_from(global.as_deref(), Path::new(PROJECT_CONFIG_FILE))
+}
+
+/// Returns the paths of the config files, whether they exist or not.
+pub fn config_files() -> Vec<PathBuf> {
+    config_dir()
+        .map(|dir| dir.join(GLOBAL_CONFIG_FILE))
+        .into_iter()
+        .chain([PathBuf::from(PROJECT_CONFIG_FILE)])
+        .collect()
+}
+
+/// Loads and merges the config files at `global` and `project`.
+fn load_from(global: Option<&Path>, project: &Path) -> Result<Config> {
+    let global = match global {
+        Some(path) => read_config(path)?,
+        None => None,
+    };
+    let project = read_config(project)?;
+
+    Ok(match (global, project) {
+        (Some(global), Some(project)) => merge(global, project),
+        (global, project) => project.or(global).unwrap_or_default(),
+    })
+}
+
+/// Reads the config file at `path`, returning `None` if it doesn't exist.
+fn read_config(path: &Path) -> Result<Option<Config>> {
+    let source = match std::fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(eyre!("Failed to read {}: {}", path.display(), e)),
+    };
+
+    parse_config(&source)
+        .map(Some)
+        .map_err(|e| eyre!("Invalid config in {}: {}", path.display(), e))
+}
+
+/// Parses a JSON5 config file and checks its tasks.
+fn parse_config(source: &str) -> Result<Config> {
+    let config: Config = json5::from_str(source).map_err(|e| eyre!("{}", e))?;
+
+    for task in &config.tasks {
+        if task.name.is_empty() {
+            return Err(eyre!("a task has no name"));
+        }
+        match (&task.command, &task.argv) {
+            (Some(_), None) => {}
+            (None, Some(argv)) if !argv.is_empty() => {}
+            (None, Some(_)) => return Err(eyre!("task '{}' has an empty argv", task.name)),
+            _ => {
+                return Err(eyre!(
+                    "task '{}' needs either a command or an argv",
+                    task.name
+                ))
+            }
+        }
+    }
+
+    for (name, grace) in [
+        ("interrupt_grace", config.stop.interrupt_grace),
+        ("terminate_grace", config.stop.terminate_grace),
+    ] {
+        if grace.is_some_and(|secs| Duration::try_from_secs_f64(secs).is_err()) {
+            return Err(eyre!("stop.{} must be a number of seconds", name));
+        }
+    }
+
+    for dir in config.script_dirs.iter().flatten() {
+        if !dir.is_relative() {
+            return Err(eyre!(
+                "script_dirs must be relative to the project directory, not {}",
+                dir.display()
+            ));
+        }
+    }
+
+    Ok(config)
+}
+
+/// Merges a project config into the global config: project tasks and
+/// sources come first, project tasks replace global tasks of the same name,
+/// and each source, stop and script directory setting of the project replaces
+/// the global one.
+fn merge(global: Config, project: Config) -> Config {
+    let mut tasks = project.tasks;
+    for task in global.tasks {
+        if !tasks.iter().any(|t| t.name == task.name) {
+            tasks.push(task);
+        }
+    }
+
+    let mut external_sources = project.external_sources;
+    for source in global.external_sources {
+        if !external_sources.contains(&source) {
+            external_sources.push(source);
+        }
+    }
+
+    Config {
+        tasks,
+        external_sources,
+        sources: SourcesConfig {
+            enabled: project.sources.enabled.or(global.sources.enabled),
+            disabled: project.sources.disabled.or(global.sources.disabled),
+            order: project.sources.order.or(global.sources.order),
+        },
+        stop: StopConfig {
+            interrupt_grace: project.stop.interrupt_grace.or(global.stop.interrupt_grace),
+            terminate_grace: project.stop.terminate_grace.or(global.stop.terminate_grace),
+        },
+        script_dirs: project.script_dirs.or(global.script_dirs),
+        files: TaskFiles::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_dir;
+    use std::fs;
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse_config(
+            r#"{
+                // Unknown sections are ignored
+                keybindings: { Home: { "<q>": "Quit" } },
+                tasks: [
+                    {
+                        name: "serve",
+                        command: "python3 -m http.server $PORT",
+                        cwd: "site",
+                        env: { PORT: "8000" },
+                        description: "Serve the site",
+                        group: "dev",
+                    },
+                    { name: "tail", argv: ["tail", "-f", "log/app.log"] },
+                ],
+                external_sources: ["tools/taskpad-source-bazel"],
+                sources: { disabled: ["vscode"], order: ["cargo", "just"] },
+                stop: { interrupt_grace: 0.5 },
+                script_dirs: ["tools", "ci/bin"],
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.tasks.len(), 2);
+        let serve = &config.tasks[0];
+        assert_eq!(serve.name, "serve");
+        assert_eq!(
+            serve.command.as_deref(),
+            Some("python3 -m http.server $PORT")
+        );
+        assert_eq!(serve.cwd, Some(PathBuf::from("site")));
+        assert_eq!(serve.env.get("PORT").map(String::as_str), Some("8000"));
+        assert_eq!(serve.group.as_deref(), Some("dev"));
+        assert_eq!(
+            config.tasks[1].argv,
+            Some(vec!["tail".into(), "-f".into(), "log/app.log".into()])
+        );
+        assert_eq!(
+            config.external_sources,
+            vec![PathBuf::from("tools/taskpad-source-bazel")]
+        );
+        assert_eq!(config.sources.disabled, Some(vec!["vscode".to_string()]));
+        assert_eq!(
+            config.sources.order,
+            Some(vec!["cargo".to_string(), "just".to_string()])
+        );
+        assert_eq!(config.sources.enabled, None);
+        assert_eq!(
+            config.stop.policy(),
+            StopPolicy {
+                interrupt_grace: Duration::from_millis(500),
+                terminate_grace: StopPolicy::default().terminate_grace,
+            }
+        );
+        assert_eq!(
+            config.script_dirs(),
+            vec![PathBuf::from("tools"), PathBuf::from("ci/bin")]
+        );
+        assert_eq!(
+            Config::default().script_dirs(),
+            vec![PathBuf::from("scripts"), PathBuf::from("bin")]
+        );
+    }
+
+    #[test]
+    fn test_parse_config_rejects_invalid_tasks() {
+        assert!(parse_config("{}").unwrap().tasks.is_empty());
+        assert!(parse_config("{ tasks: [ { command: 'ls' } ] }").is_err());
+        assert!(parse_config("{ tasks: [ { name: 'a' } ] }").is_err());
+        assert!(parse_config("{ tasks: [ { name: 'a', argv: [] } ] }").is_err());
+        assert!(parse_config("{ tasks: [ { name: 'a', command: 'ls', argv: ['ls'] } ] }").is_err());
+        assert!(parse_config("{ tasks: [").is_err());
+        assert!(parse_config("{ stop: { terminate_grace: -1 } }").is_err());
+        let err = parse_config("{ stop: { interrupt_grace: 1e20 } }").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "stop.interrupt_grace must be a number of seconds"
+        );
+        let huge = StopConfig {
+            interrupt_grace: Some(1e20),
+            terminate_grace: Some(f64::NAN),
+        };
+        assert_eq!(huge.policy(), StopPolicy::default());
+        assert!(parse_config("{ script_dirs: ['/usr/local/bin'] }").is_err());
+    }
+
+    #[test]
+    fn test_load_from_merges_project_over_global() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let global = dir.join("config.json5");
+        let project = dir.join(".taskpad.json5");
+        fs::write(
+            &global,
+            "{ tasks: [ { name: 'up', command: 'global up' }, { name: 'ps', command: 'ps' } ],
+               sources: { disabled: ['make'], order: ['just'] },
+               stop: { interrupt_grace: 1, terminate_grace: 1 }, script_dirs: ['tools'] }",
+        )
+        .unwrap();
+
+        // Only the global config exists
+        let config = load_from(Some(&global), &project).unwrap();
+        let names: Vec<&str> = config.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["up", "ps"]);
+
+        fs::write(
+            &project,
+            "{ tasks: [ { name: 'seed', command: 'seed' }, { name: 'up', command: 'project up' } ],
+               sources: { order: ['cargo'] }, stop: { terminate_grace: 5 } }",
+        )
+        .unwrap();
+        let config = load_from(Some(&global), &project).unwrap();
+        let names: Vec<&str> = config.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["seed", "up", "ps"]);
+        assert_eq!(config.tasks[1].command.as_deref(), Some("project up"));
+        assert_eq!(config.sources.order, Some(vec!["cargo".to_string()]));
+        assert_eq!(config.sources.disabled, Some(vec!["make".to_string()]));
+        assert_eq!(config.stop.interrupt_grace, Some(1.0));
+        assert_eq!(config.stop.terminate_grace, Some(5.0));
+        assert_eq!(config.script_dirs(), vec![PathBuf::from("tools")]);
+
+        // Errors name the broken file
+        fs::write(&project, "{ tasks: ").unwrap();
+        let err = load_from(Some(&global), &project).unwrap_err();
+        assert!(err.to_string().contains(".taskpad.json5"));
+
+        assert!(load_from(None, &dir.join("missing.json5"))
+            .unwrap()
+            .tasks
+            .is_empty());
+    }
+}
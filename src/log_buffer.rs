@@ -0,0 +1,508 @@
+/// Log storage with a minimal virtual-terminal line model.
+///
+/// Tasks running in a pseudo-terminal redraw progress bars and spinners by
+/// moving the cursor (`\r`, `ESC [ n A`) and erasing (`ESC [ K`) instead of
+/// printing new lines. `LogBuffer` interprets these so such output updates
+/// lines in place, the way it does in a real terminal. SGR (color) sequences
+/// are kept in the stored lines for rendering; other escape sequences are
+/// dropped.
+use unicode_width::UnicodeWidthChar;
+
+/// Escape character that introduces every ANSI sequence.
+const ESC: char = '\x1b';
+
+/// Tab stops are placed every this many columns.
+const TAB_WIDTH: usize = 8;
+
+/// Cursor movement stops at this column, so a task can't make a line grow
+/// without bound by moving the cursor far to the right.
+const MAX_COLUMN: usize = 4096;
+
+/// Upper bound for a pending (incomplete) escape sequence; longer ones are discarded.
+const MAX_PENDING_ESCAPE: usize = 256;
+
+/// Lines of task output plus the cursor of the terminal writing them.
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    /// Stored lines (may contain SGR escape sequences)
+    lines: Vec<String>,
+    /// Maximum number of lines kept; the oldest lines are dropped first
+    max_lines: usize,
+    /// Cursor line, counted from the last line (0 = last line)
+    row: usize,
+    /// Cursor column (in display columns)
+    col: usize,
+    /// Whether the cursor sits on a new line below the last one, not yet stored
+    fresh_line: bool,
+    /// Number of trailing lines the cursor may still move into.
+    ///
+    /// Complete lines (see `push_line`) are final, so cursor movement never
+    /// rewrites them or anything above them.
+    editable: usize,
+    /// Escape sequence split across two chunks of output
+    pending: String,
+}
+
+impl LogBuffer {
+    /// Creates an empty buffer keeping at most `max_lines` lines.
+    pub fn new(max_lines: usize) -> Self {
+        Self {
+            lines: Vec::new(),
+            max_lines,
+            row: 0,
+            col: 0,
+            fresh_line: true,
+            editable: 0,
+            pending: String::new(),
+        }
+    }
+
+    /// Returns the stored lines.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Removes all lines and resets the cursor.
+    pub fn clear(&mut self) {
+        *self = Self::new(self.max_lines);
+    }
+
+    /// Appends a complete line below any previous output.
+    ///
+    /// The line's own carriage returns and erase sequences are interpreted, but
+    /// it is final afterwards: later cursor movement cannot reach it.
+    pub fn push_line(&mut self, line: &str) {
+        if !self.fresh_line {
+            self.new_line();
+        }
+        self.pending.clear();
+        self.editable = 0;
+
+        self.write(line);
+        self.pending.clear();
+        if self.fresh_line {
+            // An empty line still takes up a line
+            self.lines.push(String::new());
+        }
+        self.fresh_line = true;
+        self.row = 0;
+        self.col = 0;
+        self.editable = 0;
+        self.enforce_limit();
+    }
+
+    /// Interprets a chunk of raw terminal output.
+    ///
+    /// Escape sequences may be split across chunks.
+    pub fn write(&mut self, output: &str) {
+        let mut input = std::mem::take(&mut self.pending);
+        input.push_str(output);
+
+        let mut text = String::new();
+        let mut chars = input.char_indices().peekable();
+
+        while let Some((start, ch)) = chars.next() {
+            match ch {
+                ESC => {
+                    let Some(sequence) = take_escape(&input[start..]) else {
+                        // Incomplete sequence: wait for the rest of it
+                        if input.len() - start <= MAX_PENDING_ESCAPE {
+                            self.pending = input[start..].to_string();
+                        }
+                        break;
+                    };
+                    // Skip the rest of the sequence
+                    while chars
+                        .next_if(|&(idx, _)| idx < start + sequence.len())
+                        .is_some()
+                    {}
+
+                    if let Some(params) = sequence
+                        .strip_prefix("\x1b[")
+                        .and_then(|s| s.strip_suffix('m'))
+                        .filter(|params| !params.contains(|c: char| c.is_ascii_alphabetic()))
+                    {
+                        // SGR sequences stay in the text for rendering
+                        text.push_str("\x1b[");
+                        text.push_str(params);
+                        text.push('m');
+                    } else if let Some(csi) = sequence.strip_prefix("\x1b[") {
+                        self.flush_text(&mut text);
+                        self.control_sequence(csi);
+                    }
+                }
+                '\n' => {
+                    self.flush_text(&mut text);
+                    self.new_line();
+                }
+                '\r' => {
+                    self.flush_text(&mut text);
+                    self.col = 0;
+                }
+                '\x08' => {
+                    self.flush_text(&mut text);
+                    self.col = self.col.saturating_sub(1);
+                }
+                '\t' => {
+                    let column = self.col + width(&text);
+                    let spaces = TAB_WIDTH - column % TAB_WIDTH;
+                    text.extend(std::iter::repeat_n(' ', spaces));
+                }
+                c if c.is_control() => {}
+                c => text.push(c),
+            }
+        }
+
+        self.flush_text(&mut text);
+        self.enforce_limit();
+    }
+
+    /// Writes `text` at the cursor, overwriting what is there, and advances the cursor.
+    fn flush_text(&mut self, text: &mut String) {
+        if text.is_empty() {
+            return;
+        }
+
+        if self.fresh_line {
+            self.lines.push(String::new());
+            self.fresh_line = false;
+            self.row = 0;
+            self.editable += 1;
+        }
+
+        let idx = self.lines.len() - 1 - self.row;
+        overwrite(&mut self.lines[idx], self.col, text);
+        self.col += width(text);
+        text.clear();
+    }
+
+    /// Moves the cursor to the start of the next line.
+    fn new_line(&mut self) {
+        self.col = 0;
+        if self.fresh_line {
+            // A line that received no text is still a line
+            self.lines.push(String::new());
+            self.editable += 1;
+        } else if self.row > 0 {
+            self.row -= 1;
+        } else {
+            self.fresh_line = true;
+        }
+    }
+
+    /// Applies a (non-SGR) control sequence given its parameters and final byte.
+    fn control_sequence(&mut self, csi: &str) {
+        let Some(final_byte) = csi.chars().last() else {
+            return;
+        };
+        let params = &csi[..csi.len() - final_byte.len_utf8()];
+        // Private sequences (e.g. `ESC [ ? 25 l` to hide the cursor) have no effect on the text
+        if params.starts_with(['?', '>', '<', '=']) {
+            return;
+        }
+        let arg = |default: usize| match params.split(';').next() {
+            Some(p) if !p.is_empty() => p.parse().unwrap_or(default),
+            _ => default,
+        };
+
+        match final_byte {
+            // Cursor up / previous line
+            'A' | 'F' => {
+                self.cursor_up(arg(1).max(1));
+                if final_byte == 'F' {
+                    self.col = 0;
+                }
+            }
+            // Cursor down / next line
+            'B' | 'E' => {
+                let n = arg(1).max(1);
+                if !self.fresh_line {
+                    self.row = self.row.saturating_sub(n);
+                }
+                if final_byte == 'E' {
+                    self.col = 0;
+                }
+            }
+            // Cursor forward / back
+            'C' => {
+                self.col = self
+                    .col
+                    .saturating_add(arg(1).max(1))
+                    .min(self.col.max(MAX_COLUMN))
+            }
+            'D' => self.col = self.col.saturating_sub(arg(1).max(1)),
+            // Cursor to column (1-based)
+            'G' => self.col = arg(1).saturating_sub(1).min(MAX_COLUMN),
+            // Erase in line
+            'K' => self.erase_in_line(arg(0)),
+            // Erase in display (only the part below the cursor is tracked)
+            'J' if arg(0) == 0 => {
+                self.erase_in_line(0);
+                if !self.fresh_line {
+                    let keep = self.lines.len() - self.row;
+                    self.lines.truncate(keep);
+                    self.editable = self.editable.saturating_sub(self.row);
+                    self.row = 0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves the cursor up, without leaving the lines that are still editable.
+    fn cursor_up(&mut self, n: usize) {
+        if self.editable == 0 {
+            return;
+        }
+
+        let target = if self.fresh_line {
+            n - 1
+        } else {
+            self.row.saturating_add(n)
+        };
+        self.row = target.min(self.editable - 1);
+        self.fresh_line = false;
+    }
+
+    /// Erases part of the cursor line: 0 = to the end, 1 = to the start, 2 = all of it.
+    fn erase_in_line(&mut self, mode: usize) {
+        if self.fresh_line {
+            return;
+        }
+
+        let idx = self.lines.len() - 1 - self.row;
+        let line = &mut self.lines[idx];
+        match mode {
+            0 => line.truncate(byte_index_at(line, self.col)),
+            1 => overwrite(line, 0, &" ".repeat(self.col.saturating_add(1))),
+            2 => line.clear(),
+            _ => {}
+        }
+    }
+
+    /// Drops the oldest lines beyond the limit.
+    fn enforce_limit(&mut self) {
+        if self.lines.len() > self.max_lines {
+            let excess = self.lines.len() - self.max_lines;
+            self.lines.drain(0..excess);
+            self.editable = self.editable.min(self.lines.len());
+            self.row = self.row.min(self.lines.len().saturating_sub(1));
+        }
+    }
+}
+
+/// Returns the complete escape sequence at the start of `s` (which begins with ESC).
+///
+/// Returns None if the sequence is incomplete.
+fn take_escape(s: &str) -> Option<&str> {
+    let mut chars = s.char_indices().skip(1);
+    match chars.next()? {
+        // Control Sequence Introducer: parameters up to a final byte
+        (_, '[') => chars
+            .find(|&(_, c)| ('\x40'..='\x7e').contains(&c))
+            .map(|(idx, c)| &s[..idx + c.len_utf8()]),
+        // Operating System Command: terminated by BEL or ST (ESC \)
+        (_, ']') => {
+            let end = s.find('\x07').map(|idx| idx + 1);
+            let st = s[1..].find("\x1b\\").map(|idx| idx + 3);
+            match (end, st) {
+                (Some(a), Some(b)) => Some(&s[..a.min(b)]),
+                (Some(a), None) | (None, Some(a)) => Some(&s[..a]),
+                (None, None) => None,
+            }
+        }
+        // Any other escape is ESC plus one character
+        (idx, c) => Some(&s[..idx + c.len_utf8()]),
+    }
+}
+
+/// Display width of text that may contain SGR escape sequences.
+fn width(text: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in text.chars() {
+        if in_escape {
+            in_escape = !c.is_ascii_alphabetic();
+        } else if c == ESC {
+            in_escape = true;
+        } else {
+            width += c.width().unwrap_or(0);
+        }
+    }
+    width
+}
+
+/// Returns the byte index in `line` where display column `col` starts.
+///
+/// Escape sequences before that column belong to the part before it; if the
+/// line is shorter, its length is returned.
+fn byte_index_at(line: &str, col: usize) -> usize {
+    let mut column = 0;
+    let mut in_escape = false;
+    for (idx, c) in line.char_indices() {
+        if in_escape {
+            in_escape = !c.is_ascii_alphabetic();
+        } else if c == ESC {
+            in_escape = true;
+        } else {
+            if column >= col {
+                return idx;
+            }
+            column += c.width().unwrap_or(0);
+        }
+    }
+    line.len()
+}
+
+/// Writes `text` into `line` starting at display column `col`.
+///
+/// Characters covered by `text` are replaced; escape sequences among them are
+/// kept so the style of the rest of the line is unchanged. A line shorter than
+/// `col` is padded with spaces.
+fn overwrite(line: &mut String, col: usize, text: &str) {
+    let line_width = width(line);
+    if line_width <= col {
+        line.extend(std::iter::repeat_n(' ', col - line_width));
+        line.push_str(text);
+        return;
+    }
+
+    let start = byte_index_at(line, col);
+    let text_width = width(text);
+
+    // Find the end of the replaced characters, collecting escapes along the way
+    let mut kept = String::new();
+    let mut end = line.len();
+    let mut covered = 0;
+    let mut in_escape = false;
+    for (idx, c) in line[start..].char_indices() {
+        if in_escape {
+            kept.push(c);
+            in_escape = !c.is_ascii_alphabetic();
+        } else if c == ESC {
+            kept.push(c);
+            in_escape = true;
+        } else {
+            if covered >= text_width {
+                end = start + idx;
+                break;
+            }
+            covered += c.width().unwrap_or(0);
+        }
+    }
+
+    line.replace_range(start..end, &format!("{}{}", text, kept));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer() -> LogBuffer {
+        LogBuffer::new(100)
+    }
+
+    #[test]
+    fn test_plain_lines() {
+        let mut log = buffer();
+        log.write("one\ntwo\r\n\nthree");
+        assert_eq!(log.lines(), ["one", "two", "", "three"]);
+
+        // Output continues on the unfinished line
+        log.write(" more\n");
+        assert_eq!(log.lines(), ["one", "two", "", "three more"]);
+    }
+
+    #[test]
+    fn test_carriage_return_overwrites_line() {
+        let mut log = buffer();
+        for percent in [10, 50, 100] {
+            log.write(&format!("\rDownloading... {:>3}%", percent));
+        }
+        log.write("\r\x1b[KDone\n");
+        assert_eq!(log.lines(), ["Done"]);
+
+        log.write("progress 10%\rab");
+        assert_eq!(log.lines(), ["Done", "abogress 10%"]);
+    }
+
+    #[test]
+    fn test_cursor_up_rewrites_previous_lines() {
+        let mut log = buffer();
+        log.write("crate a: 0%\ncrate b: 0%\n");
+        log.write("\x1b[2A\x1b[2Kcrate a: 100%\n\x1b[2Kcrate b: 40%\n");
+        assert_eq!(log.lines(), ["crate a: 100%", "crate b: 40%"]);
+
+        // Erasing below the cursor removes lines that were redrawn away
+        log.write("\x1b[2A\x1b[Jfinished\n");
+        assert_eq!(log.lines(), ["finished"]);
+    }
+
+    #[test]
+    fn test_complete_lines_are_not_rewritten() {
+        let mut log = buffer();
+        log.push_line("Starting task: [just] build");
+        log.write("\x1b[5Aprogress\rstep 2");
+        assert_eq!(log.lines(), ["Starting task: [just] build", "step 2ss"]);
+
+        // Complete lines start below unfinished output
+        log.push_line("Task exited with code: 0");
+        assert_eq!(
+            log.lines(),
+            [
+                "Starting task: [just] build",
+                "step 2ss",
+                "Task exited with code: 0"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_colors_are_kept_and_overwritten_by_column() {
+        let mut log = buffer();
+        log.write("\x1b[32m[#####     ]\x1b[0m 50%");
+        log.write("\r\x1b[32m[##########]\x1b[0m 100%");
+        assert_eq!(log.lines().len(), 1);
+        assert_eq!(
+            crate::ansi::plain_text(&log.lines()[0]),
+            "[##########] 100%"
+        );
+
+        // Other escape sequences are dropped
+        log.write("\n\x1b[?25l\x1b]0;title\x07hidden cursor\x1b[?25h");
+        assert_eq!(log.lines()[1], "hidden cursor");
+    }
+
+    #[test]
+    fn test_escape_split_across_writes() {
+        let mut log = buffer();
+        log.write("50%\x1b[");
+        log.write("2K\rdone");
+        assert_eq!(log.lines(), ["done"]);
+    }
+
+    #[test]
+    fn test_cursor_movement_is_bounded() {
+        let mut log = buffer();
+        log.write("abc\x1b[18446744073709551615C\x1b[5Cx");
+        assert_eq!(log.lines()[0].len(), MAX_COLUMN + 1);
+        assert!(log.lines()[0].ends_with(" x"));
+
+        log.write("\n\x1b[1000000000Gy\x1b[1K");
+        assert!(log.lines()[1].trim().is_empty());
+        assert!(log.lines()[1].len() <= MAX_COLUMN + 2);
+
+        log.write("\x1b[18446744073709551615A\x1b[18446744073709551615Fz");
+        assert_eq!(log.lines()[0], format!("z{}", &log.lines()[0][1..]));
+    }
+
+    #[test]
+    fn test_line_limit() {
+        let mut log = LogBuffer::new(3);
+        log.write("1\n2\n3\n4\n5");
+        assert_eq!(log.lines(), ["3", "4", "5"]);
+
+        log.write("\x1b[10A\rtop");
+        assert_eq!(log.lines(), ["top", "4", "5"]);
+    }
+}
@@ -2,13 +2,24 @@
 ///
 /// This is the main entry point that sets up the terminal, discovers tasks,
 /// and runs the main event loop.
+mod ansi;
 mod app;
+mod cli;
+mod config;
+mod log_buffer;
 mod process;
+mod project;
 mod tasks;
+#[cfg(test)]
+mod test_util;
 mod ui;
+mod watcher;
 
-use app::{AppState, TaskStatus};
-use color_eyre::eyre::Result;
+use app::{AppState, RunId, Task, TaskStatus};
+use clap::Parser;
+use cli::Cli;
+use color_eyre::eyre::{eyre, Result};
+use config::TaskFiles;
 use crossterm::{
     event::{
         self, Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind,
@@ -16,69 +27,64 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use process::{LogEvent, PtySize, TaskHandle};
+use project::Project;
 use ratatui::{backend::CrosstermBackend, Terminal};
+use std::collections::HashMap;
 use std::io;
-use std::sync::mpsc::{channel, Receiver};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
 use std::time::Duration;
+use tasks::DiscoveryEvent;
+use watcher::TaskWatcher;
+
+/// Channels and process handle of a single in-flight task run.
+struct RunChannels {
+    /// Receives log lines and terminal output from the task's process
+    log_rx: Receiver<LogEvent>,
+    /// Receives the final status when the process exits
+    status_rx: Receiver<TaskStatus>,
+    /// Handle used to stop the process (None if spawning failed)
+    handle: Option<TaskHandle>,
+}
+
+/// In-flight runs keyed by run id.
+type Runs = HashMap<RunId, RunChannels>;
 
 /// Main entry point for Taskpad.
 fn main() -> Result<()> {
     // Set up better panic handler
     color_eyre::install()?;
 
-    // Discover tasks from all available sources (Just and Make)
-    let tasks = match tasks::discover_all_tasks() {
-        Ok(tasks) => tasks,
-        Err(e) => {
-            // If discovery fails, create an AppState with the error
-            // and let the user see it in the TUI before quitting
-            let app = AppState::with_error(e.to_string());
-            return run_app_with_error(app);
-        }
-    };
+    let cli = Cli::parse();
+    let project = Project::from_cli(&cli, &std::env::current_dir()?)?;
+
+    // Task sources discover tasks in the current directory, and tasks run in
+    // it unless they have a directory of their own
+    std::env::set_current_dir(&project.root)
+        .map_err(|e| eyre!("Failed to enter {}: {}", project.root.display(), e))?;
 
-    // Create initial app state
-    let app = AppState::new(tasks);
+    // Tasks are discovered in the background once the TUI is up
+    let app = AppState::new(Vec::new());
 
     // Run the main application
-    run_app(app)
+    run_app(app, &project.files)
 }
 
-/// Runs the app when there's an error discovering tasks.
-/// Shows the error in the TUI and waits for the user to quit.
-fn run_app_with_error(app: AppState) -> Result<()> {
+/// Runs the main application with the given initial state, using the task
+/// `files` chosen on the command line.
+fn run_app(mut app: AppState, files: &TaskFiles) -> Result<()> {
     // Set up terminal
     let mut terminal = setup_terminal()?;
 
-    // Draw the UI once to show the error
-    terminal.draw(|frame| ui::render(frame, &app))?;
-
-    // Wait for 'q' key to quit
-    loop {
-        if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press && key.code == KeyCode::Char('q') {
-                    break;
-                }
-            }
-        }
-    }
-
-    // Restore terminal
-    restore_terminal(&mut terminal)?;
-
-    Ok(())
-}
+    // Channels for process communication, created fresh for each run
+    let mut runs: Runs = HashMap::new();
 
-/// Runs the main application with the given initial state.
-fn run_app(mut app: AppState) -> Result<()> {
-    // Set up terminal
-    let mut terminal = setup_terminal()?;
+    // Progress of the running task discovery, if any
+    let mut discovery = Some(tasks::spawn_discovery(files.clone()));
+    app.start_discovery();
 
-    // Create channels for process communication
-    // These will be created fresh each time we start a task
-    let mut log_rx: Option<Receiver<String>> = None;
-    let mut status_rx: Option<Receiver<TaskStatus>> = None;
+    // Watches the task files once the first discovery finished
+    let mut watcher: Option<TaskWatcher> = None;
 
     // Main event loop
     loop {
@@ -97,28 +103,83 @@ fn run_app(mut app: AppState) -> Result<()> {
         let task_list_inner_height = task_list_outer_height.saturating_sub(2); // Subtract borders
         app.adjust_task_scroll(task_list_inner_height);
 
+        // Keep the terminal size of PTY tasks in sync with the log pane
+        let pane_size = log_pane_inner_size(&app, terminal.size()?);
+        if pane_size != app.log_pane_size {
+            app.log_pane_size = pane_size;
+            for handle in runs.values().filter_map(|run| run.handle.as_ref()) {
+                handle.resize(pane_size);
+            }
+        }
+
         // Update scroll offset for history if visible
         if app.show_history {
             let history_inner_height = 8usize.saturating_sub(2); // History height (8) - borders (2)
             app.adjust_history_scroll(history_inner_height);
         }
 
-        // Check for process events (log lines, status updates)
-        if let Some(ref rx) = log_rx {
-            while let Ok(line) = rx.try_recv() {
-                app.append_log(line);
+        // Check for process events (log lines, status updates) of every run
+        runs.retain(|&run_id, run| {
+            while let Ok(event) = run.log_rx.try_recv() {
+                app.append_log_event(run_id, event);
+            }
+
+            match run.status_rx.try_recv() {
+                Ok(status) => {
+                    // Pick up any lines sent right before the final status
+                    while let Ok(event) = run.log_rx.try_recv() {
+                        app.append_log_event(run_id, event);
+                    }
+                    app.update_task_status(run_id, status);
+                    // Run finished, drop its receivers
+                    false
+                }
+                Err(_) => true,
             }
+        });
+
+        // Stop typing into a task once its run finished
+        if app.is_logs_focused() && active_handle(&app, &runs).is_none() {
+            app.focus_tasks();
         }
 
-        if let Some(ref rx) = status_rx {
-            if let Ok(status) = rx.try_recv() {
-                app.update_task_status(status);
-                // Task finished, clear the receivers
-                log_rx = None;
-                status_rx = None;
+        // Pick up the progress of the running discovery
+        if let Some(rx) = &discovery {
+            loop {
+                match rx.try_recv() {
+                    Ok(DiscoveryEvent::Source(diagnostic)) => app.update_diagnostic(diagnostic),
+                    Ok(DiscoveryEvent::Finished { result, watched }) => {
+                        app.finish_discovery(result);
+                        // Pick up changed stop settings along with the tasks (an
+                        // invalid config is already reported by the discovery)
+                        if let Ok(config) = config::load() {
+                            app.stop_policy = config.stop.policy();
+                        }
+                        // Files may have been added or removed (e.g. includes)
+                        match TaskWatcher::new(&watched) {
+                            Ok(new_watcher) => watcher = Some(new_watcher),
+                            Err(e) => app.set_message(e.to_string()),
+                        }
+                        discovery = None;
+                        break;
+                    }
+                    Err(TryRecvError::Empty) => break,
+                    Err(TryRecvError::Disconnected) => {
+                        app.finish_discovery(Err(eyre!("Task discovery stopped unexpectedly.")));
+                        discovery = None;
+                        break;
+                    }
+                }
             }
         }
 
+        // Reload the tasks when their files change (changes made during a
+        // discovery are picked up once it finished)
+        if discovery.is_none() && watcher.as_mut().is_some_and(TaskWatcher::changed) {
+            discovery = Some(tasks::spawn_discovery(files.clone()));
+            app.start_discovery();
+        }
+
         // Handle auto-scroll during drag selection
         if app.is_selecting {
             app.perform_drag_scroll();
@@ -128,20 +189,12 @@ fn run_app(mut app: AppState) -> Result<()> {
         // ~60 FPS for smoother mouse tracking and auto-scroll during drag selection
         if event::poll(Duration::from_millis(16))? {
             match event::read()? {
-                Event::Key(key) => {
-                    if key.kind == KeyEventKind::Press {
-                        handle_key_event(&mut app, key, &mut log_rx, &mut status_rx);
-                    }
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    handle_key_event(&mut app, key, &mut runs, &mut discovery, files);
                 }
                 Event::Mouse(mouse) => {
                     let terminal_height = terminal.size()?.height;
-                    handle_mouse_event(
-                        &mut app,
-                        mouse,
-                        terminal_height,
-                        &mut log_rx,
-                        &mut status_rx,
-                    );
+                    handle_mouse_event(&mut app, mouse, terminal_height);
                 }
                 _ => {}
             }
@@ -153,12 +206,33 @@ fn run_app(mut app: AppState) -> Result<()> {
         }
     }
 
+    // Don't leave running tasks (or anything they spawned) behind
+    for handle in runs.values().filter_map(|run| run.handle.as_ref()) {
+        handle.kill();
+    }
+
     // Restore terminal
     restore_terminal(&mut terminal)?;
 
     Ok(())
 }
 
+/// Calculates the inner size of the log pane for the given terminal size
+fn log_pane_inner_size(app: &AppState, terminal_size: ratatui::layout::Size) -> PtySize {
+    const TASK_LIST_WIDTH: u16 = 35;
+
+    // Top bar (1) + bottom bar (1) + log borders (2), plus the info box (6) if visible
+    let reserved_rows = if app.show_info { 4 + 6 } else { 4 };
+
+    PtySize {
+        cols: terminal_size
+            .width
+            .saturating_sub(TASK_LIST_WIDTH + 2)
+            .max(1),
+        rows: terminal_size.height.saturating_sub(reserved_rows).max(1),
+    }
+}
+
 /// Determines which region the mouse is over on the left side
 #[derive(Debug, PartialEq)]
 enum LeftRegion {
@@ -288,16 +362,16 @@ fn handle_scroll_down(app: &mut AppState, column: u16, row: u16, terminal_height
 }
 
 /// Handles mouse input events.
-fn handle_mouse_event(
-    app: &mut AppState,
-    mouse: MouseEvent,
-    terminal_height: u16,
-    _log_rx: &mut Option<Receiver<String>>,
-    _status_rx: &mut Option<Receiver<TaskStatus>>,
-) {
+#[allow(clippy::collapsible_match)]
+fn handle_mouse_event(app: &mut AppState, mouse: MouseEvent, terminal_height: u16) {
     // Task list width from ui module
     const TASK_LIST_WIDTH: u16 = 35;
 
+    // The arguments form and the diagnostics popup are modal
+    if app.args_form.is_some() || app.show_diagnostics {
+        return;
+    }
+
     match mouse.kind {
         // Handle left click
         MouseEventKind::Down(MouseButton::Left) => {
@@ -310,10 +384,11 @@ fn handle_mouse_event(
                         let clicked_row = (mouse.row - 2) as usize;
                         let task_index = clicked_row + app.task_scroll_offset;
 
-                        // Update selection and focus if valid
-                        if task_index < app.tasks.len() {
+                        // Update selection and focus if valid (clicking a module toggles it)
+                        if task_index < app.task_rows.len() {
                             app.selected_index = task_index;
                             app.focus_tasks();
+                            app.toggle_selected_module();
                         }
                     }
                     LeftRegion::History => {
@@ -334,6 +409,11 @@ fn handle_mouse_event(
                         }
                     }
                 }
+            } else if mouse.column > TASK_LIST_WIDTH && mouse.row == log_pane_top(app) {
+                // Click on the log pane's top border - switch run tabs
+                if let Some(tab) = ui::run_tab_at(app, mouse.column - TASK_LIST_WIDTH - 1) {
+                    app.select_run(tab);
+                }
             } else if mouse.column >= TASK_LIST_WIDTH && mouse.row >= 2 {
                 // Click in logs area - start text selection
                 // Convert screen coordinates to log line/column
@@ -398,6 +478,15 @@ fn handle_mouse_event(
     }
 }
 
+/// Returns the screen row of the log pane's top border (where run tabs are drawn)
+fn log_pane_top(app: &AppState) -> u16 {
+    if app.show_info {
+        1 + 6 // Top bar (1) + info box (6)
+    } else {
+        1 // Top bar (1)
+    }
+}
+
 /// Converts screen coordinates to log line and column position
 fn screen_to_log_position(
     app: &AppState,
@@ -472,11 +561,32 @@ fn screen_to_log_position(
 fn handle_key_event(
     app: &mut AppState,
     key: KeyEvent,
-    log_rx: &mut Option<Receiver<String>>,
-    status_rx: &mut Option<Receiver<TaskStatus>>,
+    runs: &mut Runs,
+    discovery: &mut Option<Receiver<DiscoveryEvent>>,
+    files: &TaskFiles,
 ) {
     use crossterm::event::KeyModifiers;
 
+    // The arguments form captures all keys while it is open
+    if app.args_form.is_some() {
+        handle_args_form_key(app, key, runs);
+        return;
+    }
+
+    // Keys go to the selected task's terminal while the log pane is focused
+    if app.is_logs_focused() {
+        handle_log_input_key(app, key, runs);
+        return;
+    }
+
+    // The diagnostics popup closes on Esc or D
+    if app.show_diagnostics {
+        if matches!(key.code, KeyCode::Esc | KeyCode::Char('D')) {
+            app.toggle_diagnostics();
+        }
+        return;
+    }
+
     match key.code {
         // Quit
         KeyCode::Char('q') => {
@@ -545,65 +655,87 @@ fn handle_key_event(
 
         // Run selected task or rerun from history
         KeyCode::Enter => {
-            if app.is_task_running() {
-                app.set_message("A task is already running. Wait for it to finish.".to_string());
-            } else if app.is_history_focused() {
+            if app.is_history_focused() {
                 // Rerun task from history
                 if let Some(entry) = app.selected_history_entry() {
                     // Find matching task in current task list
-                    if let Some(task) = app
-                        .tasks
-                        .iter()
-                        .find(|t| t.name == entry.task_name && t.runner == entry.runner)
-                        .cloned()
-                    {
-                        // Create new channels for this task
-                        let (log_tx, new_log_rx) = channel();
-                        let (status_tx, new_status_rx) = channel();
-
-                        *log_rx = Some(new_log_rx);
-                        *status_rx = Some(new_status_rx);
-
-                        // Start the task and reset log scrolling
-                        app.start_task_with_scroll_reset(task.clone());
-                        process::run_task(task, log_tx, status_tx);
-
-                        // Switch focus back to tasks
-                        app.focus_tasks();
+                    if let Some(task) = app.tasks.iter().find(|t| t.has_key(&entry.task)).cloned() {
+                        if run_or_prompt(app, task.clone(), runs) {
+                            // Switch focus back to tasks and show the new run
+                            select_run_of(app, &task);
+                        }
                     } else {
                         app.set_message(format!(
                             "Task '{}' not found in current task list",
-                            entry.task_name
+                            entry.task.name
                         ));
                     }
                 }
             } else if let Some(task) = app.selected_task().cloned() {
-                // Create new channels for this task
-                let (log_tx, new_log_rx) = channel();
-                let (status_tx, new_status_rx) = channel();
+                run_or_prompt(app, task, runs);
+            } else {
+                app.toggle_selected_module();
+            }
+        }
+
+        // Expand / collapse modules in the task list
+        KeyCode::Char('+') | KeyCode::Char('=') => {
+            app.set_selected_module_expanded(true);
+        }
+
+        KeyCode::Char('-') => {
+            app.set_selected_module_expanded(false);
+        }
+
+        KeyCode::Char(' ') => {
+            app.toggle_selected_module();
+        }
+
+        // Stop the selected task's run (pressing again escalates to the next signal)
+        KeyCode::Char('s') => {
+            if let Some(handle) = active_handle(app, runs) {
+                handle.stop();
+                app.set_message("Stopping task... (press 's' again to force)".to_string());
+            } else {
+                app.set_message("The selected task is not running.".to_string());
+            }
+        }
+
+        // Type into the selected task's terminal (e.g. to answer a prompt)
+        KeyCode::Char('I') => {
+            if active_handle(app, runs).is_some_and(TaskHandle::accepts_input) {
+                app.focus_logs();
+            } else {
+                app.set_message(
+                    "The selected task is not running in a pseudo-terminal.".to_string(),
+                );
+            }
+        }
 
-                *log_rx = Some(new_log_rx);
-                *status_rx = Some(new_status_rx);
+        // Switch between run tabs
+        KeyCode::Tab => {
+            app.select_next_run();
+        }
+
+        KeyCode::BackTab => {
+            app.select_previous_run();
+        }
 
-                // Start the task and reset log scrolling
-                app.start_task_with_scroll_reset(task.clone());
-                process::run_task(task, log_tx, status_tx);
+        // Close the active run tab
+        KeyCode::Char('x') => {
+            let closed = app.close_active_run();
+            if !closed {
+                app.set_message("Only finished runs can be closed.".to_string());
             }
         }
 
         // Reload tasks
         KeyCode::Char('r') => {
-            if app.is_task_running() {
-                app.set_message("Cannot reload tasks while a task is running.".to_string());
+            if discovery.is_some() {
+                app.set_message("Tasks are already being discovered.".to_string());
             } else {
-                match tasks::discover_all_tasks() {
-                    Ok(new_tasks) => {
-                        app.reload_tasks(new_tasks);
-                    }
-                    Err(e) => {
-                        app.set_message(format!("Failed to reload tasks: {}", e));
-                    }
-                }
+                *discovery = Some(tasks::spawn_discovery(files.clone()));
+                app.start_discovery();
             }
         }
 
@@ -614,6 +746,31 @@ fn handle_key_event(
             app.set_message("Logs cleared".to_string());
         }
 
+        // Toggle between rendering and stripping ANSI colors in the log pane
+        KeyCode::Char('a') => {
+            app.toggle_render_ansi();
+        }
+
+        // Toggle between pseudo-terminal and piped execution (globally)
+        KeyCode::Char('p') => {
+            app.toggle_exec_mode();
+        }
+
+        // Toggle between pseudo-terminal and piped execution for the selected task
+        KeyCode::Char('P') => {
+            app.toggle_selected_task_exec_mode();
+        }
+
+        // Show or hide hidden tasks (e.g. private recipes)
+        KeyCode::Char('.') => {
+            app.toggle_hidden_tasks();
+        }
+
+        // Show or hide the task source diagnostics
+        KeyCode::Char('D') => {
+            app.toggle_diagnostics();
+        }
+
         // Toggle info box
         KeyCode::Char('i') => {
             app.toggle_info();
@@ -643,6 +800,181 @@ fn handle_key_event(
     }
 }
 
+/// Returns the process handle of the selected task's run, if it is running.
+fn active_handle<'a>(app: &AppState, runs: &'a Runs) -> Option<&'a TaskHandle> {
+    app.active_run()
+        .filter(|run| run.is_running())
+        .and_then(|run| runs.get(&run.id))
+        .and_then(|run| run.handle.as_ref())
+}
+
+/// Handles keyboard input while the log pane is focused, forwarding keys to
+/// the selected task's terminal.
+fn handle_log_input_key(app: &mut AppState, key: KeyEvent, runs: &Runs) {
+    let Some(handle) = active_handle(app, runs) else {
+        // The run finished (or another task was selected)
+        app.focus_tasks();
+        return;
+    };
+
+    if key.code == KeyCode::Esc {
+        app.focus_tasks();
+        app.set_message("Stopped typing into the task".to_string());
+        return;
+    }
+
+    if let Some(input) = key_input(key) {
+        if let Err(e) = handle.write_input(&input) {
+            app.focus_tasks();
+            app.set_message(format!("Failed to send input: {}", e));
+        }
+    }
+}
+
+/// Returns the bytes a terminal sends for the given key, if any.
+fn key_input(key: KeyEvent) -> Option<Vec<u8>> {
+    use crossterm::event::KeyModifiers;
+
+    let sequence: &[u8] = match key.code {
+        KeyCode::Char(ch) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            // Ctrl+A..Ctrl+Z (and Ctrl+Space for NUL) map to control characters
+            return match ch.to_ascii_lowercase() {
+                ch @ 'a'..='z' => Some(vec![ch as u8 - b'a' + 1]),
+                ' ' | '@' => Some(vec![0]),
+                _ => None,
+            };
+        }
+        KeyCode::Char(ch) => {
+            let mut input = Vec::new();
+            if key.modifiers.contains(KeyModifiers::ALT) {
+                input.push(0x1b);
+            }
+            input.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+            return Some(input);
+        }
+        KeyCode::Enter => b"\r",
+        KeyCode::Tab => b"\t",
+        KeyCode::BackTab => b"\x1b[Z",
+        KeyCode::Backspace => b"\x7f",
+        KeyCode::Up => b"\x1b[A",
+        KeyCode::Down => b"\x1b[B",
+        KeyCode::Right => b"\x1b[C",
+        KeyCode::Left => b"\x1b[D",
+        KeyCode::Home => b"\x1b[H",
+        KeyCode::End => b"\x1b[F",
+        KeyCode::Delete => b"\x1b[3~",
+        KeyCode::PageUp => b"\x1b[5~",
+        KeyCode::PageDown => b"\x1b[6~",
+        _ => return None,
+    };
+    Some(sequence.to_vec())
+}
+
+/// Handles keyboard input while the arguments form is open.
+fn handle_args_form_key(app: &mut AppState, key: KeyEvent, runs: &mut Runs) {
+    use crossterm::event::KeyModifiers;
+
+    let Some(form) = app.args_form.as_mut() else {
+        return;
+    };
+
+    match key.code {
+        // Cancel without running
+        KeyCode::Esc => {
+            app.close_args_form();
+        }
+
+        // Run the task with the entered arguments
+        KeyCode::Enter => match form.args() {
+            Ok(args) => {
+                let task = form.task.clone();
+                app.close_args_form();
+                if start_run(app, task.clone(), args, runs) && app.is_history_focused() {
+                    // Started from history: switch focus back to tasks and show the new run
+                    select_run_of(app, &task);
+                }
+            }
+            Err(message) => {
+                app.set_message(message);
+            }
+        },
+
+        // Move between fields
+        KeyCode::Tab | KeyCode::Down => {
+            form.focus_next();
+        }
+
+        KeyCode::BackTab | KeyCode::Up => {
+            form.focus_previous();
+        }
+
+        // Edit the focused field
+        KeyCode::Backspace => {
+            form.delete_char();
+        }
+
+        KeyCode::Char(ch) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+            form.insert_char(ch);
+        }
+
+        _ => {}
+    }
+}
+
+/// Selects the run tab of the given task (and the task itself).
+fn select_run_of(app: &mut AppState, task: &Task) {
+    let key = task.key();
+    if let Some(tab) = app.runs.iter().position(|run| run.task.has_key(&key)) {
+        app.select_run(tab);
+    }
+}
+
+/// Runs a task, or opens the arguments form first if the task has parameters.
+///
+/// Returns true if a run was started right away.
+fn run_or_prompt(app: &mut AppState, task: Task, runs: &mut Runs) -> bool {
+    // A task that is already running is reported right away rather than after
+    // the user filled in the form
+    if task.params.is_empty() || app.is_running(&task) {
+        start_run(app, task, Vec::new(), runs)
+    } else {
+        app.open_args_form(task);
+        false
+    }
+}
+
+/// Starts a new run of the given task and registers its channels.
+///
+/// Returns false (and leaves a message) if the task is already running.
+fn start_run(app: &mut AppState, task: Task, args: Vec<String>, runs: &mut Runs) -> bool {
+    if app.is_running(&task) {
+        app.set_message(format!(
+            "{} {} is already running.",
+            task.runner.prefix(),
+            task.name
+        ));
+        return false;
+    }
+
+    let (log_tx, log_rx) = channel();
+    let (status_tx, status_rx) = channel();
+
+    // Start the task and reset log scrolling
+    let run_id = app.start_task_with_scroll_reset(task.clone());
+    let options = app.run_options_for(&task);
+    let handle = process::run_task(task, args, options, log_tx, status_tx);
+
+    runs.insert(
+        run_id,
+        RunChannels {
+            log_rx,
+            status_rx,
+            handle,
+        },
+    );
+    true
+}
+
 /// Copies text to the system clipboard
 fn copy_to_clipboard(text: &str) -> Result<()> {
     use arboard::Clipboard;
@@ -3,119 +3,650 @@
 /// This module handles running tasks as subprocesses and streaming
 /// their output back to the main thread via channels.
 use crate::app::{Task, TaskStatus};
-use std::io::{BufRead, BufReader};
-use std::process::{Command, Stdio};
-use std::sync::mpsc::Sender;
+use crate::tasks::registry::SourceRegistry;
+use std::io::{self, BufRead, BufReader, Read};
+use std::process::{Child, ChildStderr, ChildStdout, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
 use std::thread;
+use std::time::{Duration, Instant};
+
+#[cfg(unix)]
+use std::fs::File;
+
+/// Output of a task, as sent to the log pane.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogEvent {
+    /// A complete line (Taskpad's own messages, or a line of piped stderr)
+    Line(String),
+    /// Raw output of the task, which may redraw earlier output
+    Output(String),
+}
+
+/// How often the supervising thread checks whether the child has exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How a task's process is attached to Taskpad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecMode {
+    /// Run inside a pseudo-terminal so the task sees a real TTY (colors,
+    /// progress bars and TTY detection work as in a terminal)
+    Pty,
+    /// Pipe stdout and stderr; stderr lines are prefixed with `[stderr]`
+    Piped,
+}
+
+impl ExecMode {
+    /// Returns the short name shown in the UI
+    pub fn label(&self) -> &str {
+        match self {
+            ExecMode::Pty => "pty",
+            ExecMode::Piped => "piped",
+        }
+    }
+
+    /// Returns the other execution mode
+    pub fn toggled(&self) -> ExecMode {
+        match self {
+            ExecMode::Pty => ExecMode::Piped,
+            ExecMode::Piped => ExecMode::Pty,
+        }
+    }
+}
+
+impl Default for ExecMode {
+    /// PTY mode where supported, piped mode elsewhere
+    fn default() -> Self {
+        if cfg!(unix) {
+            ExecMode::Pty
+        } else {
+            ExecMode::Piped
+        }
+    }
+}
+
+/// Size of the terminal presented to a task running in a pseudo-terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PtySize {
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl Default for PtySize {
+    fn default() -> Self {
+        Self { cols: 80, rows: 24 }
+    }
+}
+
+/// Grace periods used when stopping a running task.
+///
+/// Stopping sends SIGINT to the task's process group, escalates to SIGTERM after
+/// `interrupt_grace` and finally to SIGKILL after a further `terminate_grace`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StopPolicy {
+    /// How long to wait after SIGINT before sending SIGTERM
+    pub interrupt_grace: Duration,
+    /// How long to wait after SIGTERM before sending SIGKILL
+    pub terminate_grace: Duration,
+}
+
+impl Default for StopPolicy {
+    fn default() -> Self {
+        Self {
+            interrupt_grace: Duration::from_secs(3),
+            terminate_grace: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Options controlling how a task is executed.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RunOptions {
+    /// Whether to run the task in a pseudo-terminal or with pipes
+    pub exec_mode: ExecMode,
+    /// Grace periods used when the task is stopped via its handle
+    pub stop_policy: StopPolicy,
+    /// Initial terminal size in PTY mode (normally the log pane's inner size)
+    pub pty_size: PtySize,
+}
+
+/// Requests sent from a `TaskHandle` to the thread supervising the child.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Control {
+    /// Start (or advance) graceful termination
+    Stop,
+    /// Kill the process group immediately
+    Kill,
+}
+
+/// Escalation stage of a stop request.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StopStage {
+    Interrupt,
+    Terminate,
+    Kill,
+}
+
+impl StopStage {
+    /// Returns the signal name logged when entering this stage
+    fn signal_name(&self) -> &str {
+        match self {
+            StopStage::Interrupt => "SIGINT",
+            StopStage::Terminate => "SIGTERM",
+            StopStage::Kill => "SIGKILL",
+        }
+    }
+
+    /// Returns the next stage, if any
+    fn next(&self) -> Option<StopStage> {
+        match self {
+            StopStage::Interrupt => Some(StopStage::Terminate),
+            StopStage::Terminate => Some(StopStage::Kill),
+            StopStage::Kill => None,
+        }
+    }
+}
+
+/// Handle to a task started by `run_task`, used to stop or resize it.
+#[derive(Debug)]
+pub struct TaskHandle {
+    /// Process id of the child (also its process group id on Unix)
+    pid: u32,
+    /// Channel to the supervising thread
+    control_tx: Sender<Control>,
+    /// Master side of the task's pseudo-terminal (PTY mode only)
+    #[cfg(unix)]
+    pty_master: Option<File>,
+}
+
+impl TaskHandle {
+    /// Requests a graceful stop.
+    ///
+    /// The first call sends SIGINT to the task's process group; further calls
+    /// skip the remaining grace period and escalate to the next signal.
+    pub fn stop(&self) {
+        let _ = self.control_tx.send(Control::Stop);
+    }
+
+    /// Kills the task's whole process group immediately.
+    ///
+    /// On Unix the signal is delivered synchronously, so this is safe to call
+    /// right before Taskpad exits.
+    pub fn kill(&self) {
+        #[cfg(unix)]
+        signal_group(self.pid, libc::SIGKILL);
+        let _ = self.control_tx.send(Control::Kill);
+    }
+
+    /// Returns true if the task runs in a pseudo-terminal and can be typed into
+    pub fn accepts_input(&self) -> bool {
+        #[cfg(unix)]
+        return self.pty_master.is_some();
+
+        #[cfg(not(unix))]
+        false
+    }
+
+    /// Writes keyboard input to the task's pseudo-terminal, as if typed into it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task runs in piped mode (its stdin is closed) or
+    /// the input can't be written.
+    pub fn write_input(&self, input: &[u8]) -> io::Result<()> {
+        #[cfg(unix)]
+        if let Some(mut master) = self.pty_master.as_ref() {
+            use std::io::Write;
+            return master.write_all(input);
+        }
+
+        let _ = input;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the task is not running in a pseudo-terminal",
+        ))
+    }
+
+    /// Resizes the task's pseudo-terminal; the kernel notifies the task with SIGWINCH.
+    ///
+    /// Does nothing for tasks running in piped mode.
+    pub fn resize(&self, size: PtySize) {
+        #[cfg(unix)]
+        if let Some(master) = &self.pty_master {
+            use std::os::fd::AsRawFd;
+            let _ = set_window_size(master.as_raw_fd(), size);
+        }
+
+        #[cfg(not(unix))]
+        let _ = size;
+    }
+}
+
+/// Output streams of a spawned task.
+enum TaskOutput {
+    /// Separate stdout and stderr pipes
+    Piped {
+        stdout: ChildStdout,
+        stderr: ChildStderr,
+    },
+    /// Master side of the task's pseudo-terminal (stdout and stderr combined)
+    #[cfg(unix)]
+    Pty(File),
+}
+
+/// Builds the command for a task (`just <recipe-name>`, `make <target>`,
+/// `<package-manager> run <script>`, or the task's command for cargo, mise,
+/// Python tools and docker compose, e.g. `cargo run --bin server` or
+/// `docker compose up --detach db`, and the program of VS Code, script,
+/// custom and external tasks).
+fn task_command(task: &Task, args: &[String]) -> Command {
+    let mut command = SourceRegistry::builtin().command(task, args);
+    if let Some(cwd) = &task.cwd {
+        command.current_dir(cwd);
+    }
+    command.envs(task.env.iter().map(|(key, value)| (key, value)));
+    command
+}
+
+/// Spawns a task with its stdout and stderr connected to pipes.
+fn spawn_piped(task: &Task, args: &[String]) -> io::Result<(Child, TaskOutput)> {
+    let mut command = task_command(task, args);
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // Give the task its own process group so signals reach the whole tree
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command.spawn()?;
+    let stdout = child.stdout.take().expect("Failed to capture stdout");
+    let stderr = child.stderr.take().expect("Failed to capture stderr");
+
+    Ok((child, TaskOutput::Piped { stdout, stderr }))
+}
+
+/// Spawns a task inside a new pseudo-terminal of the given size.
+///
+/// The child becomes the leader of a new session with the PTY as its
+/// controlling terminal, which also makes it the leader of its own process
+/// group (so stopping it reaches grandchildren just like in piped mode).
+#[cfg(unix)]
+fn spawn_pty(task: &Task, args: &[String], size: PtySize) -> io::Result<(Child, TaskOutput)> {
+    use std::os::fd::{FromRawFd, OwnedFd};
+    use std::os::unix::process::CommandExt;
+
+    let mut master: libc::c_int = -1;
+    let mut slave: libc::c_int = -1;
+    let mut winsize = libc::winsize {
+        ws_row: size.rows,
+        ws_col: size.cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    let result = unsafe {
+        libc::openpty(
+            &mut master,
+            &mut slave,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            // Some platforms declare this parameter as `*mut`
+            std::ptr::addr_of_mut!(winsize),
+        )
+    };
+    if result != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Take ownership right away so the descriptors are closed on every path
+    let (master, slave) = unsafe { (OwnedFd::from_raw_fd(master), OwnedFd::from_raw_fd(slave)) };
+
+    // Keep other tasks from inheriting this PTY, otherwise its master would never
+    // see end-of-file after our child exits
+    set_cloexec(&master)?;
+    set_cloexec(&slave)?;
+
+    let slave = File::from(slave);
+    let mut command = task_command(task, args);
+    command
+        .stdin(Stdio::from(slave.try_clone()?))
+        .stdout(Stdio::from(slave.try_clone()?))
+        .stderr(Stdio::from(slave));
+
+    // Tools only emit colors when they know the terminal type
+    if std::env::var_os("TERM").is_none() {
+        command.env("TERM", "xterm-256color");
+    }
+
+    // SAFETY: only async-signal-safe functions are called between fork and exec
+    unsafe {
+        command.pre_exec(|| {
+            if libc::setsid() == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            // stdin is already the PTY slave at this point
+            if libc::ioctl(0, libc::TIOCSCTTY as _, 0) == -1 {
+                return Err(io::Error::last_os_error());
+            }
+            Ok(())
+        });
+    }
+
+    let child = command.spawn()?;
+
+    // `command` (holding the parent's copies of the slave) is dropped on return,
+    // so the master sees end-of-file once the task and its children exit
+    Ok((child, TaskOutput::Pty(File::from(master))))
+}
+
+/// Marks a file descriptor close-on-exec.
+#[cfg(unix)]
+fn set_cloexec(fd: &std::os::fd::OwnedFd) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let result = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC) };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Sets the window size of the pseudo-terminal behind `fd`.
+#[cfg(unix)]
+fn set_window_size(fd: std::os::fd::RawFd, size: PtySize) -> io::Result<()> {
+    let winsize = libc::winsize {
+        ws_row: size.rows,
+        ws_col: size.cols,
+        ws_xpixel: 0,
+        ws_ypixel: 0,
+    };
+
+    let result = unsafe { libc::ioctl(fd, libc::TIOCSWINSZ as _, &winsize) };
+    if result == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Reads raw output from `reader` in chunks and sends it to `tx`.
+///
+/// Chunks are split on UTF-8 character boundaries; invalid UTF-8 is replaced.
+/// Piped and PTY output both go through the log buffer's virtual terminal, so
+/// carriage returns and cursor movement are interpreted the same way.
+fn forward_output(mut reader: impl Read, tx: &Sender<LogEvent>) -> io::Result<()> {
+    let mut buf = [0u8; 4096];
+    let mut bytes = Vec::new();
+
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => {
+                flush_output(&mut bytes, tx);
+                return Err(e);
+            }
+        };
+        bytes.extend_from_slice(&buf[..n]);
+
+        // Hold back a character that is cut off at the end of the chunk
+        let complete = match std::str::from_utf8(&bytes) {
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => bytes.len(),
+        };
+        let rest = bytes.split_off(complete);
+        flush_output(&mut bytes, tx);
+        bytes = rest;
+    }
+
+    flush_output(&mut bytes, tx);
+    Ok(())
+}
+
+/// Reads output from `reader` and sends each complete line to `tx`, starting
+/// it with `prefix`.
+///
+/// Piped stderr is forwarded this way while stdout streams through the log
+/// buffer's virtual terminal, so the two never share a cursor: a stderr line
+/// always starts on a line of its own and can't be overwritten by stdout's
+/// carriage returns. Carriage returns within the line are still interpreted.
+fn forward_lines(reader: impl Read, prefix: &str, tx: &Sender<LogEvent>) -> io::Result<()> {
+    let mut reader = BufReader::new(reader);
+    let mut line = Vec::new();
+
+    loop {
+        line.clear();
+        let result = reader.read_until(b'\n', &mut line);
+        if !line.is_empty() {
+            let text = String::from_utf8_lossy(&line);
+            let text = text.strip_suffix('\n').unwrap_or(&text);
+            let _ = tx.send(LogEvent::Line(prefix_line(prefix, text)));
+        }
+        match result {
+            Ok(0) => return Ok(()),
+            Ok(_) => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Sends the buffered output bytes (if any) to `tx` and clears the buffer.
+fn flush_output(bytes: &mut Vec<u8>, tx: &Sender<LogEvent>) {
+    if !bytes.is_empty() {
+        let output = String::from_utf8_lossy(bytes).into_owned();
+        let _ = tx.send(LogEvent::Output(output));
+        bytes.clear();
+    }
+}
+
+/// Returns `line` with `prefix` (e.g. `[stderr] `) inserted at its start.
+///
+/// The prefix is repeated after each carriage return too, so that a line
+/// redrawn in place (like a progress bar) keeps it.
+fn prefix_line(prefix: &str, line: &str) -> String {
+    let mut prefixed = String::with_capacity(prefix.len() + line.len());
+    let mut line_start = true;
+    for ch in line.chars() {
+        if ch == '\r' {
+            line_start = true;
+        } else if line_start {
+            prefixed.push_str(prefix);
+            line_start = false;
+        }
+        prefixed.push(ch);
+    }
+    prefixed
+}
+
+/// Sends a signal to every process in the given process group.
+#[cfg(unix)]
+fn signal_group(pgid: u32, signal: libc::c_int) {
+    // A negative pid addresses the whole process group. Errors (e.g. ESRCH when
+    // the group has already exited) are deliberately ignored.
+    unsafe {
+        libc::kill(-(pgid as libc::pid_t), signal);
+    }
+}
+
+/// Delivers the signal for the given stop stage to the child.
+///
+/// On Unix the signal goes to the child's entire process group so grandchildren
+/// (e.g. compilers spawned by make) are stopped too. Other platforms have no
+/// equivalent of process-group signals, so every stage kills the child directly.
+fn send_stop_signal(child: &mut Child, stage: StopStage) {
+    #[cfg(unix)]
+    {
+        let signal = match stage {
+            StopStage::Interrupt => libc::SIGINT,
+            StopStage::Terminate => libc::SIGTERM,
+            StopStage::Kill => libc::SIGKILL,
+        };
+        signal_group(child.id(), signal);
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = stage;
+        let _ = child.kill();
+    }
+}
+
+/// Returns the signal that terminated the process, if any.
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        status.signal()
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = status;
+        None
+    }
+}
+
+/// Maps a finished process to the status recorded for the task.
+///
+/// A task that exits after the user asked it to stop is `Cancelled`, unless it
+/// had to be forced down by a signal other than SIGINT, in which case it is
+/// `Killed` with that signal.
+fn final_status(status: &ExitStatus, stop_requested: bool) -> TaskStatus {
+    let exit_code = status.code().unwrap_or(-1);
+
+    match exit_signal(status) {
+        #[cfg(unix)]
+        Some(libc::SIGINT) if stop_requested => TaskStatus::Cancelled,
+        Some(signal) => TaskStatus::Killed(signal),
+        None if stop_requested => TaskStatus::Cancelled,
+        None if status.success() => TaskStatus::Success(exit_code),
+        None => TaskStatus::Failed(exit_code),
+    }
+}
 
 /// Spawns a task as a subprocess and streams its output.
 ///
 /// This function:
-/// 1. Spawns the appropriate command (`just <recipe-name>` or `make <target>`) based on task.runner
+/// 1. Spawns the appropriate command (`just <recipe-name>` or `make <target>`) based on task.runner,
+///    either inside a pseudo-terminal or with piped output depending on `options.exec_mode`
 /// 2. Captures both stdout and stderr
-/// 3. Streams output line-by-line to `log_tx`
+/// 3. Streams output to `log_tx`: raw chunks, except piped stderr, which is sent
+///    a line at a time with a `[stderr]` prefix
 /// 4. Sends final status to `status_tx` when the process exits
 ///
-/// The function spawns a separate thread to handle I/O, so it returns immediately
-/// and doesn't block the caller.
+/// The process is spawned right away, but its I/O is handled on separate threads,
+/// so the function returns immediately and doesn't block the caller. On Unix the
+/// child is placed in its own process group so that stopping it also reaches any
+/// grandchildren it spawned. PTY mode is only available on Unix; elsewhere tasks
+/// always run in piped mode.
 ///
 /// # Arguments
 ///
 /// * `task` - The task to run
-/// * `log_tx` - Channel sender for log lines
+/// * `args` - Arguments passed to the task (e.g. values for recipe parameters)
+/// * `options` - Execution mode, stop policy and initial PTY size
+/// * `log_tx` - Channel sender for log lines and terminal output
 /// * `status_tx` - Channel sender for final status updates
 ///
-/// # Panics
+/// # Returns
 ///
-/// This function may panic if the channels are disconnected, which would indicate
-/// a programming error (the main thread dropped its receivers).
-pub fn run_task(task: Task, log_tx: Sender<String>, status_tx: Sender<TaskStatus>) {
-    thread::spawn(move || {
-        // Send initial log message
-        let _ = log_tx.send(format!(
-            "Starting task: {} {}",
-            task.runner.prefix(),
-            task.name
-        ));
-
-        // Spawn the appropriate command based on the task runner
-        let command = task.runner.command();
-        let mut child = match Command::new(command)
-            .arg(&task.name)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-        {
-            Ok(child) => child,
-            Err(e) => {
-                let _ = log_tx.send(format!("ERROR: Failed to spawn process: {}", e));
-                let _ = status_tx.send(TaskStatus::Failed(-1));
-                return;
-            }
-        };
+/// A `TaskHandle` that can be used to stop or resize the task, or `None` if the
+/// process could not be spawned (the error is reported through the channels).
+pub fn run_task(
+    task: Task,
+    args: Vec<String>,
+    options: RunOptions,
+    log_tx: Sender<LogEvent>,
+    status_tx: Sender<TaskStatus>,
+) -> Option<TaskHandle> {
+    // Send initial log message
+    let mut command_line = format!("{} {}", task.runner.prefix(), task.name);
+    for arg in &args {
+        if arg.is_empty() || arg.contains(char::is_whitespace) {
+            command_line.push_str(&format!(" {:?}", arg));
+        } else {
+            command_line.push_str(&format!(" {}", arg));
+        }
+    }
+    let _ = log_tx.send(LogEvent::Line(format!("Starting task: {}", command_line)));
 
-        // Get stdout and stderr handles
-        let stdout = child.stdout.take().expect("Failed to capture stdout");
-        let stderr = child.stderr.take().expect("Failed to capture stderr");
-
-        // Create channels for merging stdout and stderr
-        let (merged_tx, merged_rx) = std::sync::mpsc::channel();
-
-        // Spawn thread for stdout
-        let stdout_tx = merged_tx.clone();
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                match line {
-                    Ok(line) => {
-                        let _ = stdout_tx.send(line);
-                    }
-                    Err(e) => {
-                        let _ = stdout_tx.send(format!("ERROR reading stdout: {}", e));
-                        break;
-                    }
+    // Spawn the appropriate command based on the task runner
+    let spawned = match options.exec_mode {
+        #[cfg(unix)]
+        ExecMode::Pty => spawn_pty(&task, &args, options.pty_size),
+        _ => spawn_piped(&task, &args),
+    };
+
+    let (mut child, output) = match spawned {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            let _ = log_tx.send(LogEvent::Line(format!(
+                "ERROR: Failed to spawn process: {}",
+                e
+            )));
+            let _ = status_tx.send(TaskStatus::Failed(-1));
+            return None;
+        }
+    };
+
+    let (control_tx, control_rx) = channel();
+    let handle = TaskHandle {
+        pid: child.id(),
+        control_tx,
+        #[cfg(unix)]
+        pty_master: match &output {
+            TaskOutput::Pty(master) => master.try_clone().ok(),
+            _ => None,
+        },
+    };
+
+    // Stream output on dedicated threads
+    match output {
+        TaskOutput::Piped { stdout, stderr } => {
+            let stdout_tx = log_tx.clone();
+            thread::spawn(move || {
+                if let Err(e) = forward_output(stdout, &stdout_tx) {
+                    let _ = stdout_tx.send(LogEvent::Line(format!("ERROR reading stdout: {}", e)));
                 }
-            }
-        });
+            });
 
-        // Spawn thread for stderr
-        let stderr_tx = merged_tx;
-        thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                match line {
-                    Ok(line) => {
-                        // Prefix stderr lines to distinguish them
-                        let _ = stderr_tx.send(format!("[stderr] {}", line));
-                    }
-                    Err(e) => {
-                        let _ = stderr_tx.send(format!("ERROR reading stderr: {}", e));
-                        break;
-                    }
+            // Prefix stderr lines to distinguish them
+            let stderr_tx = log_tx.clone();
+            thread::spawn(move || {
+                if let Err(e) = forward_lines(stderr, "[stderr] ", &stderr_tx) {
+                    let _ = stderr_tx.send(LogEvent::Line(format!("ERROR reading stderr: {}", e)));
                 }
-            }
-        });
+            });
+        }
+        #[cfg(unix)]
+        TaskOutput::Pty(master) => {
+            let pty_tx = log_tx.clone();
+            thread::spawn(move || {
+                // Reading the master fails with EIO once every process holding the
+                // slave has exited, which simply marks the end of output
+                let _ = forward_output(master, &pty_tx);
+            });
+        }
+    }
 
-        // Forward merged output to log channel
-        // We need to stop when both stdout and stderr threads have finished
-        // For simplicity, we'll read until the child process exits
-        let log_tx_clone = log_tx.clone();
-        let _reader_thread = thread::spawn(move || {
-            while let Ok(line) = merged_rx.recv() {
-                let _ = log_tx_clone.send(line);
-            }
-        });
+    thread::spawn(move || {
+        // Wait for the child process to exit, handling stop requests meanwhile
+        let (result, stop_requested) =
+            supervise(&mut child, &control_rx, options.stop_policy, &log_tx);
 
-        // Wait for the child process to exit
-        match child.wait() {
+        match result {
             Ok(status) => {
                 // Give a brief moment for remaining output to be processed.
                 //
-                // The stdout/stderr reader threads forward lines through `merged_tx` to the
-                // log channel. When the child exits, there can be a small delay before those
-                // threads finish reading and sending any final lines. This fixed sleep acts
-                // as a simple heuristic to reduce the chance of truncating late output before
-                // we log the final exit status.
+                // The reader threads forward lines to the log channel. When the child
+                // exits, there can be a small delay before those threads finish reading
+                // and sending any final lines. This fixed sleep acts as a simple
+                // heuristic to reduce the chance of truncating late output before we
+                // log the final exit status.
                 //
                 // The 50ms value is a tradeoff between:
                 // - Reliability: a longer delay gives the reader threads more time to drain
@@ -127,23 +658,344 @@ pub fn run_task(task: Task, log_tx: Sender<String>, status_tx: Sender<TaskStatus
                 // expected output volume, and I/O performance) and balance these concerns.
                 thread::sleep(std::time::Duration::from_millis(50));
 
-                let exit_code = status.code().unwrap_or(-1);
-                let _ = log_tx.send(format!("Task exited with code: {}", exit_code));
-
-                let task_status = if status.success() {
-                    TaskStatus::Success(exit_code)
-                } else {
-                    TaskStatus::Failed(exit_code)
-                };
+                match exit_signal(&status) {
+                    Some(signal) => {
+                        let _ = log_tx.send(LogEvent::Line(format!(
+                            "Task terminated by signal: {}",
+                            signal
+                        )));
+                    }
+                    None => {
+                        let exit_code = status.code().unwrap_or(-1);
+                        let _ = log_tx.send(LogEvent::Line(format!(
+                            "Task exited with code: {}",
+                            exit_code
+                        )));
+                    }
+                }
 
-                let _ = status_tx.send(task_status);
+                let _ = status_tx.send(final_status(&status, stop_requested));
             }
             Err(e) => {
-                let _ = log_tx.send(format!("ERROR: Failed to wait for process: {}", e));
+                let _ = log_tx.send(LogEvent::Line(format!(
+                    "ERROR: Failed to wait for process: {}",
+                    e
+                )));
                 let _ = status_tx.send(TaskStatus::Failed(-1));
             }
         }
 
-        // The reader thread will exit when merged_tx is dropped (when stdout/stderr threads finish)
+        // The reader threads exit on their own once the output streams are closed
     });
+
+    Some(handle)
+}
+
+/// Waits for the child to exit while servicing stop requests from its handle.
+///
+/// Returns the exit status together with whether a stop was requested.
+fn supervise(
+    child: &mut Child,
+    control_rx: &Receiver<Control>,
+    stop_policy: StopPolicy,
+    log_tx: &Sender<LogEvent>,
+) -> (std::io::Result<ExitStatus>, bool) {
+    let mut stop: Option<(StopStage, Instant)> = None;
+
+    let result = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break Ok(status),
+            Ok(None) => {}
+            Err(e) => break Err(e),
+        }
+
+        let requested = match control_rx.recv_timeout(POLL_INTERVAL) {
+            Ok(Control::Stop) => match stop {
+                // Repeated stop requests skip the remaining grace period
+                Some((stage, _)) => stage.next(),
+                None => Some(StopStage::Interrupt),
+            },
+            Ok(Control::Kill) => Some(StopStage::Kill),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => {
+                // Nobody can stop us anymore; keep polling for exit
+                thread::sleep(POLL_INTERVAL);
+                None
+            }
+        };
+
+        // Escalate once the grace period of the current stage runs out
+        let escalated = stop.and_then(|(stage, since)| {
+            let grace = match stage {
+                StopStage::Interrupt => stop_policy.interrupt_grace,
+                StopStage::Terminate => stop_policy.terminate_grace,
+                StopStage::Kill => return None,
+            };
+            if since.elapsed() >= grace {
+                stage.next()
+            } else {
+                None
+            }
+        });
+
+        if let Some(stage) = requested.or(escalated) {
+            let already_there = stop.map(|(current, _)| current == stage).unwrap_or(false);
+            if !already_there {
+                let _ = log_tx.send(LogEvent::Line(format!(
+                    "Stopping task ({})...",
+                    stage.signal_name()
+                )));
+                send_stop_signal(child, stage);
+                stop = Some((stage, Instant::now()));
+            }
+        }
+    };
+
+    // Make sure nothing from the task's process group outlives a stop request
+    #[cfg(unix)]
+    if stop.is_some() {
+        signal_group(child.id(), libc::SIGKILL);
+    }
+
+    (result, stop.is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::TaskRunner;
+    use crate::log_buffer::LogBuffer;
+
+    /// Returns a task running `script` through `sh -c`
+    #[cfg(unix)]
+    fn shell_task(script: &str) -> Task {
+        Task {
+            name: "test".to_string(),
+            runner: TaskRunner::Custom,
+            command: vec!["sh".to_string(), "-c".to_string(), script.to_string()],
+            ..Default::default()
+        }
+    }
+
+    /// Returns true if the process exists and hasn't exited (zombies count as exited)
+    #[cfg(unix)]
+    fn is_alive(pid: u32) -> bool {
+        let output = Command::new("ps")
+            .args(["-o", "stat=", "-p", &pid.to_string()])
+            .output()
+            .unwrap();
+        let stat = String::from_utf8_lossy(&output.stdout);
+        !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+    }
+
+    #[cfg(unix)]
+    fn status_from_raw(raw: i32) -> ExitStatus {
+        use std::os::unix::process::ExitStatusExt;
+        ExitStatus::from_raw(raw)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_final_status_normal_exit() {
+        // Raw wait status encodes the exit code in the high byte
+        assert_eq!(
+            final_status(&status_from_raw(0), false),
+            TaskStatus::Success(0)
+        );
+        assert_eq!(
+            final_status(&status_from_raw(2 << 8), false),
+            TaskStatus::Failed(2)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_final_status_after_stop() {
+        assert_eq!(
+            final_status(&status_from_raw(libc::SIGINT), true),
+            TaskStatus::Cancelled
+        );
+        assert_eq!(
+            final_status(&status_from_raw(130 << 8), true),
+            TaskStatus::Cancelled
+        );
+        assert_eq!(
+            final_status(&status_from_raw(libc::SIGKILL), true),
+            TaskStatus::Killed(libc::SIGKILL)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_final_status_killed_externally() {
+        assert_eq!(
+            final_status(&status_from_raw(libc::SIGTERM), false),
+            TaskStatus::Killed(libc::SIGTERM)
+        );
+    }
+
+    #[test]
+    fn test_stop_stage_escalation() {
+        assert_eq!(StopStage::Interrupt.next(), Some(StopStage::Terminate));
+        assert_eq!(StopStage::Terminate.next(), Some(StopStage::Kill));
+        assert_eq!(StopStage::Kill.next(), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_pty_task_sees_sized_terminal_and_input() {
+        let task = shell_task(
+            "test -t 0 && test -t 1 && echo tty; stty size; read line; echo \"got $line\"",
+        );
+        let options = RunOptions {
+            exec_mode: ExecMode::Pty,
+            pty_size: PtySize {
+                cols: 100,
+                rows: 30,
+            },
+            ..Default::default()
+        };
+        let (log_tx, log_rx) = channel();
+        let (status_tx, status_rx) = channel();
+        let handle = run_task(task, Vec::new(), options, log_tx, status_tx).unwrap();
+        assert!(handle.accepts_input());
+
+        let mut output = String::new();
+        let mut wait_for = |text: &str| -> String {
+            while !output.contains(text) {
+                match log_rx.recv_timeout(Duration::from_secs(5)) {
+                    Ok(LogEvent::Output(chunk)) => output.push_str(&chunk),
+                    Ok(LogEvent::Line(_)) => {}
+                    Err(e) => panic!("{:?} waiting for {:?} in {:?}", e, text, output),
+                }
+            }
+            output.clone()
+        };
+
+        let output = wait_for("30 100");
+        assert!(output.contains("tty"));
+
+        // Enter sends a carriage return, which the terminal turns into a newline
+        handle.write_input(b"hello\r").unwrap();
+        wait_for("got hello");
+
+        let status = status_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(status, TaskStatus::Success(0));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_piped_task_takes_no_input() {
+        let options = RunOptions {
+            exec_mode: ExecMode::Piped,
+            ..Default::default()
+        };
+        let (log_tx, _log_rx) = channel();
+        let (status_tx, status_rx) = channel();
+        let handle = run_task(shell_task("true"), Vec::new(), options, log_tx, status_tx).unwrap();
+
+        assert!(!handle.accepts_input());
+        assert!(handle.write_input(b"y").is_err());
+        status_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    #[test]
+    fn test_forward_output_keeps_split_characters_whole() {
+        // "é" is split across the two reads
+        let chunks = [b"caf\xc3".to_vec(), b"\xa9\r50%".to_vec()];
+        let reader = io::Read::chain(&chunks[0][..], &chunks[1][..]);
+        let (tx, rx) = channel();
+
+        forward_output(reader, &tx).unwrap();
+        drop(tx);
+
+        let events: Vec<LogEvent> = rx.iter().collect();
+        assert_eq!(
+            events,
+            vec![
+                LogEvent::Output("caf".to_string()),
+                LogEvent::Output("é\r50%".to_string()),
+            ]
+        );
+    }
+
+    /// Writes the events received on `rx` into a log buffer, like the app does.
+    fn replay(rx: &Receiver<LogEvent>) -> LogBuffer {
+        let mut log = LogBuffer::new(10);
+        for event in rx.try_iter() {
+            match event {
+                LogEvent::Output(output) => log.write(&output),
+                LogEvent::Line(line) => log.push_line(&line),
+            }
+        }
+        log
+    }
+
+    #[test]
+    fn test_forward_lines_prefixes_redrawn_lines() {
+        let (tx, rx) = channel();
+        forward_lines(&b"10%\r100%\r\ndone\nno newline"[..], "[stderr] ", &tx).unwrap();
+
+        assert_eq!(
+            replay(&rx).lines(),
+            ["[stderr] 100%", "[stderr] done", "[stderr] no newline"]
+        );
+    }
+
+    #[test]
+    fn test_piped_streams_dont_share_a_cursor() {
+        let (tx, rx) = channel();
+        forward_output(&b"abc"[..], &tx).unwrap();
+        forward_lines(&b"err\n"[..], "[stderr] ", &tx).unwrap();
+        forward_output(&b"\rdef\n"[..], &tx).unwrap();
+
+        assert_eq!(replay(&rx).lines(), ["abc", "[stderr] err", "def"]);
+    }
+
+    #[test]
+    fn test_prefix_line() {
+        assert_eq!(prefix_line("> ", "a\rb"), "> a\r> b");
+        assert_eq!(prefix_line("> ", "c\r"), "> c\r");
+        assert_eq!(prefix_line("> ", ""), "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stop_reaches_grandchildren() {
+        // Background jobs of a non-interactive shell ignore SIGINT, so the
+        // `sleep` outlives the shell unless the whole group is killed
+        let task = shell_task("sleep 30 & echo $!; wait");
+        let options = RunOptions {
+            exec_mode: ExecMode::Piped,
+            stop_policy: StopPolicy {
+                interrupt_grace: Duration::from_millis(200),
+                terminate_grace: Duration::from_millis(200),
+            },
+            ..Default::default()
+        };
+        let (log_tx, log_rx) = channel();
+        let (status_tx, status_rx) = channel();
+        let handle = run_task(task, Vec::new(), options, log_tx, status_tx).unwrap();
+
+        let mut output = String::new();
+        let grandchild = loop {
+            if let Some((pid, _)) = output.split_once('\n') {
+                break pid.parse::<u32>().unwrap();
+            }
+            if let LogEvent::Output(chunk) = log_rx.recv_timeout(Duration::from_secs(5)).unwrap() {
+                output.push_str(&chunk);
+            }
+        };
+        assert!(is_alive(grandchild));
+
+        handle.stop();
+        let status = status_rx.recv_timeout(Duration::from_secs(10)).unwrap();
+        assert_eq!(status, TaskStatus::Cancelled);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while is_alive(grandchild) {
+            assert!(Instant::now() < deadline, "grandchild survived the stop");
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
 }
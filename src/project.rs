@@ -0,0 +1,154 @@
+/// Project directory discovery.
+///
+/// Like `just`, Taskpad can be started anywhere inside a project: it walks up
+/// from the current directory to the nearest directory with a task file
+/// (justfile, Makefile, package.json, ...) or the root of a repository, and
+/// discovers and runs tasks there. Task files are the files the task sources
+/// are detected by, so every source's files count.
+use crate::cli::Cli;
+use crate::config::{TaskFiles, PROJECT_CONFIG_FILE};
+use crate::tasks::registry::SourceRegistry;
+use color_eyre::eyre::{eyre, Result};
+use std::path::{Path, PathBuf};
+
+/// Version control directories marking the root of a repository (`.git` is a
+/// file in worktrees and submodules).
+const VCS_DIRS: &[&str] = &[".git", ".hg", ".jj", ".svn"];
+
+/// The project Taskpad works in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    /// Directory tasks are discovered and run in
+    pub root: PathBuf,
+    /// Task files chosen on the command line
+    pub files: TaskFiles,
+}
+
+impl Project {
+    /// Resolves the project selected by the command line, which Taskpad was
+    /// started with in `cwd`. Paths on the command line are relative to `cwd`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chosen directory or task files don't exist.
+    pub fn from_cli(cli: &Cli, cwd: &Path) -> Result<Self> {
+        let root = match &cli.dir {
+            Some(dir) => {
+                let dir = cwd.join(dir);
+                if !dir.is_dir() {
+                    return Err(eyre!("{} is not a directory", dir.display()));
+                }
+                dir
+            }
+            None => find_root(cwd),
+        };
+
+        let file = |path: &Option<PathBuf>, kind: &str| match path {
+            Some(path) if !cwd.join(path).is_file() => {
+                Err(eyre!("{} not found: {}", kind, path.display()))
+            }
+            path => Ok(path.as_ref().map(|path| cwd.join(path))),
+        };
+        let files = TaskFiles {
+            justfile: file(&cli.justfile, "Justfile")?,
+            makefile: file(&cli.makefile, "Makefile")?,
+        };
+
+        Ok(Self { root, files })
+    }
+}
+
+/// Returns the project directory containing `dir`: the nearest of `dir` and
+/// its parents with a task file, a project config or a version control
+/// directory, or `dir` itself if there is none.
+pub fn find_root(dir: &Path) -> PathBuf {
+    let registry = SourceRegistry::builtin();
+    dir.ancestors()
+        .find(|dir| {
+            registry.marks_project(dir)
+                || dir.join(PROJECT_CONFIG_FILE).is_file()
+                || VCS_DIRS.iter().any(|name| dir.join(name).exists())
+        })
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_dir;
+    use std::fs;
+
+    #[test]
+    fn test_find_root() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let nested = dir.join("repo/app/src/bin");
+        fs::create_dir_all(&nested).unwrap();
+
+        fs::create_dir_all(dir.join("repo/.git")).unwrap();
+        assert_eq!(find_root(&nested), dir.join("repo"));
+
+        // The nearest task file wins over the repository root
+        fs::write(dir.join("repo/app/package.json"), "{}").unwrap();
+        assert_eq!(find_root(&nested), dir.join("repo/app"));
+        fs::write(dir.join("repo/app/src/justfile"), "").unwrap();
+        assert_eq!(find_root(&nested), dir.join("repo/app/src"));
+
+        // Every source's files mark a project, but script directories don't
+        for file in [
+            "Cargo.toml",
+            "pyproject.toml",
+            "compose.yaml",
+            ".vscode/tasks.json",
+        ] {
+            let project = dir.join("other").join(file.replace(['.', '/'], "_"));
+            let nested = project.join("src");
+            fs::create_dir_all(&nested).unwrap();
+            fs::create_dir_all(project.join(file).parent().unwrap()).unwrap();
+            fs::write(project.join(file), "").unwrap();
+            assert_eq!(find_root(&nested), project, "{file}");
+            fs::create_dir_all(nested.join("scripts")).unwrap();
+            assert_eq!(find_root(&nested), project, "{file}");
+        }
+        let config_dir = dir.join("repo/app/src/bin");
+        fs::write(config_dir.join(PROJECT_CONFIG_FILE), "{}").unwrap();
+        assert_eq!(find_root(&config_dir), config_dir);
+    }
+
+    #[test]
+    fn test_project_from_cli() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        fs::create_dir_all(dir.join("app/src")).unwrap();
+        fs::write(dir.join("app/justfile"), "").unwrap();
+        fs::write(dir.join("build.mk"), "").unwrap();
+
+        let project = Project::from_cli(&Cli::default(), &dir.join("app/src")).unwrap();
+        assert_eq!(project.root, dir.join("app"));
+        assert_eq!(project.files, TaskFiles::default());
+
+        let cli = Cli {
+            dir: Some(PathBuf::from("..")),
+            makefile: Some(PathBuf::from("../build.mk")),
+            ..Default::default()
+        };
+        let project = Project::from_cli(&cli, &dir.join("app")).unwrap();
+        assert_eq!(project.root, dir.join("app/.."));
+        assert_eq!(project.files.makefile, Some(dir.join("app/../build.mk")));
+        assert_eq!(project.files.justfile, None);
+
+        let cli = Cli {
+            justfile: Some(PathBuf::from("missing.just")),
+            ..Default::default()
+        };
+        let err = Project::from_cli(&cli, dir).unwrap_err();
+        assert_eq!(err.to_string(), "Justfile not found: missing.just");
+
+        let cli = Cli {
+            dir: Some(PathBuf::from("missing")),
+            ..Default::default()
+        };
+        assert!(Project::from_cli(&cli, dir).is_err());
+    }
+}
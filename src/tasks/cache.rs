@@ -0,0 +1,222 @@
+/// Discovery cache.
+///
+/// Running task runners to list their tasks (`just --dump`, `make -qp`, ...)
+/// can take a while, so the tasks a source found are stored on disk under the
+/// data directory. They are reused as long as the files they were read from
+/// (e.g. the justfile and the files it imports) and the task runner itself
+/// don't change. Files only known after discovery (e.g. makefiles included
+/// through a variable) are stored with the entry and checked when loading it. Keys are SHA-256 hashes, so they stay the same across builds.
+use crate::app::Task;
+use crate::config;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A source's cached tasks.
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Key of the files the tasks were read from
+    key: String,
+    /// Files discovery found the tasks to be read from, besides the ones in `key`
+    #[serde(default)]
+    inputs: Vec<PathBuf>,
+    /// Key of `inputs` when the tasks were stored
+    #[serde(default)]
+    inputs_key: String,
+    tasks: Vec<Task>,
+}
+
+/// Tasks of each source and project, stored as JSON files in a directory.
+#[derive(Debug, Clone)]
+pub struct DiscoveryCache {
+    dir: PathBuf,
+}
+
+impl DiscoveryCache {
+    /// Creates a cache storing its entries in `dir`.
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Opens the cache in the data directory, if there is one.
+    pub fn open() -> Option<Self> {
+        config::data_dir().map(|dir| Self::new(dir.join("discovery")))
+    }
+
+    /// Computes the key of the contents of `inputs`, read by the task runner
+    /// reporting `tool_version`. Missing files count as empty but distinct
+    /// from existing empty files, so creating an optional include invalidates
+    /// the cache too.
+    pub fn key(inputs: &[PathBuf], tool_version: Option<&str>) -> String {
+        let mut hasher = Sha256::new();
+        // Other versions of Taskpad or of the runner may read the same files differently
+        hash_field(&mut hasher, Some(env!("CARGO_PKG_VERSION").as_bytes()));
+        hash_field(&mut hasher, tool_version.map(str::as_bytes));
+        for path in inputs {
+            hash_field(&mut hasher, Some(path.as_os_str().as_encoded_bytes()));
+            hash_field(&mut hasher, std::fs::read(path).ok().as_deref());
+        }
+        hex(&hasher.finalize())
+    }
+
+    /// Returns the tasks cached for `source` in the project at `root` and the
+    /// files they were found to be read from, if they were stored with `key`
+    /// and those files haven't changed since.
+    pub fn load(&self, source: &str, root: &Path, key: &str) -> Option<(Vec<Task>, Vec<PathBuf>)> {
+        let contents = std::fs::read_to_string(self.entry_path(source, root)).ok()?;
+        let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+        (entry.key == key && entry.inputs_key == Self::key(&entry.inputs, None))
+            .then_some((entry.tasks, entry.inputs))
+    }
+
+    /// Stores the tasks `source` found in the project at `root` under `key`,
+    /// along with the files discovery found them to be read from (`inputs`).
+    ///
+    /// The cache is only an optimization, so failing to write it is ignored.
+    pub fn store(&self, source: &str, root: &Path, key: &str, tasks: &[Task], inputs: &[PathBuf]) {
+        let entry = CacheEntry {
+            key: key.to_string(),
+            inputs: inputs.to_vec(),
+            inputs_key: Self::key(inputs, None),
+            tasks: tasks.to_vec(),
+        };
+        if let Ok(contents) = serde_json::to_string(&entry) {
+            let _ = std::fs::create_dir_all(&self.dir);
+            let _ = std::fs::write(self.entry_path(source, root), contents);
+        }
+    }
+
+    /// Returns the file caching the tasks of `source` in the project at `root`.
+    fn entry_path(&self, source: &str, root: &Path) -> PathBuf {
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let hash = Sha256::digest(root.as_os_str().as_encoded_bytes());
+        self.dir
+            .join(format!("{}-{}.json", hex(&hash[..8]), source))
+    }
+}
+
+/// Returns the `--version` output of `program`, or `None` if it can't be run.
+pub fn tool_version(program: &str) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Adds a field that may be missing to `hasher`, length-prefixed so that
+/// consecutive fields can't run into each other.
+fn hash_field(hasher: &mut Sha256, field: Option<&[u8]>) {
+    match field {
+        Some(bytes) => {
+            hasher.update([1]);
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        }
+        None => hasher.update([0]),
+    }
+}
+
+/// Formats bytes as lowercase hexadecimal.
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::TaskRunner;
+    use crate::test_util::scratch_dir;
+    use std::fs;
+
+    #[test]
+    fn test_key_changes_with_inputs() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let justfile = dir.join("justfile");
+        let import = dir.join("extra.just");
+        let inputs = vec![justfile.clone(), import.clone()];
+
+        fs::write(&justfile, "build:\n    cargo build\n").unwrap();
+        let key = DiscoveryCache::key(&inputs, Some("just 1.40.0"));
+        assert_eq!(DiscoveryCache::key(&inputs, Some("just 1.40.0")), key);
+        assert_eq!(key.len(), 64);
+
+        // Upgrading the runner changes the key
+        assert_ne!(DiscoveryCache::key(&inputs, Some("just 1.41.0")), key);
+        assert_ne!(DiscoveryCache::key(&inputs, None), key);
+
+        // A missing input that appears changes the key, even if it is empty
+        fs::write(&import, "").unwrap();
+        let with_import = DiscoveryCache::key(&inputs, Some("just 1.40.0"));
+        assert_ne!(with_import, key);
+
+        fs::write(&justfile, "test:\n    cargo test\n").unwrap();
+        assert_ne!(
+            DiscoveryCache::key(&inputs, Some("just 1.40.0")),
+            with_import
+        );
+    }
+
+    #[test]
+    fn test_entry_path_is_stable() {
+        // The entry of a project must not move between builds of Taskpad
+        let cache = DiscoveryCache::new(PathBuf::from("cache"));
+        assert_eq!(
+            cache.entry_path("just", Path::new("/taskpad/missing/project")),
+            PathBuf::from("cache/006c02af463dd040-just.json")
+        );
+    }
+
+    #[test]
+    fn test_tool_version() {
+        assert!(tool_version("cargo").unwrap().starts_with("cargo "));
+        assert_eq!(tool_version("taskpad-missing-tool"), None);
+    }
+
+    #[test]
+    fn test_store_and_load() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let cache = DiscoveryCache::new(dir.join("cache"));
+        let (project, other) = (dir.join("project"), dir.join("other"));
+        fs::create_dir_all(&project).unwrap();
+        fs::create_dir_all(&other).unwrap();
+
+        assert!(cache.load("just", &project, "k1").is_none());
+        let include = dir.join("extra.mk");
+        fs::write(&include, "lint:\n").unwrap();
+
+        let tasks = vec![Task {
+            id: 3,
+            name: "build".to_string(),
+            runner: TaskRunner::Just,
+            body: vec!["cargo build".to_string()],
+            ..Default::default()
+        }];
+        cache.store(
+            "just",
+            &project,
+            "k1",
+            &tasks,
+            std::slice::from_ref(&include),
+        );
+
+        let (cached, inputs) = cache.load("just", &project, "k1").unwrap();
+        assert_eq!(inputs, vec![include.clone()]);
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].name, "build");
+        assert_eq!(cached[0].runner, TaskRunner::Just);
+        assert_eq!(cached[0].body, vec!["cargo build"]);
+
+        // Entries are per key, source and project
+        assert!(cache.load("just", &project, "k2").is_none());
+        assert!(cache.load("make", &project, "k1").is_none());
+        assert!(cache.load("just", &other, "k1").is_none());
+
+        // Changing a file found during discovery invalidates the entry too
+        fs::write(&include, "lint:\ntest:\n").unwrap();
+        assert!(cache.load("just", &project, "k1").is_none());
+    }
+}
@@ -0,0 +1,411 @@
+/// Cargo task discovery module.
+///
+/// This module discovers tasks for Rust projects: `[alias]` entries from
+/// `.cargo/config.toml`, `cargo xtask` and the binaries and examples of the
+/// workspace members reported by `cargo metadata`.
+use crate::app::{ParamKind, Task, TaskParam, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::TaskSource;
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// An alias from cargo's `[alias]` table and the arguments it expands to.
+type Alias = (String, Vec<String>);
+
+/// The parts of a cargo config file used for discovery.
+#[derive(Debug, Default, Deserialize)]
+struct CargoConfig {
+    #[serde(default)]
+    alias: BTreeMap<String, AliasValue>,
+}
+
+/// The value of an alias: a command line or a list of arguments.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum AliasValue {
+    Line(String),
+    Args(Vec<String>),
+}
+
+/// Output of `cargo metadata --no-deps --format-version 1`.
+#[derive(Debug, Deserialize)]
+struct Metadata {
+    /// Workspace members (only those with `--no-deps`)
+    packages: Vec<MetadataPackage>,
+}
+
+/// A package from `cargo metadata`.
+#[derive(Debug, Deserialize)]
+struct MetadataPackage {
+    name: String,
+    targets: Vec<MetadataTarget>,
+}
+
+/// A build target of a package (library, binary, example, ...).
+#[derive(Debug, Deserialize)]
+struct MetadataTarget {
+    name: String,
+    kind: Vec<String>,
+    #[serde(rename = "required-features", default)]
+    required_features: Vec<String>,
+}
+
+/// Discovers Cargo tasks in the current directory.
+///
+/// This function:
+/// 1. Checks for a `Cargo.toml` in the current directory
+/// 2. Reads `[alias]` entries from `.cargo/config.toml` here and in parent directories
+/// 3. Runs `cargo metadata --no-deps` to list the workspace's binaries and examples
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if:
+/// - There is no Cargo.toml in the current directory
+/// - `cargo` is not installed or not on PATH
+/// - `cargo metadata` fails (e.g. the manifest is invalid)
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks() -> Result<Vec<Task>> {
+    if !Path::new("Cargo.toml").is_file() {
+        return Err(eyre!("No Cargo.toml found in this directory."));
+    }
+
+    let aliases = std::env::current_dir()
+        .map(|dir| collect_aliases(&dir))
+        .unwrap_or_default();
+
+    let output = Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .output()
+        .map_err(|_| eyre!("cargo not found on PATH. Please install Rust and try again."))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("cargo metadata failed: {}", stderr.trim()));
+    }
+
+    let metadata = parse_metadata(&String::from_utf8_lossy(&output.stdout))?;
+    let tasks = cargo_tasks(&aliases, &metadata);
+
+    if tasks.is_empty() {
+        return Err(eyre!("No cargo aliases, binaries or examples discovered."));
+    }
+
+    Ok(tasks)
+}
+
+/// Collects cargo aliases the way cargo resolves its configuration: from
+/// `.cargo/config.toml` (or the legacy `.cargo/config`) in `dir` and each of
+/// its parents, with the nearest definition of an alias winning.
+fn collect_aliases(dir: &Path) -> Vec<Alias> {
+    let mut aliases: Vec<Alias> = Vec::new();
+
+    for ancestor in dir.ancestors() {
+        for file in ["config.toml", "config"] {
+            let Ok(source) = std::fs::read_to_string(ancestor.join(".cargo").join(file)) else {
+                continue;
+            };
+            for (name, args) in parse_aliases(&source).unwrap_or_default() {
+                if !aliases.iter().any(|(existing, _)| *existing == name) {
+                    aliases.push((name, args));
+                }
+            }
+        }
+    }
+
+    aliases.sort_by(|a, b| a.0.cmp(&b.0));
+    aliases
+}
+
+/// Parses the `[alias]` table of a cargo config file.
+///
+/// Aliases are either a string, split on whitespace like cargo does, or an
+/// array of arguments:
+/// ```text
+/// [alias]
+/// xtask = "run --package xtask --"
+/// ci = ["clippy", "--all-targets", "--", "-D", "warnings"]
+/// ```
+fn parse_aliases(source: &str) -> Result<Vec<Alias>> {
+    let config: CargoConfig =
+        toml::from_str(source).map_err(|e| eyre!("Failed to parse cargo config: {}", e))?;
+
+    Ok(config
+        .alias
+        .into_iter()
+        .map(|(name, value)| {
+            let args = match value {
+                AliasValue::Line(line) => line.split_whitespace().map(str::to_string).collect(),
+                AliasValue::Args(args) => args,
+            };
+            (name, args)
+        })
+        .collect())
+}
+
+/// Parses the output of `cargo metadata --format-version 1`.
+fn parse_metadata(output: &str) -> Result<Metadata> {
+    serde_json::from_str(output).map_err(|e| eyre!("Failed to parse cargo metadata: {}", e))
+}
+
+/// Builds the task list from the aliases and workspace metadata.
+///
+/// Aliases come first (named after the alias), followed by `xtask` and the
+/// binaries and examples grouped in `bin` and `example` modules. `cargo xtask`
+/// always takes a subcommand, so that task asks for its arguments.
+fn cargo_tasks(aliases: &[Alias], metadata: &Metadata) -> Vec<Task> {
+    let mut tasks = Vec::new();
+
+    for (name, args) in aliases {
+        tasks.push(cargo_task(name, vec![name.clone()], Some(args), &[]));
+    }
+
+    let has_xtask_package = metadata.packages.iter().any(|p| p.name == "xtask");
+    match tasks.iter_mut().find(|t| t.name == "xtask") {
+        Some(task) => task.params = vec![xtask_param()],
+        None if has_xtask_package => {
+            let mut task = cargo_task(
+                "xtask",
+                vec![
+                    "run".into(),
+                    "--package".into(),
+                    "xtask".into(),
+                    "--".into(),
+                ],
+                None,
+                &[],
+            );
+            task.params = vec![xtask_param()];
+            tasks.push(task);
+        }
+        None => {}
+    }
+
+    for (kind, flag) in [("bin", "--bin"), ("example", "--example")] {
+        for package in &metadata.packages {
+            // The xtask package is run through the xtask task
+            if package.name == "xtask" {
+                continue;
+            }
+            for target in package
+                .targets
+                .iter()
+                .filter(|t| t.kind.iter().any(|k| k == kind))
+            {
+                let mut command = vec![
+                    "run".to_string(),
+                    "--package".to_string(),
+                    package.name.clone(),
+                    flag.to_string(),
+                    target.name.clone(),
+                ];
+                if !target.required_features.is_empty() {
+                    command.push("--features".to_string());
+                    command.push(target.required_features.join(","));
+                }
+                tasks.push(cargo_task(
+                    &format!("{}::{}", kind, target.name),
+                    command,
+                    None,
+                    &[kind],
+                ));
+            }
+        }
+    }
+
+    for (id, task) in tasks.iter_mut().enumerate() {
+        task.id = id;
+    }
+    tasks
+}
+
+/// Creates a cargo task running `cargo <command>`, described by the command
+/// line it runs (including what an alias expands to).
+fn cargo_task(
+    name: &str,
+    command: Vec<String>,
+    expansion: Option<&[String]>,
+    module: &[&str],
+) -> Task {
+    let mut description = format!("cargo {}", command.join(" "));
+    if let Some(expansion) = expansion {
+        description.push_str(&format!(" (cargo {})", expansion.join(" ")));
+    }
+
+    Task {
+        name: name.to_string(),
+        description: Some(description),
+        runner: TaskRunner::Cargo,
+        command,
+        module_path: module.iter().map(|m| m.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+/// The arguments of `cargo xtask` (its subcommand and options).
+fn xtask_param() -> TaskParam {
+    TaskParam {
+        name: "args".to_string(),
+        kind: ParamKind::Plus,
+        ..Default::default()
+    }
+}
+
+/// Cargo aliases, xtask, binaries and examples.
+pub struct CargoSource;
+
+impl TaskSource for CargoSource {
+    fn name(&self) -> &'static str {
+        "cargo"
+    }
+
+    fn describe(&self) -> &'static str {
+        "Cargo aliases, binaries and examples"
+    }
+
+    fn detect(&self, root: &Path, _config: &Config) -> bool {
+        root.join("Cargo.toml").is_file()
+    }
+
+    fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+        discover_tasks()
+    }
+
+    fn watched_files(&self, root: &Path, _config: &Config) -> Vec<PathBuf> {
+        ["Cargo.toml", ".cargo/config.toml", ".cargo/config"]
+            .iter()
+            .map(|name| root.join(name))
+            .collect()
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::Cargo
+    }
+
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        let mut command = Command::new("cargo");
+        command.args(&task.command).args(args);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_aliases() {
+        let source = r#"
+[alias]
+xtask = "run --package xtask --"
+ci = ["clippy", "--all-targets", "--", "-D", "warnings"]
+b = "build"
+Lint = "clippy"
+
+[build]
+jobs = 4
+"#;
+
+        let aliases = parse_aliases(source).unwrap();
+        assert_eq!(
+            aliases,
+            vec![
+                ("Lint".to_string(), vec!["clippy".to_string()]),
+                ("b".to_string(), vec!["build".to_string()]),
+                (
+                    "ci".to_string(),
+                    vec!["clippy", "--all-targets", "--", "-D", "warnings"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                ),
+                (
+                    "xtask".to_string(),
+                    vec!["run", "--package", "xtask", "--"]
+                        .into_iter()
+                        .map(String::from)
+                        .collect()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_aliases_without_table() {
+        assert!(parse_aliases("[build]\njobs = 4\n").unwrap().is_empty());
+        assert!(parse_aliases("").unwrap().is_empty());
+        assert!(parse_aliases("[alias\n").is_err());
+    }
+
+    #[test]
+    fn test_cargo_tasks_from_metadata() {
+        let metadata = parse_metadata(include_str!("fixtures/cargo/workspace.json")).unwrap();
+
+        let tasks = cargo_tasks(&[], &metadata);
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["xtask", "bin::admin", "bin::app", "example::demo"]
+        );
+        assert!(tasks.iter().enumerate().all(|(i, t)| t.id == i));
+        assert!(tasks.iter().all(|t| t.runner == TaskRunner::Cargo));
+
+        let xtask = &tasks[0];
+        assert_eq!(xtask.command, vec!["run", "--package", "xtask", "--"]);
+        assert_eq!(xtask.params[0].kind, ParamKind::Plus);
+        assert!(xtask.module_path.is_empty());
+
+        let admin = &tasks[1];
+        assert_eq!(admin.short_name(), "admin");
+        assert_eq!(admin.module_path, vec!["bin"]);
+        assert_eq!(
+            admin.description,
+            Some("cargo run --package app --bin admin --features cli".to_string())
+        );
+
+        let demo = &tasks[3];
+        assert_eq!(demo.module_path, vec!["example"]);
+        assert_eq!(
+            demo.command,
+            vec!["run", "--package", "app", "--example", "demo"]
+        );
+        assert!(demo.params.is_empty());
+    }
+
+    #[test]
+    fn test_cargo_tasks_with_aliases() {
+        let metadata = parse_metadata(include_str!("fixtures/cargo/workspace.json")).unwrap();
+        let aliases = vec![
+            (
+                "ci".to_string(),
+                vec!["clippy".to_string(), "--all-targets".to_string()],
+            ),
+            (
+                "xtask".to_string(),
+                vec!["run".to_string(), "-p".to_string(), "xtask".to_string()],
+            ),
+        ];
+
+        let tasks = cargo_tasks(&aliases, &metadata);
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        // The xtask alias replaces the generated xtask task
+        assert_eq!(
+            names,
+            vec!["ci", "xtask", "bin::admin", "bin::app", "example::demo"]
+        );
+
+        assert_eq!(tasks[0].command, vec!["ci"]);
+        assert_eq!(
+            tasks[0].description,
+            Some("cargo ci (cargo clippy --all-targets)".to_string())
+        );
+        assert!(tasks[0].params.is_empty());
+        assert_eq!(tasks[1].command, vec!["xtask"]);
+        assert_eq!(tasks[1].params[0].kind, ParamKind::Plus);
+    }
+}
@@ -0,0 +1,382 @@
+/// Docker Compose task discovery module.
+///
+/// This module turns the services of a `compose.yaml` (or `docker-compose.yml`)
+/// into tasks starting, stopping, restarting and following the logs of each
+/// service with `docker compose`.
+use crate::app::{Task, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::{defined_in, TaskSource};
+use color_eyre::eyre::{eyre, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// Compose files `docker compose` looks for, in its order of preference.
+const COMPOSE_FILES: &[&str] = &[
+    "compose.yaml",
+    "compose.yml",
+    "docker-compose.yaml",
+    "docker-compose.yml",
+];
+
+/// The tasks created for each service: name, `docker compose` arguments
+/// (followed by the service) and description.
+const SERVICE_ACTIONS: &[(&str, &[&str], &str)] = &[
+    ("up", &["up", "--detach"], "Start"),
+    ("down", &["down"], "Stop and remove"),
+    ("restart", &["restart"], "Restart"),
+    ("logs", &["logs", "--follow"], "Follow the logs of"),
+];
+
+/// A service from the compose file.
+#[derive(Debug, PartialEq)]
+struct Service {
+    name: String,
+    /// Image the service runs, or `None` if it is built from a Dockerfile
+    image: Option<String>,
+    depends_on: Vec<String>,
+}
+
+/// Discovers docker compose services in the current directory.
+///
+/// This function:
+/// 1. Checks for a compose file in the current directory
+/// 2. Runs `docker compose config --format json` to resolve the file (includes,
+///    extends and variables), reading the compose file itself if that fails
+/// 3. Creates `up`, `down`, `restart` and `logs` tasks for each service
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if:
+/// - There is no compose file in the current directory
+/// - `docker` is not installed or not on PATH
+/// - The compose file can't be parsed or defines no services
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks() -> Result<Vec<Task>> {
+    discover_tasks_with(Path::new("."), Path::new("docker"))
+}
+
+/// Discovers the compose services in `root`, running `docker` to resolve them.
+fn discover_tasks_with(root: &Path, docker: &Path) -> Result<Vec<Task>> {
+    let Some(file) = COMPOSE_FILES
+        .iter()
+        .map(|name| root.join(name))
+        .find(|path| path.is_file())
+    else {
+        return Err(eyre!("No compose file found in this directory."));
+    };
+
+    let output = Command::new(docker)
+        .args(["compose", "config", "--format", "json"])
+        .current_dir(root)
+        .output()
+        .map_err(|_| eyre!("docker not found on PATH. Please install Docker and try again."))?;
+
+    // Older versions of compose can't print JSON, and `config` fails on
+    // variables missing from the environment
+    let source = match output.status.success() {
+        true => String::from_utf8_lossy(&output.stdout).into_owned(),
+        false => std::fs::read_to_string(&file)
+            .map_err(|e| eyre!("Failed to read {}: {}", file.display(), e))?,
+    };
+
+    let services = parse_services(&source)?;
+    if services.is_empty() {
+        return Err(eyre!("No services defined in {}.", file.display()));
+    }
+
+    Ok(defined_in(service_tasks(&services), Some(file)))
+}
+
+/// Parses the services of a compose file, or of the JSON printed by
+/// `docker compose config` (which YAML parses as well).
+fn parse_services(source: &str) -> Result<Vec<Service>> {
+    let documents = YamlLoader::load_from_str(source)
+        .map_err(|e| eyre!("Failed to parse compose file: {}", e))?;
+    let Some(services) = documents.first().and_then(|doc| doc["services"].as_hash()) else {
+        return Ok(Vec::new());
+    };
+
+    Ok(services
+        .iter()
+        .filter_map(|(name, service)| {
+            // `depends_on` is a list of services or a map of service to condition
+            let depends_on = match &service["depends_on"] {
+                Yaml::Array(services) => services
+                    .iter()
+                    .filter_map(|s| s.as_str().map(str::to_string))
+                    .collect(),
+                Yaml::Hash(services) => services
+                    .keys()
+                    .filter_map(|s| s.as_str().map(str::to_string))
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            Some(Service {
+                name: name.as_str()?.to_string(),
+                image: service["image"].as_str().map(str::to_string),
+                depends_on,
+            })
+        })
+        .collect())
+}
+
+/// Creates the tasks of each service, grouped in a module named after the
+/// service (`web:up`, `web:down`, ...).
+///
+/// `up` lists the services it starts along with the service as dependencies.
+fn service_tasks(services: &[Service]) -> Vec<Task> {
+    let mut tasks = Vec::new();
+
+    for service in services {
+        for (action, args, summary) in SERVICE_ACTIONS {
+            let mut command: Vec<String> = std::iter::once("compose")
+                .chain(args.iter().copied())
+                .map(str::to_string)
+                .collect();
+            command.push(service.name.clone());
+
+            let mut description = format!("{} {}", summary, service.name);
+            if let Some(image) = &service.image {
+                description.push_str(&format!(" ({})", image));
+            }
+            description.push_str(&format!("\ndocker {}", command.join(" ")));
+
+            tasks.push(Task {
+                id: tasks.len(),
+                name: format!("{}:{}", service.name, action),
+                description: Some(description),
+                runner: TaskRunner::Compose,
+                dependencies: match *action {
+                    "up" => service.depends_on.clone(),
+                    _ => Vec::new(),
+                },
+                module_path: vec![service.name.clone()],
+                command,
+                ..Default::default()
+            });
+        }
+    }
+
+    tasks
+}
+
+/// Services of the compose file.
+pub struct ComposeSource;
+
+impl TaskSource for ComposeSource {
+    fn name(&self) -> &'static str {
+        "compose"
+    }
+
+    fn describe(&self) -> &'static str {
+        "docker compose services"
+    }
+
+    fn detect(&self, root: &Path, _config: &Config) -> bool {
+        COMPOSE_FILES.iter().any(|name| root.join(name).is_file())
+    }
+
+    fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+        discover_tasks()
+    }
+
+    fn watched_files(&self, root: &Path, _config: &Config) -> Vec<PathBuf> {
+        COMPOSE_FILES.iter().map(|name| root.join(name)).collect()
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::Compose
+    }
+
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        let mut command = Command::new("docker");
+        command.args(&task.command).args(args);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{AppState, ModuleKey, TaskRow};
+    use crate::test_util::scratch_dir;
+    use std::fs;
+
+    /// Writes a `docker` stub printing `stdout` and exiting with `status`.
+    #[cfg(unix)]
+    fn stub_docker(dir: &Path, stdout: &str, status: i32) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let stub = dir.join("docker");
+        fs::write(
+            &stub,
+            format!("#!/bin/sh\ncat <<'EOF'\n{}\nEOF\nexit {}\n", stdout, status),
+        )
+        .unwrap();
+        fs::set_permissions(&stub, fs::Permissions::from_mode(0o755)).unwrap();
+        stub
+    }
+
+    #[test]
+    fn test_parse_services_from_compose_file() {
+        let services = parse_services(include_str!("fixtures/compose/compose.yaml")).unwrap();
+
+        assert_eq!(
+            services,
+            vec![
+                Service {
+                    name: "web".to_string(),
+                    image: None,
+                    depends_on: vec!["db".to_string(), "cache".to_string()],
+                },
+                Service {
+                    name: "db".to_string(),
+                    image: Some("postgres:16".to_string()),
+                    depends_on: Vec::new(),
+                },
+                Service {
+                    name: "cache".to_string(),
+                    image: Some("redis:7".to_string()),
+                    depends_on: Vec::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_services_from_config_json() {
+        let services = parse_services(include_str!("fixtures/compose/config.json")).unwrap();
+
+        let names: Vec<&str> = services.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["cache", "db", "web"]);
+        assert_eq!(services[2].depends_on, vec!["cache", "db"]);
+        assert_eq!(services[1].image, Some("postgres:16".to_string()));
+    }
+
+    #[test]
+    fn test_parse_services_without_services() {
+        assert!(parse_services("name: app\n").unwrap().is_empty());
+        assert!(parse_services("").unwrap().is_empty());
+        assert!(parse_services("services: [").is_err());
+    }
+
+    #[test]
+    fn test_service_tasks() {
+        let tasks = service_tasks(&[Service {
+            name: "db".to_string(),
+            image: Some("postgres:16".to_string()),
+            depends_on: vec!["volumes".to_string()],
+        }]);
+
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["db:up", "db:down", "db:restart", "db:logs"]);
+        assert!(tasks.iter().enumerate().all(|(i, t)| t.id == i));
+        assert!(tasks.iter().all(|t| t.runner == TaskRunner::Compose));
+        assert!(tasks.iter().all(|t| t.module_path == vec!["db"]));
+
+        assert_eq!(tasks[0].command, vec!["compose", "up", "--detach", "db"]);
+        assert_eq!(tasks[0].short_name(), "up");
+        assert_eq!(tasks[0].dependencies, vec!["volumes"]);
+        assert_eq!(
+            tasks[0].description,
+            Some("Start db (postgres:16)\ndocker compose up --detach db".to_string())
+        );
+        assert!(tasks[1].dependencies.is_empty());
+        assert_eq!(tasks[3].command, vec!["compose", "logs", "--follow", "db"]);
+    }
+
+    #[test]
+    fn test_service_modules_stay_apart_from_just_modules() {
+        let just = Task {
+            name: "db::migrate".to_string(),
+            runner: TaskRunner::Just,
+            module_path: vec!["db".to_string()],
+            ..Default::default()
+        };
+        let mut tasks = vec![just.clone()];
+        tasks.extend(service_tasks(&[Service {
+            name: "db".to_string(),
+            image: None,
+            depends_on: Vec::new(),
+        }]));
+        let db = |runner| ModuleKey {
+            runner,
+            path: vec!["db".to_string()],
+        };
+
+        let mut app = AppState::new(tasks);
+        assert_eq!(
+            app.task_rows,
+            vec![
+                TaskRow::Module {
+                    module: db(TaskRunner::Just),
+                    expanded: false,
+                },
+                TaskRow::Module {
+                    module: db(TaskRunner::Compose),
+                    expanded: false,
+                },
+            ]
+        );
+
+        // Running the just recipe doesn't mark the service as running
+        app.start_task(just);
+        assert!(app.is_row_running(&app.task_rows[0].clone()));
+        assert!(!app.is_row_running(&app.task_rows[1].clone()));
+
+        // Expanding the service lists its actions only
+        app.selected_index = 1;
+        assert!(app.toggle_selected_module());
+        assert_eq!(app.task_rows.len(), 6);
+        assert!(!app.expanded_modules.contains(&db(TaskRunner::Just)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discover_tasks_with_docker_config() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        fs::write(dir.join("compose.yaml"), "services:\n  ignored:\n").unwrap();
+        let docker = stub_docker(dir, include_str!("fixtures/compose/config.json"), 0);
+
+        // The services come from `docker compose config`, not the file
+        let tasks = discover_tasks_with(dir, &docker).unwrap();
+        assert_eq!(tasks.len(), 12);
+        assert_eq!(tasks[0].name, "cache:up");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_discover_tasks_falls_back_to_compose_file() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        fs::write(
+            dir.join("docker-compose.yml"),
+            include_str!("fixtures/compose/compose.yaml"),
+        )
+        .unwrap();
+        let docker = stub_docker(dir, "unknown flag: --format", 1);
+
+        let tasks = discover_tasks_with(dir, &docker).unwrap();
+        assert_eq!(tasks.len(), 12);
+        assert_eq!(tasks[0].name, "web:up");
+        assert_eq!(tasks[0].dependencies, vec!["db", "cache"]);
+    }
+
+    #[test]
+    fn test_discover_tasks_errors() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let missing = dir.join("no-docker");
+        assert!(discover_tasks_with(dir, &missing).is_err());
+
+        fs::write(dir.join("compose.yaml"), "services:\n  db:\n").unwrap();
+        let err = discover_tasks_with(dir, &missing).unwrap_err();
+        assert!(err.to_string().contains("docker not found"));
+    }
+}
@@ -0,0 +1,147 @@
+/// Custom task module.
+///
+/// This module turns the tasks defined in the Taskpad config files (see
+/// [`crate::config`]) into tasks. A task's `command` runs through the shell,
+/// while its `argv` runs directly.
+use crate::app::{Task, TaskRunner};
+use crate::config::{Config, CustomTask};
+use crate::tasks::registry::TaskSource;
+use color_eyre::eyre::{eyre, Result};
+use std::path::Path;
+
+/// Returns the custom tasks defined in `config`.
+///
+/// # Errors
+///
+/// Returns an error if the config defines no tasks.
+pub fn discover_tasks(config: &Config) -> Result<Vec<Task>> {
+    if config.tasks.is_empty() {
+        return Err(eyre!("No custom tasks configured."));
+    }
+
+    Ok(config
+        .tasks
+        .iter()
+        .enumerate()
+        .map(|(id, task)| custom_task(id, task))
+        .collect())
+}
+
+/// Creates the task running a custom task definition.
+fn custom_task(id: usize, task: &CustomTask) -> Task {
+    let (command, body) = match (&task.command, &task.argv) {
+        (Some(line), _) => {
+            let shell: &[&str] = if cfg!(windows) {
+                &["cmd", "/C"]
+            } else {
+                &["sh", "-c"]
+            };
+            let mut command: Vec<String> = shell.iter().map(|s| s.to_string()).collect();
+            command.push(line.clone());
+            (command, line.clone())
+        }
+        (None, argv) => {
+            let argv = argv.clone().unwrap_or_default();
+            let body = argv.join(" ");
+            (argv, body)
+        }
+    };
+
+    Task {
+        id,
+        name: task.name.clone(),
+        description: task.description.clone(),
+        runner: TaskRunner::Custom,
+        groups: task.group.iter().cloned().collect(),
+        body: vec![body],
+        cwd: task.cwd.clone(),
+        env: task.env.clone().into_iter().collect(),
+        command,
+        ..Default::default()
+    }
+}
+
+/// Custom tasks from the config.
+pub struct CustomSource;
+
+impl TaskSource for CustomSource {
+    fn name(&self) -> &'static str {
+        "custom"
+    }
+
+    fn describe(&self) -> &'static str {
+        "custom tasks from the config"
+    }
+
+    fn detect(&self, _root: &Path, config: &Config) -> bool {
+        !config.tasks.is_empty()
+    }
+
+    fn marks_project(&self, _dir: &Path) -> bool {
+        // Detected by the config, which is read from the project directory
+        false
+    }
+
+    fn discover(&self, config: &Config) -> Result<Vec<Task>> {
+        discover_tasks(config)
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::Custom
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_custom_tasks() {
+        let config = Config {
+            tasks: vec![
+                CustomTask {
+                    name: "serve".to_string(),
+                    command: Some("python3 -m http.server $PORT".to_string()),
+                    cwd: Some(PathBuf::from("site")),
+                    env: [("PORT".to_string(), "8000".to_string())].into(),
+                    description: Some("Serve the site".to_string()),
+                    group: Some("dev".to_string()),
+                    ..Default::default()
+                },
+                CustomTask {
+                    name: "tail".to_string(),
+                    argv: Some(vec![
+                        "tail".to_string(),
+                        "-f".to_string(),
+                        "app.log".to_string(),
+                    ]),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let tasks = discover_tasks(&config).unwrap();
+        assert!(tasks.iter().enumerate().all(|(i, t)| t.id == i));
+        assert!(tasks.iter().all(|t| t.runner == TaskRunner::Custom));
+
+        let serve = &tasks[0];
+        assert_eq!(
+            serve.command.last().unwrap(),
+            "python3 -m http.server $PORT"
+        );
+        assert_eq!(serve.body, vec!["python3 -m http.server $PORT"]);
+        assert_eq!(serve.cwd, Some(PathBuf::from("site")));
+        assert_eq!(serve.env, vec![("PORT".to_string(), "8000".to_string())]);
+        assert_eq!(serve.groups, vec!["dev"]);
+        assert_eq!(serve.description, Some("Serve the site".to_string()));
+
+        let tail = &tasks[1];
+        assert_eq!(tail.command, vec!["tail", "-f", "app.log"]);
+        assert!(tail.groups.is_empty());
+        assert!(tail.env.is_empty());
+
+        assert!(discover_tasks(&Config::default()).is_err());
+    }
+}
@@ -0,0 +1,513 @@
+/// External task source module.
+///
+/// Task sources can live outside Taskpad as executables named
+/// `taskpad-source-<name>` on PATH, or listed under `external_sources` in the
+/// config. Taskpad runs each of them in the project directory, writes a
+/// request to its stdin and reads the tasks it found from its stdout:
+///
+/// ```text
+/// stdin:  {"version": 1, "root": "/home/user/project"}
+/// stdout: {"tasks": [{"name": "build //app", "description": "Build the app",
+///           "argv": ["bazel", "build", "//app"], "cwd": "app",
+///           "env": {"CC": "clang"}, "group": "build"}]}
+/// ```
+///
+/// Only `name` and `argv` are required. Tasks run their `argv` directly, in
+/// `cwd` (relative to the project root) with `env` added to the environment.
+/// A source that doesn't answer within [`SOURCE_TIMEOUT`] is killed and
+/// reported as failed.
+use crate::app::{Task, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::TaskSource;
+use crate::tasks::scripts::is_executable;
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Prefix of the executables found on PATH.
+const SOURCE_PREFIX: &str = "taskpad-source-";
+
+/// How long a source may take to answer before it is killed.
+const SOURCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a running source is checked for having exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Version of the protocol sent to sources.
+const PROTOCOL_VERSION: u32 = 1;
+
+/// The request written to a source's stdin.
+#[derive(Debug, Serialize)]
+struct Request<'a> {
+    version: u32,
+    /// Absolute path of the project directory
+    root: &'a Path,
+}
+
+/// The response a source prints to stdout.
+#[derive(Debug, Deserialize)]
+struct Response {
+    tasks: Vec<ExternalTask>,
+}
+
+/// A task found by an external source.
+#[derive(Debug, Deserialize)]
+struct ExternalTask {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    /// Program and arguments that run the task
+    argv: Vec<String>,
+    #[serde(default)]
+    cwd: Option<PathBuf>,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    #[serde(default)]
+    group: Option<String>,
+}
+
+/// Discovers the tasks of every external source: the `taskpad-source-<name>`
+/// executables on PATH and those listed in the config.
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with the tasks of all sources that succeeded, or
+/// an error listing the failures if no source found any tasks.
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks(config: &Config) -> Result<Vec<Task>> {
+    let root = std::env::current_dir()
+        .map_err(|e| eyre!("Failed to read the current directory: {}", e))?;
+
+    let mut sources = config
+        .external_sources
+        .iter()
+        .map(|path| (source_name(path), path.clone()))
+        .collect::<Vec<_>>();
+    if let Some(path) = std::env::var_os("PATH") {
+        for (name, path) in find_on_path(&path) {
+            if !sources.iter().any(|(existing, _)| *existing == name) {
+                sources.push((name, path));
+            }
+        }
+    }
+
+    if sources.is_empty() {
+        return Err(eyre!("No external task sources found."));
+    }
+
+    // Sources run concurrently, so slow ones don't add up
+    let results: Vec<Result<Vec<Task>>> = thread::scope(|scope| {
+        let handles: Vec<_> = sources
+            .iter()
+            .map(|(name, path)| scope.spawn(|| run_source(name, path, &root, SOURCE_TIMEOUT)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|_| Err(eyre!("discovery panicked")))
+            })
+            .collect()
+    });
+
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+    for ((name, _), result) in sources.iter().zip(results) {
+        match result {
+            Ok(found) => tasks.extend(found),
+            Err(e) => errors.push(format!("{}: {}", name, e)),
+        }
+    }
+
+    if tasks.is_empty() {
+        return Err(eyre!(
+            "No tasks found by external sources.\n{}",
+            errors.join("\n")
+        ));
+    }
+
+    for (id, task) in tasks.iter_mut().enumerate() {
+        task.id = id;
+    }
+    Ok(tasks)
+}
+
+/// Finds the `taskpad-source-<name>` executables in the directories of a
+/// PATH value. The first executable of a name wins, like in a shell.
+fn find_on_path(path: &OsStr) -> Vec<(String, PathBuf)> {
+    let mut sources: Vec<(String, PathBuf)> = Vec::new();
+
+    for dir in std::env::split_paths(path) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut found: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(SOURCE_PREFIX))
+            })
+            .filter(|path| is_executable(path))
+            .collect();
+        found.sort();
+
+        for path in found {
+            let name = source_name(&path);
+            if !sources.iter().any(|(existing, _)| *existing == name) {
+                sources.push((name, path));
+            }
+        }
+    }
+
+    sources
+}
+
+/// Names a source after its executable: `taskpad-source-bazel` (or
+/// `taskpad-source-bazel.exe`) is `bazel`.
+fn source_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match stem.strip_prefix(SOURCE_PREFIX) {
+        Some(name) => name.to_string(),
+        None => stem,
+    }
+}
+
+/// Runs a source in `root` and returns the tasks it found.
+///
+/// A source that hasn't exited and closed its output after `timeout` is
+/// killed, along with anything it started.
+fn run_source(name: &str, path: &Path, root: &Path, timeout: Duration) -> Result<Vec<Task>> {
+    let mut command = Command::new(path);
+    command
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        // Its own process group, so a timeout can kill what it spawned too
+        command.process_group(0);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| eyre!("Failed to run {}: {}", path.display(), e))?;
+
+    let request = serde_json::to_string(&Request {
+        version: PROTOCOL_VERSION,
+        root,
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Sources are free to ignore the request and exit without reading it
+        let _ = stdin.write_all(request.as_bytes());
+    }
+
+    // Read the pipes on their own threads, so a source filling them doesn't
+    // block while it is waited for
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    let timed_out = || eyre!("{} timed out after {:?}", path.display(), timeout);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                kill_group(child.id());
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(timed_out());
+            }
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Err(eyre!("Failed to run {}: {}", path.display(), e)),
+        }
+    };
+
+    // Something the source started may still hold its output open
+    let remaining = || deadline.saturating_duration_since(Instant::now());
+    let (Ok(stdout), Ok(stderr)) = (
+        stdout.recv_timeout(remaining()),
+        stderr.recv_timeout(remaining()),
+    ) else {
+        kill_group(child.id());
+        return Err(timed_out());
+    };
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Err(eyre!("{} failed: {}", path.display(), stderr.trim()));
+    }
+
+    parse_response(name, &String::from_utf8_lossy(&stdout))
+}
+
+/// Reads a child's pipe to the end on a new thread, returning the receiver
+/// of its contents.
+fn read_pipe(pipe: Option<impl Read + Send + 'static>) -> Receiver<Vec<u8>> {
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        let _ = tx.send(bytes);
+    });
+    rx
+}
+
+/// Kills the processes started by a source that timed out. The source leads
+/// its own process group (see `run_source`), which outlives the source as
+/// long as any of them is left.
+fn kill_group(pid: u32) {
+    #[cfg(unix)]
+    unsafe {
+        libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+    }
+    #[cfg(not(unix))]
+    let _ = pid;
+}
+
+/// Parses a source's response into tasks run by the source named `name`.
+fn parse_response(name: &str, output: &str) -> Result<Vec<Task>> {
+    let response: Response =
+        serde_json::from_str(output).map_err(|e| eyre!("Invalid response: {}", e))?;
+
+    response
+        .tasks
+        .into_iter()
+        .enumerate()
+        .map(|(id, task)| {
+            if task.name.is_empty() || task.argv.is_empty() {
+                return Err(eyre!("Task {} needs a name and an argv", id + 1));
+            }
+            Ok(Task {
+                id,
+                description: task.description.filter(|d| !d.is_empty()),
+                runner: TaskRunner::External(name.to_string()),
+                groups: task.group.into_iter().collect(),
+                body: vec![task.argv.join(" ")],
+                cwd: task.cwd,
+                env: task.env.into_iter().collect(),
+                command: task.argv,
+                name: task.name,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// External `taskpad-source-<name>` executables.
+pub struct ExternalSource;
+
+impl TaskSource for ExternalSource {
+    fn name(&self) -> &'static str {
+        "external"
+    }
+
+    fn describe(&self) -> &'static str {
+        "external taskpad-source-<name> executables"
+    }
+
+    fn detect(&self, _root: &Path, config: &Config) -> bool {
+        !config.external_sources.is_empty()
+            || std::env::var_os("PATH").is_some_and(|path| !find_on_path(&path).is_empty())
+    }
+
+    fn marks_project(&self, _dir: &Path) -> bool {
+        // Detected by the config and PATH, not by anything in the directory
+        false
+    }
+
+    fn discover(&self, config: &Config) -> Result<Vec<Task>> {
+        discover_tasks(config)
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        matches!(runner, TaskRunner::External(_))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_dir;
+    use std::fs;
+
+    /// Writes an executable shell script.
+    #[cfg(unix)]
+    fn write_script(path: &Path, source: &str) {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::write(path, source).unwrap();
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let tasks =
+            parse_response("bazel", include_str!("fixtures/external/response.json")).unwrap();
+
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["build //app", "test //app:unit"]);
+        assert!(tasks
+            .iter()
+            .all(|t| t.runner == TaskRunner::External("bazel".to_string())));
+        assert_eq!(tasks[0].runner.prefix(), "[bazel]");
+
+        let build = &tasks[0];
+        assert_eq!(build.description, Some("Build the app".to_string()));
+        assert_eq!(build.command, vec!["bazel", "build", "//app"]);
+        assert_eq!(build.cwd, Some(PathBuf::from("app")));
+        assert_eq!(build.env, vec![("CC".to_string(), "clang".to_string())]);
+        assert_eq!(build.groups, vec!["build"]);
+
+        let test = &tasks[1];
+        assert_eq!(test.description, None);
+        assert_eq!(test.cwd, None);
+        assert!(test.env.is_empty());
+    }
+
+    #[test]
+    fn test_parse_response_errors() {
+        assert!(parse_response("x", "not json").is_err());
+        assert!(parse_response("x", r#"{"tasks": [{"name": "a", "argv": []}]}"#).is_err());
+        assert!(parse_response("x", r#"{"tasks": [{"name": "a"}]}"#).is_err());
+        assert!(parse_response("x", r#"{"tasks": []}"#).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_source_name() {
+        assert_eq!(
+            source_name(Path::new("/usr/bin/taskpad-source-bazel")),
+            "bazel"
+        );
+        assert_eq!(source_name(Path::new("taskpad-source-buck.exe")), "buck");
+        assert_eq!(
+            source_name(Path::new("./tools/list-tasks.sh")),
+            "list-tasks"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_on_path() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let (first, second) = (dir.join("first"), dir.join("second"));
+        fs::create_dir_all(&first).unwrap();
+        fs::create_dir_all(&second).unwrap();
+        write_script(&first.join("taskpad-source-bazel"), "#!/bin/sh\n");
+        write_script(&second.join("taskpad-source-bazel"), "#!/bin/sh\n");
+        write_script(&second.join("taskpad-source-buck"), "#!/bin/sh\n");
+        write_script(&second.join("other-tool"), "#!/bin/sh\n");
+        fs::write(second.join("taskpad-source-docs"), "not executable").unwrap();
+
+        let path = std::env::join_paths([&first, &second, &dir.join("missing")]).unwrap();
+        let sources = find_on_path(&path);
+        assert_eq!(
+            sources,
+            vec![
+                ("bazel".to_string(), first.join("taskpad-source-bazel")),
+                ("buck".to_string(), second.join("taskpad-source-buck")),
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_source() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let source = dir.join("taskpad-source-echo");
+        // Saves the request in its working directory
+        write_script(
+            &source,
+            "#!/bin/sh\ncat > request.json\necho '{\"tasks\": [{\"name\": \"where\", \"argv\": [\"pwd\"]}]}'\n",
+        );
+
+        let tasks = run_source("echo", &source, dir, SOURCE_TIMEOUT).unwrap();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].name, "where");
+        assert_eq!(tasks[0].command, vec!["pwd"]);
+        assert_eq!(tasks[0].runner.prefix(), "[echo]");
+
+        let request: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("request.json")).unwrap()).unwrap();
+        assert_eq!(request["version"], 1);
+        assert_eq!(request["root"], dir.display().to_string());
+
+        let failing = dir.join("taskpad-source-broken");
+        write_script(&failing, "#!/bin/sh\necho 'no workspace' >&2\nexit 2\n");
+        let err = run_source("broken", &failing, dir, SOURCE_TIMEOUT).unwrap_err();
+        assert!(err.to_string().contains("no workspace"));
+    }
+
+    /// Waits up to a few seconds for the process with the pid in `pid_file`
+    /// to exit, returning false if it is still running.
+    #[cfg(unix)]
+    fn exits(pid_file: &Path) -> bool {
+        let pid = fs::read_to_string(pid_file).unwrap();
+        // Killed processes count as exited even before they are reaped
+        let alive = || {
+            let output = Command::new("ps")
+                .args(["-o", "stat=", "-p", pid.trim()])
+                .output()
+                .unwrap();
+            let stat = String::from_utf8_lossy(&output.stdout);
+            !stat.trim().is_empty() && !stat.trim().starts_with('Z')
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while alive() {
+            if Instant::now() >= deadline {
+                return false;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        true
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_source_times_out() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let timeout = Duration::from_millis(300);
+
+        // A source that hangs is killed, along with what it started
+        let hanging = dir.join("taskpad-source-hanging");
+        write_script(
+            &hanging,
+            "#!/bin/sh\nsleep 30 &\necho $! > hanging.pid\nwait\n",
+        );
+        let start = Instant::now();
+        let err = run_source("hanging", &hanging, dir, timeout).unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(exits(&dir.join("hanging.pid")));
+
+        // So is one that exits but leaves a process holding its output open
+        let detached = dir.join("taskpad-source-detached");
+        write_script(
+            &detached,
+            "#!/bin/sh\nsleep 30 &\necho $! > detached.pid\necho '{\"tasks\": []}'\n",
+        );
+        let err = run_source("detached", &detached, dir, timeout).unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+        assert!(exits(&dir.join("detached.pid")));
+    }
+}
@@ -0,0 +1,468 @@
+/// Task (go-task) discovery module.
+///
+/// This module discovers the tasks of a `Taskfile.yml` by running
+/// `task --list-all --json`. Tasks from included Taskfiles keep their
+/// namespace (`docker:build`), and the variables a task can be given on the
+/// command line are read from the Taskfiles so they can be prompted for.
+use crate::app::{ParamDefault, Task, TaskParam, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::{defined_in, TaskSource};
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use yaml_rust2::{Yaml, YamlLoader};
+
+/// File names Task looks for, in its order of preference.
+const TASKFILES: &[&str] = &[
+    "Taskfile.yml",
+    "taskfile.yml",
+    "Taskfile.yaml",
+    "taskfile.yaml",
+    "Taskfile.dist.yml",
+    "taskfile.dist.yml",
+    "Taskfile.dist.yaml",
+    "taskfile.dist.yaml",
+];
+
+/// Output of `task --list-all --json`.
+#[derive(Debug, Deserialize)]
+struct TaskList {
+    tasks: Vec<ListedTask>,
+}
+
+/// A task from `task --list-all --json`.
+#[derive(Debug, Deserialize)]
+struct ListedTask {
+    /// Name used to invoke the task, including its namespace
+    task: String,
+    #[serde(default)]
+    desc: String,
+    #[serde(default)]
+    summary: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    location: Option<TaskLocation>,
+}
+
+/// Where a task is defined.
+#[derive(Debug, Deserialize)]
+struct TaskLocation {
+    taskfile: PathBuf,
+}
+
+/// Discovers Task tasks in the current directory.
+///
+/// This function:
+/// 1. Checks for a Taskfile in the current directory
+/// 2. Runs `task --list-all --json` to list every task, including tasks
+///    without a description and tasks of included Taskfiles
+/// 3. Reads each task's Taskfile for the variables it can be given
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if:
+/// - There is no Taskfile in the current directory
+/// - `task` is not installed or not on PATH
+/// - `task --list-all --json` returns an error
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks() -> Result<Vec<Task>> {
+    // task searches parent directories too, but only this directory counts
+    if !TASKFILES.iter().any(|name| Path::new(name).is_file()) {
+        return Err(eyre!("No Taskfile found in this directory."));
+    }
+
+    let list_tasks = |args: &[&str]| {
+        Command::new("task")
+            .args(args)
+            .output()
+            .map_err(|_| eyre!("task not found on PATH. Please install Task and try again."))
+    };
+
+    // `--no-status` and `--sort none` need Task 3.24+, so fall back without them
+    let mut output = list_tasks(&["--list-all", "--json", "--no-status", "--sort", "none"])?;
+    if !output.status.success() {
+        output = list_tasks(&["--list-all", "--json"])?;
+    }
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("task --list-all failed: {}", stderr.trim()));
+    }
+
+    let list = parse_task_list(&String::from_utf8_lossy(&output.stdout))?;
+
+    // Each Taskfile is read once, however many tasks it defines
+    let mut taskfiles: HashMap<PathBuf, Option<Yaml>> = HashMap::new();
+    let mut tasks = Vec::new();
+    for (id, listed) in list.tasks.into_iter().enumerate() {
+        let taskfile = listed.location.as_ref().and_then(|location| {
+            taskfiles
+                .entry(location.taskfile.clone())
+                .or_insert_with(|| load_taskfile(&location.taskfile))
+                .as_ref()
+        });
+        let params = taskfile
+            .map(|taskfile| task_params(taskfile, &listed.task))
+            .unwrap_or_default();
+        tasks.push(listed_task_to_task(id, listed, params));
+    }
+
+    if tasks.is_empty() {
+        return Err(eyre!(
+            "No tasks discovered. Is there a Taskfile with tasks in this directory?"
+        ));
+    }
+
+    Ok(tasks)
+}
+
+/// Parses the output of `task --list-all --json`.
+fn parse_task_list(output: &str) -> Result<TaskList> {
+    serde_json::from_str(output).map_err(|e| eyre!("Failed to parse task --list-all output: {}", e))
+}
+
+/// Reads and parses a Taskfile, or returns `None` if it can't be read.
+fn load_taskfile(path: &Path) -> Option<Yaml> {
+    let source = std::fs::read_to_string(path).ok()?;
+    parse_taskfile(&source)
+}
+
+/// Parses the YAML of a Taskfile.
+fn parse_taskfile(source: &str) -> Option<Yaml> {
+    YamlLoader::load_from_str(source).ok()?.into_iter().next()
+}
+
+/// Converts a listed task into a task.
+///
+/// The namespace of tasks from included Taskfiles (`docker` in
+/// `docker:build`) becomes the task's module path. The description is the
+/// task's `desc`, followed by its `summary` if it has one.
+fn listed_task_to_task(id: usize, listed: ListedTask, params: Vec<TaskParam>) -> Task {
+    let mut module_path: Vec<String> = listed.task.split(':').map(str::to_string).collect();
+    module_path.pop();
+
+    let description = [listed.desc.trim(), listed.summary.trim()]
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    Task {
+        id,
+        name: listed.task,
+        description: (!description.is_empty()).then_some(description),
+        runner: TaskRunner::GoTask,
+        params,
+        aliases: listed.aliases,
+        module_path,
+        ..Default::default()
+    }
+}
+
+/// Returns the variables a task can be given on the command line
+/// (`task build VERSION=1.2`).
+///
+/// Task gives variables declared in the task precedence over command line
+/// variables, so only these become parameters:
+/// - variables listed under `requires: vars:`, which have to be given
+/// - task variables defaulting to themselves, the usual way to make a
+///   variable overridable: `VERSION: '{{.VERSION | default "1.0"}}'`
+///
+/// `name` is the task's full name; tasks of included Taskfiles are looked up
+/// without their namespace.
+fn task_params(taskfile: &Yaml, name: &str) -> Vec<TaskParam> {
+    let tasks = &taskfile["tasks"];
+    let definition = std::iter::once(name)
+        .chain(name.match_indices(':').map(|(i, _)| &name[i + 1..]))
+        .map(|local| &tasks[local])
+        .find(|definition| !definition.is_badvalue())
+        .unwrap_or(&tasks[name]);
+
+    let mut params = Vec::new();
+
+    if let Some(required) = definition["requires"]["vars"].as_vec() {
+        for var in required {
+            // Either a name or `{ name: ENV, enum: [dev, prod] }`
+            let name = var.as_str().or_else(|| var["name"].as_str());
+            if let Some(name) = name {
+                params.push(TaskParam {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    if let Some(vars) = definition["vars"].as_hash() {
+        for (key, value) in vars {
+            let (Some(key), Some(value)) = (key.as_str(), value.as_str()) else {
+                continue;
+            };
+            if params.iter().any(|p| p.name == key) {
+                continue;
+            }
+            if let Some(default) = template_default(key, value) {
+                params.push(TaskParam {
+                    name: key.to_string(),
+                    default: Some(ParamDefault::Literal(default)),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    params
+}
+
+/// Extracts the default of a variable declared as defaulting to itself, like
+/// `{{.VERSION | default "1.0"}}` or `{{default "1.0" .VERSION}}`.
+///
+/// Returns `None` if the value doesn't reference the variable through
+/// `default`, and an empty default if the default isn't a literal.
+fn template_default(name: &str, value: &str) -> Option<String> {
+    let template = value.trim().strip_prefix("{{")?.strip_suffix("}}")?;
+    let references_itself = template
+        .split(|c: char| c.is_whitespace() || c == '|' || c == '(' || c == ')')
+        .any(|word| word.strip_prefix('.') == Some(name));
+    if !references_itself {
+        return None;
+    }
+    let (_, after) = template.split_once("default")?;
+
+    let after = after.trim_start();
+    let literal = match after.chars().next() {
+        Some(quote @ ('"' | '\'' | '`')) => after[1..].split(quote).next().map(str::to_string),
+        Some(c) if c.is_ascii_digit() || c == '-' => after
+            .split(|c: char| c.is_whitespace() || c == '}')
+            .next()
+            .map(str::to_string),
+        _ => None,
+    };
+    Some(literal.unwrap_or_default())
+}
+
+/// Tasks of the Taskfile.
+pub struct GoTaskSource;
+
+impl TaskSource for GoTaskSource {
+    fn name(&self) -> &'static str {
+        "taskfile"
+    }
+
+    fn describe(&self) -> &'static str {
+        "Taskfile (go-task) tasks"
+    }
+
+    fn detect(&self, root: &Path, _config: &Config) -> bool {
+        TASKFILES.iter().any(|name| root.join(name).is_file())
+    }
+
+    fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+        let taskfile = TASKFILES
+            .iter()
+            .map(PathBuf::from)
+            .find(|path| path.is_file());
+        discover_tasks().map(|tasks| defined_in(tasks, taskfile))
+    }
+
+    fn watched_files(&self, root: &Path, _config: &Config) -> Vec<PathBuf> {
+        TASKFILES.iter().map(|name| root.join(name)).collect()
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::GoTask
+    }
+
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        let mut command = Command::new("task");
+        command.arg(&task.name).args(args);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{AppState, ModuleKey, TaskRow};
+
+    const TASKFILE: &str = r#"
+version: '3'
+
+includes:
+  docker: ./docker
+
+tasks:
+  build:
+    desc: Build the app
+    vars:
+      VERSION: '{{.VERSION | default "1.0.0"}}'
+      OUT: 'dist/{{.VERSION}}'
+      COMMIT:
+        sh: git rev-parse HEAD
+    cmds:
+      - go build -o {{.OUT}}
+
+  deploy:
+    requires:
+      vars:
+        - TARGET
+        - name: REGION
+          enum: [eu, us]
+    vars:
+      REPLICAS: '{{default 3 .REPLICAS}}'
+      TAG: '{{.TAG | default .VERSION}}'
+    cmds:
+      - ./deploy.sh
+
+  lint: golangci-lint run
+"#;
+
+    #[test]
+    fn test_parse_task_list() {
+        let list = parse_task_list(include_str!("fixtures/go_task/list.json")).unwrap();
+        let tasks: Vec<Task> = list
+            .tasks
+            .into_iter()
+            .enumerate()
+            .map(|(id, listed)| listed_task_to_task(id, listed, Vec::new()))
+            .collect();
+
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "build",
+                "deploy",
+                "lint",
+                "docker:build",
+                "docker:db:migrate"
+            ]
+        );
+        assert!(tasks.iter().all(|t| t.runner == TaskRunner::GoTask));
+
+        assert_eq!(tasks[0].description, Some("Build the app".to_string()));
+        assert_eq!(tasks[0].aliases, vec!["b"]);
+        assert!(tasks[0].module_path.is_empty());
+        assert_eq!(
+            tasks[1].description,
+            Some("Deploy the app\n\nRolls out the current build\nto the cluster.".to_string())
+        );
+        assert_eq!(tasks[2].description, None);
+
+        assert_eq!(tasks[3].module_path, vec!["docker"]);
+        assert_eq!(tasks[3].short_name(), "build");
+        assert_eq!(tasks[4].module_path, vec!["docker", "db"]);
+        assert_eq!(tasks[4].short_name(), "migrate");
+    }
+
+    #[test]
+    fn test_namespaces_stay_apart_from_just_modules() {
+        let list = parse_task_list(include_str!("fixtures/go_task/list.json")).unwrap();
+        let mut tasks = vec![Task {
+            name: "docker::up".to_string(),
+            runner: TaskRunner::Just,
+            module_path: vec!["docker".to_string()],
+            ..Default::default()
+        }];
+        for listed in list.tasks {
+            tasks.push(listed_task_to_task(tasks.len(), listed, Vec::new()));
+        }
+
+        // The Taskfile's `docker:` namespace gets its own row next to `mod docker`
+        let mut app = AppState::new(tasks);
+        let modules: Vec<&ModuleKey> = app
+            .task_rows
+            .iter()
+            .filter_map(|row| match row {
+                TaskRow::Module { module, .. } => Some(module),
+                TaskRow::Task(_) => None,
+            })
+            .collect();
+        let docker = |runner| ModuleKey {
+            runner,
+            path: vec!["docker".to_string()],
+        };
+        assert_eq!(
+            modules,
+            vec![&docker(TaskRunner::Just), &docker(TaskRunner::GoTask)]
+        );
+
+        // Selecting a namespaced task only expands the Taskfile namespaces
+        app.start_task(app.tasks[5].clone());
+        app.select_run(0);
+        assert_eq!(app.selected_task().unwrap().name, "docker:db:migrate");
+        assert!(!app.expanded_modules.contains(&docker(TaskRunner::Just)));
+        assert!(app.expanded_modules.contains(&docker(TaskRunner::GoTask)));
+    }
+
+    #[test]
+    fn test_task_params() {
+        let taskfile = parse_taskfile(TASKFILE).unwrap();
+
+        let build = task_params(&taskfile, "build");
+        assert_eq!(build.len(), 1);
+        assert_eq!(build[0].name, "VERSION");
+        assert_eq!(
+            build[0].default,
+            Some(ParamDefault::Literal("1.0.0".to_string()))
+        );
+
+        let deploy = task_params(&taskfile, "deploy");
+        let names: Vec<&str> = deploy.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["TARGET", "REGION", "REPLICAS", "TAG"]);
+        assert!(deploy[0].is_required());
+        assert!(deploy[1].is_required());
+        assert_eq!(
+            deploy[2].default,
+            Some(ParamDefault::Literal("3".to_string()))
+        );
+        // Not a literal, so Task evaluates the default itself
+        assert_eq!(
+            deploy[3].default,
+            Some(ParamDefault::Literal(String::new()))
+        );
+
+        assert!(task_params(&taskfile, "lint").is_empty());
+        assert!(task_params(&taskfile, "missing").is_empty());
+    }
+
+    #[test]
+    fn test_task_params_of_included_task() {
+        let taskfile =
+            parse_taskfile("version: '3'\ntasks:\n  build:\n    requires:\n      vars: [IMAGE]\n")
+                .unwrap();
+
+        let params = task_params(&taskfile, "docker:build");
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0].name, "IMAGE");
+    }
+
+    #[test]
+    fn test_template_default() {
+        assert_eq!(
+            template_default("ENV", r#"{{.ENV | default "dev"}}"#),
+            Some("dev".to_string())
+        );
+        assert_eq!(
+            template_default("ENV", "{{ default 'dev' .ENV }}"),
+            Some("dev".to_string())
+        );
+        assert_eq!(
+            template_default("N", "{{.N | default 10}}"),
+            Some("10".to_string())
+        );
+        assert_eq!(
+            template_default("ENV", "{{.ENV | default .OTHER}}"),
+            Some(String::new())
+        );
+        // Defaults of another variable or plain values can't be overridden
+        assert_eq!(template_default("OUT", r#"{{.DIR | default "out"}}"#), None);
+        assert_eq!(template_default("ENV", "dev"), None);
+        assert_eq!(template_default("ENV", "{{.ENV}}"), None);
+    }
+}
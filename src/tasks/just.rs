@@ -1,17 +1,27 @@
 /// Just recipe discovery module.
 ///
 /// This module provides functionality to discover available Just recipes
-/// in the current directory by running `just --list` and parsing its output.
-use crate::app::{Task, TaskRunner};
+/// in the current directory from `just --dump --dump-format json`, falling back
+/// to parsing `just --list` for versions of just without JSON output.
+use crate::app::{ParamDefault, ParamKind, Task, TaskParam, TaskRunner};
+use crate::config::Config;
+use crate::tasks::cache;
+use crate::tasks::registry::{defined_in, TaskSource};
 use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-/// Discovers available Just recipes in the current directory.
+/// Discovers available Just recipes in the current directory, or in
+/// `justfile` if given.
 ///
 /// This function:
 /// 1. Checks if `just` is available on PATH
-/// 2. Runs `just --list --unsorted` to get all recipes
-/// 3. Parses the output to extract recipe names and descriptions
+/// 2. Runs `just --dump --dump-format json` to get all recipes with their
+///    parameters, attributes, dependencies and bodies
+/// 3. Falls back to parsing `just --list --unsorted` if the JSON dump is unavailable
 ///
 /// # Returns
 ///
@@ -23,7 +33,7 @@ use std::process::Command;
 /// # Errors
 ///
 /// Returns descriptive errors that can be displayed to the user in the TUI.
-pub fn discover_tasks() -> Result<Vec<Task>> {
+pub fn discover_tasks(justfile: Option<&Path>) -> Result<Vec<Task>> {
     // First check if just is available
     let just_check = Command::new("just").arg("--version").output();
 
@@ -41,13 +51,56 @@ pub fn discover_tasks() -> Result<Vec<Task>> {
         _ => {}
     }
 
-    // Run just --list --unsorted to get all recipes
-    let output = Command::new("just")
-        .arg("--list")
-        .arg("--unsorted")
+    match dump_tasks(justfile) {
+        Ok(tasks) => Ok(tasks),
+        Err(_) => list_tasks(justfile),
+    }
+}
+
+/// Discovers recipes from `just --dump --dump-format json`, in source order.
+fn dump_tasks(justfile: Option<&Path>) -> Result<Vec<Task>> {
+    let output = just_command(justfile)
+        .args(["--dump", "--dump-format", "json"])
+        .output()
+        .map_err(|e| eyre!("Failed to execute just --dump: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("just --dump failed: {}", stderr.trim()));
+    }
+
+    let mut tasks = parse_just_dump(&String::from_utf8_lossy(&output.stdout))?;
+
+    // The dump lists recipes alphabetically; `--summary --unsorted` has the source order
+    if let Ok(output) = just_command(justfile)
+        .args(["--summary", "--unsorted"])
+        .output()
+    {
+        if output.status.success() {
+            sort_by_source_order(&mut tasks, &String::from_utf8_lossy(&output.stdout));
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// Discovers recipes by parsing `just --list --unsorted`.
+fn list_tasks(justfile: Option<&Path>) -> Result<Vec<Task>> {
+    // Run just --list --unsorted to get all recipes, including those of modules
+    // if this version of just supports them
+    let mut output = just_command(justfile)
+        .args(["--list", "--unsorted", "--list-submodules"])
         .output()
         .map_err(|e| eyre!("Failed to execute just --list: {}", e))?;
 
+    if !output.status.success() {
+        output = just_command(justfile)
+            .arg("--list")
+            .arg("--unsorted")
+            .output()
+            .map_err(|e| eyre!("Failed to execute just --list: {}", e))?;
+    }
+
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
         return Err(eyre!("just --list failed: {}", stderr.trim()));
@@ -66,9 +119,14 @@ pub fn discover_tasks() -> Result<Vec<Task>> {
 ///     another-recipe
 /// ```
 ///
+/// With `--list-submodules`, recipes of modules are listed indented below a
+/// `module:` heading and become tasks named `module::recipe`.
+///
 /// This function handles:
 /// - Lines with both recipe name and description
 /// - Lines with only recipe name
+/// - Recipe parameters following the name (e.g. `deploy env="staging" *flags`)
+/// - Group headings (`[group]`) and alias notes (`[alias: b]`) in descriptions
 /// - Skips header lines and empty lines
 ///
 /// # Arguments
@@ -81,9 +139,13 @@ pub fn discover_tasks() -> Result<Vec<Task>> {
 fn parse_just_list_output(output: &str) -> Result<Vec<Task>> {
     let mut tasks = Vec::new();
     let mut task_id = 0;
+    let mut current_group: Option<(usize, String)> = None;
+    // Enclosing module headings with their indentation
+    let mut modules: Vec<(usize, String)> = Vec::new();
 
     for line in output.lines() {
         let trimmed = line.trim();
+        let indent = line.len() - line.trim_start().len();
 
         // Skip empty lines
         if trimmed.is_empty() {
@@ -95,36 +157,78 @@ fn parse_just_list_output(output: &str) -> Result<Vec<Task>> {
             continue;
         }
 
-        // Parse recipe line
-        // Format is typically: "    recipe-name # description"
-        // or just: "    recipe-name"
+        // Leave the modules this line is not nested in
+        while modules.last().is_some_and(|(level, _)| *level >= indent) {
+            modules.pop();
+        }
 
-        // Split by '#' to separate name from description
-        let parts: Vec<&str> = trimmed.splitn(2, '#').collect();
+        // Group headings ("[group]") apply to the recipes listed below them
+        if let Some(group) = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+        {
+            current_group = Some((indent, group.to_string()));
+            continue;
+        }
 
-        let name = parts[0].trim();
+        // Module headings ("name:") contain the more indented lines below them
+        if let Some(module) = trimmed
+            .strip_suffix(':')
+            .filter(|name| !name.contains([' ', '#']))
+        {
+            if current_group
+                .as_ref()
+                .is_some_and(|(level, _)| *level >= indent)
+            {
+                current_group = None;
+            }
+            modules.push((indent, module.to_string()));
+            continue;
+        }
+
+        // Parse recipe line
+        // Format is typically: "    recipe-name param=\"default\" # description"
+        // or just: "    recipe-name"
+        let (signature, description) = split_comment(trimmed);
+        let mut words = split_signature(signature).into_iter();
 
         // Skip if name is empty (shouldn't happen but be defensive)
-        if name.is_empty() {
+        let Some(name) = words.next() else {
+            continue;
+        };
+
+        // Collapsed modules ("name ...") are listed without their recipes
+        let params: Vec<String> = words.collect();
+        if params == ["..."] {
             continue;
         }
 
-        let description = if parts.len() > 1 {
-            let desc = parts[1].trim();
-            if desc.is_empty() {
-                None
-            } else {
-                Some(desc.to_string())
-            }
+        let module_path: Vec<String> = modules.iter().map(|(_, name)| name.clone()).collect();
+        let name = if module_path.is_empty() {
+            name
         } else {
-            None
+            format!("{}::{}", module_path.join("::"), name)
         };
 
+        let (description, aliases) = split_aliases(description.unwrap_or_default().trim());
+        let description = Some(description)
+            .filter(|desc| !desc.is_empty())
+            .map(str::to_string);
+
         tasks.push(Task {
             id: task_id,
-            name: name.to_string(),
+            name,
             description,
             runner: TaskRunner::Just,
+            params: params.iter().map(|word| parse_param(word)).collect(),
+            groups: current_group
+                .iter()
+                .filter(|(level, _)| *level <= indent)
+                .map(|(_, group)| group.clone())
+                .collect(),
+            aliases,
+            module_path,
+            ..Default::default()
         });
 
         task_id += 1;
@@ -139,9 +243,551 @@ fn parse_just_list_output(output: &str) -> Result<Vec<Task>> {
     Ok(tasks)
 }
 
+/// Splits the `[alias: a]` / `[aliases: a, b]` note off the end of a `just --list` comment.
+fn split_aliases(comment: &str) -> (&str, Vec<String>) {
+    let note = comment
+        .strip_suffix(']')
+        .and_then(|rest| rest.rsplit_once('['))
+        .and_then(|(desc, note)| {
+            let names = note
+                .strip_prefix("alias:")
+                .or_else(|| note.strip_prefix("aliases:"))?;
+            Some((desc.trim_end(), names))
+        });
+
+    match note {
+        Some((desc, names)) => (
+            desc,
+            names.split(',').map(|n| n.trim().to_string()).collect(),
+        ),
+        None => (comment, Vec::new()),
+    }
+}
+
+/// Splits a `just --list` line into the recipe signature and its comment.
+///
+/// A `#` only starts the comment outside of quoted default values.
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    for (idx, ch) in line.char_indices() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(ch),
+            (None, '#') => return (&line[..idx], Some(&line[idx + 1..])),
+            _ => {}
+        }
+    }
+    (line, None)
+}
+
+/// Splits a recipe signature into its name and parameters.
+///
+/// Whitespace inside quotes or parentheses (in default values) does not split.
+fn split_signature(signature: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote = None;
+    let mut depth = 0usize;
+
+    for ch in signature.chars() {
+        match (quote, ch) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'' | '`') => quote = Some(ch),
+            (None, '(') => depth += 1,
+            (None, ')') => depth = depth.saturating_sub(1),
+            (None, c) if c.is_whitespace() && depth == 0 => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                continue;
+            }
+            _ => {}
+        }
+        word.push(ch);
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Parses a parameter as shown by `just --list` (e.g. `*$flags` or `env="staging"`).
+fn parse_param(word: &str) -> TaskParam {
+    let (kind, rest) = if let Some(rest) = word.strip_prefix('*') {
+        (ParamKind::Star, rest)
+    } else if let Some(rest) = word.strip_prefix('+') {
+        (ParamKind::Plus, rest)
+    } else {
+        (ParamKind::Singular, word)
+    };
+
+    let (exported, rest) = match rest.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, rest),
+    };
+
+    let (name, default) = match rest.split_once('=') {
+        Some((name, default)) => (name, Some(unquote(default))),
+        None => (rest, None),
+    };
+
+    TaskParam {
+        name: name.to_string(),
+        default,
+        kind,
+        exported,
+    }
+}
+
+/// Returns the value of a string literal default, or the expression as written.
+fn unquote(default: &str) -> ParamDefault {
+    for quote in ["\"", "'"] {
+        if let Some(inner) = default
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return ParamDefault::Literal(inner.to_string());
+        }
+    }
+    ParamDefault::Expression(default.to_string())
+}
+
+/// Top level of the output of `just --dump --dump-format json`.
+#[derive(Debug, Deserialize)]
+struct JustDump {
+    #[serde(default)]
+    recipes: BTreeMap<String, DumpRecipe>,
+    #[serde(default)]
+    aliases: BTreeMap<String, DumpAlias>,
+    #[serde(default)]
+    modules: BTreeMap<String, JustDump>,
+}
+
+/// A recipe in the JSON dump.
+#[derive(Debug, Deserialize)]
+struct DumpRecipe {
+    name: String,
+    /// Name including the module path (e.g. `docker::up`), in newer versions of just
+    #[serde(default)]
+    namepath: Option<String>,
+    #[serde(default)]
+    doc: Option<String>,
+    #[serde(default)]
+    attributes: Vec<Value>,
+    #[serde(default)]
+    body: Vec<Vec<Value>>,
+    #[serde(default)]
+    dependencies: Vec<DumpDependency>,
+    #[serde(default)]
+    parameters: Vec<DumpParameter>,
+    #[serde(default)]
+    private: bool,
+}
+
+/// A recipe parameter in the JSON dump.
+#[derive(Debug, Deserialize)]
+struct DumpParameter {
+    name: String,
+    #[serde(default)]
+    default: Option<Value>,
+    #[serde(default)]
+    kind: String,
+    #[serde(default)]
+    export: bool,
+}
+
+/// A recipe dependency in the JSON dump.
+#[derive(Debug, Deserialize)]
+struct DumpDependency {
+    recipe: String,
+    #[serde(default)]
+    arguments: Vec<Value>,
+}
+
+/// An alias in the JSON dump.
+#[derive(Debug, Deserialize)]
+struct DumpAlias {
+    target: String,
+}
+
+/// Parses the output of `just --dump --dump-format json` into a list of tasks.
+///
+/// Recipes are returned in the (alphabetical) order of the dump.
+///
+/// # Arguments
+///
+/// * `output` - The stdout from `just --dump --dump-format json`
+fn parse_just_dump(output: &str) -> Result<Vec<Task>> {
+    let dump: JustDump =
+        serde_json::from_str(output).map_err(|e| eyre!("Invalid just JSON dump: {}", e))?;
+
+    let mut tasks = Vec::new();
+    collect_module_tasks(dump, &[], &mut tasks);
+
+    if tasks.is_empty() {
+        return Err(eyre!(
+            "No tasks discovered. Is there a justfile in this directory?"
+        ));
+    }
+
+    Ok(tasks)
+}
+
+/// Appends the tasks of a module (the justfile itself or a `mod`) and its submodules.
+///
+/// Recipes of submodules are named by their path (e.g. `docker::up`), which is how
+/// just expects them to be invoked.
+fn collect_module_tasks(dump: JustDump, module_path: &[String], tasks: &mut Vec<Task>) {
+    let first = tasks.len();
+    for recipe in dump.recipes.into_values() {
+        let mut task = dump_recipe_to_task(tasks.len(), recipe);
+        if task.name.split("::").count() <= module_path.len() {
+            // Older versions of just don't include the module path in the name
+            task.name = [module_path.join("::"), task.name].join("::");
+        }
+        task.module_path = module_path.to_vec();
+        tasks.push(task);
+    }
+
+    for (alias, target) in dump.aliases {
+        if let Some(task) = tasks[first..]
+            .iter_mut()
+            .find(|task| task.short_name() == target.target)
+        {
+            task.aliases.push(alias);
+        }
+    }
+
+    for (name, module) in dump.modules {
+        let path = [module_path, std::slice::from_ref(&name)].concat();
+        collect_module_tasks(module, &path, tasks);
+    }
+}
+
+/// Converts a recipe from the JSON dump into a task.
+fn dump_recipe_to_task(id: usize, recipe: DumpRecipe) -> Task {
+    let mut groups = Vec::new();
+    let mut attributes = Vec::new();
+
+    // Attributes are either bare names ("no-cd") or objects with an argument ({"group": "ci"})
+    for attribute in &recipe.attributes {
+        match attribute {
+            Value::String(name) if name == "private" => {}
+            Value::String(name) => attributes.push(name.clone()),
+            Value::Object(map) => {
+                for (name, argument) in map {
+                    match (name.as_str(), argument) {
+                        ("group", Value::String(group)) => groups.push(group.clone()),
+                        // Already available as the recipe's doc
+                        ("doc", _) => {}
+                        (_, Value::Null) => attributes.push(name.clone()),
+                        (_, Value::Array(args)) => {
+                            let args: Vec<String> = args.iter().map(expression).collect();
+                            attributes.push(format!("{}({})", name, args.join(", ")));
+                        }
+                        (_, argument) => {
+                            attributes.push(format!("{}({})", name, expression(argument)))
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let params = recipe
+        .parameters
+        .into_iter()
+        .map(|param| TaskParam {
+            name: param.name,
+            // String defaults are literals; anything else is an expression
+            default: param.default.map(|default| match default {
+                Value::String(value) => ParamDefault::Literal(value),
+                other => ParamDefault::Expression(expression(&other)),
+            }),
+            kind: match param.kind.as_str() {
+                "star" => ParamKind::Star,
+                "plus" => ParamKind::Plus,
+                _ => ParamKind::Singular,
+            },
+            exported: param.export,
+        })
+        .collect();
+
+    let dependencies = recipe
+        .dependencies
+        .into_iter()
+        .map(|dependency| {
+            if dependency.arguments.is_empty() {
+                dependency.recipe
+            } else {
+                let args: Vec<String> = dependency.arguments.iter().map(expression).collect();
+                format!("({} {})", dependency.recipe, args.join(" "))
+            }
+        })
+        .collect();
+
+    // Body lines are lists of text fragments and `{{interpolations}}`
+    let body = recipe
+        .body
+        .iter()
+        .map(|fragments| {
+            fragments
+                .iter()
+                .map(|fragment| match fragment {
+                    Value::String(text) => text.clone(),
+                    Value::Array(parts) => match parts.first() {
+                        Some(expr) => format!("{{{{{}}}}}", expression(expr)),
+                        None => String::new(),
+                    },
+                    _ => String::new(),
+                })
+                .collect()
+        })
+        .collect();
+
+    Task {
+        id,
+        name: recipe.namepath.unwrap_or(recipe.name),
+        description: recipe.doc,
+        runner: TaskRunner::Just,
+        source: None,
+        params,
+        groups,
+        aliases: Vec::new(),
+        private: recipe.private,
+        attributes,
+        dependencies,
+        body,
+        module_path: Vec::new(),
+        file_target: false,
+        cwd: None,
+        env: Vec::new(),
+        command: Vec::new(),
+    }
+}
+
+/// Renders an expression from the JSON dump back into just syntax.
+///
+/// Expressions are nested arrays like `["concatenate", a, b]`; string values are
+/// string literals. Unknown expression kinds are rendered as calls.
+fn expression(value: &Value) -> String {
+    let Value::Array(parts) = value else {
+        return match value {
+            Value::String(text) => format!("{:?}", text),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+    };
+
+    let kind = parts.first().and_then(Value::as_str).unwrap_or_default();
+    let args = &parts[1.min(parts.len())..];
+    let arg = |idx: usize| args.get(idx).map(expression).unwrap_or_default();
+
+    match kind {
+        "variable" => args
+            .first()
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        "evaluate" => format!(
+            "`{}`",
+            args.first().and_then(Value::as_str).unwrap_or_default()
+        ),
+        "concatenate" => format!("{} + {}", arg(0), arg(1)),
+        "join" => format!("{} / {}", arg(0), arg(1)).trim_start().to_string(),
+        "call" => {
+            let name = args.first().and_then(Value::as_str).unwrap_or_default();
+            let call_args: Vec<String> = args.iter().skip(1).map(expression).collect();
+            format!("{}({})", name, call_args.join(", "))
+        }
+        _ => {
+            let rendered: Vec<String> = args.iter().map(expression).collect();
+            format!("{}({})", kind, rendered.join(", "))
+        }
+    }
+}
+
+/// Orders tasks as listed by `just --summary --unsorted` (space-separated names).
+///
+/// Tasks missing from the summary (such as private recipes) keep their relative
+/// order after the listed ones. Task IDs are renumbered to match the new order.
+fn sort_by_source_order(tasks: &mut [Task], summary: &str) {
+    let order: Vec<&str> = summary.split_whitespace().collect();
+    tasks.sort_by_key(|task| {
+        order
+            .iter()
+            .position(|name| *name == task.name)
+            .unwrap_or(order.len())
+    });
+    for (id, task) in tasks.iter_mut().enumerate() {
+        task.id = id;
+    }
+}
+
+/// Returns the justfile of the project in `root`: the one chosen on the
+/// command line, or else the one just finds.
+fn justfile(root: &Path, config: &Config) -> Option<PathBuf> {
+    config
+        .files
+        .justfile
+        .clone()
+        .or_else(|| find_justfile(root))
+}
+
+/// Creates a `just` command using `justfile` if given (recipes then run in
+/// its directory), or else the justfile just finds.
+fn just_command(justfile: Option<&Path>) -> Command {
+    let mut command = Command::new("just");
+    if let Some(justfile) = justfile {
+        command.arg("--justfile").arg(justfile);
+    }
+    command
+}
+
+/// Finds the justfile just uses in `root`: the first file named `justfile`
+/// (any capitalization, optionally with a leading dot) in the directory or
+/// its parents.
+fn find_justfile(root: &Path) -> Option<PathBuf> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    root.ancestors().find_map(justfile_in)
+}
+
+/// Returns the first file named `justfile` (any capitalization, optionally
+/// with a leading dot) in `dir` itself.
+fn justfile_in(dir: &Path) -> Option<PathBuf> {
+    let mut names: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name().is_some_and(|name| {
+                let name = name.to_string_lossy().to_lowercase();
+                name == "justfile" || name == ".justfile"
+            })
+        })
+        .collect();
+    names.sort();
+    names.into_iter().next()
+}
+
+/// Returns the files a justfile is made of: the justfile itself and,
+/// recursively, the files it `import`s and the source files of its `mod`s.
+///
+/// All the files a module may be read from are listed, since creating one
+/// of them changes the recipes as well.
+fn justfile_sources(justfile: &Path) -> Vec<PathBuf> {
+    let mut sources = vec![justfile.to_path_buf()];
+    let mut next = 0;
+    while let Some(path) = sources.get(next).cloned() {
+        next += 1;
+        let (Ok(source), Some(dir)) = (std::fs::read_to_string(&path), path.parent()) else {
+            continue;
+        };
+        for included in parse_includes(&source) {
+            let included = dir.join(included);
+            if !sources.contains(&included) {
+                sources.push(included);
+            }
+        }
+    }
+    sources
+}
+
+/// Extracts the paths (relative to the justfile) that `import` and `mod`
+/// statements read.
+fn parse_includes(source: &str) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for line in source.lines() {
+        // Recipe bodies are indented
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let keyword = words.next().unwrap_or_default();
+        let argument = words.next().unwrap_or_default();
+        let quoted = |word: &str| {
+            word.strip_prefix('\'')
+                .and_then(|w| w.strip_suffix('\''))
+                .or_else(|| word.strip_prefix('"').and_then(|w| w.strip_suffix('"')))
+                .map(PathBuf::from)
+        };
+
+        match keyword.trim_end_matches('?') {
+            "import" => paths.extend(quoted(argument)),
+            "mod" if !argument.is_empty() => match words.next().and_then(quoted) {
+                Some(path) => paths.push(path),
+                None => {
+                    paths.push(PathBuf::from(format!("{}.just", argument)));
+                    for name in ["mod.just", "justfile", ".justfile"] {
+                        paths.push(Path::new(argument).join(name));
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+    paths
+}
+
+/// Recipes of the justfile.
+pub struct JustSource;
+
+impl TaskSource for JustSource {
+    fn name(&self) -> &'static str {
+        "just"
+    }
+
+    fn describe(&self) -> &'static str {
+        "justfile recipes"
+    }
+
+    fn detect(&self, root: &Path, config: &Config) -> bool {
+        justfile(root, config).is_some()
+    }
+
+    fn marks_project(&self, dir: &Path) -> bool {
+        // Only the directory's own justfile, not one just would find above it
+        justfile_in(dir).is_some()
+    }
+
+    fn discover(&self, config: &Config) -> Result<Vec<Task>> {
+        let justfile = justfile(Path::new("."), config);
+        discover_tasks(config.files.justfile.as_deref()).map(|tasks| defined_in(tasks, justfile))
+    }
+
+    fn cache_inputs(&self, root: &Path, config: &Config) -> Option<Vec<PathBuf>> {
+        justfile(root, config).map(|justfile| justfile_sources(&justfile))
+    }
+
+    fn tool_version(&self) -> Option<String> {
+        cache::tool_version("just")
+    }
+
+    fn watched_files(&self, root: &Path, config: &Config) -> Vec<PathBuf> {
+        self.cache_inputs(root, config)
+            .unwrap_or_else(|| vec![root.join("justfile"), root.join(".justfile")])
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::Just
+    }
+
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        let mut command = just_command(task.source.as_deref());
+        command.arg(&task.name).args(args);
+        command
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::scratch_dir;
 
     #[test]
     fn test_parse_just_list_basic() {
@@ -200,4 +846,351 @@ mod tests {
         let tasks = parse_just_list_output(output).unwrap();
         assert_eq!(tasks.len(), 2);
     }
+
+    #[test]
+    fn test_parse_just_list_with_parameters() {
+        let output = r#"Available recipes:
+    deploy env="staging" *flags                        # Deploy the app
+    build target='debug' $mode=version
+    test +args
+    greet name desc="hello world # not comment" *$rest
+    expr x=("a" + "b") y=`echo hi`
+"#;
+
+        let tasks = parse_just_list_output(output).unwrap();
+        assert_eq!(tasks.len(), 5);
+
+        assert_eq!(tasks[0].name, "deploy");
+        assert_eq!(tasks[0].description, Some("Deploy the app".to_string()));
+        assert_eq!(
+            tasks[0].params,
+            vec![
+                TaskParam {
+                    name: "env".to_string(),
+                    default: Some(ParamDefault::Literal("staging".to_string())),
+                    kind: ParamKind::Singular,
+                    exported: false,
+                },
+                TaskParam {
+                    name: "flags".to_string(),
+                    default: None,
+                    kind: ParamKind::Star,
+                    exported: false,
+                },
+            ]
+        );
+
+        assert_eq!(
+            tasks[1].params[0].default,
+            Some(ParamDefault::Literal("debug".to_string()))
+        );
+        assert!(tasks[1].params[1].exported);
+        // Unquoted defaults are variables, evaluated by just
+        assert_eq!(
+            tasks[1].params[1].default,
+            Some(ParamDefault::Expression("version".to_string()))
+        );
+
+        assert_eq!(tasks[2].params[0].kind, ParamKind::Plus);
+        assert!(tasks[2].params[0].is_required());
+
+        // A '#' inside a quoted default does not start the description
+        assert_eq!(tasks[3].name, "greet");
+        assert_eq!(tasks[3].description, None);
+        assert_eq!(
+            tasks[3].params[1].default,
+            Some(ParamDefault::Literal(
+                "hello world # not comment".to_string()
+            ))
+        );
+        assert_eq!(tasks[3].params[2].kind, ParamKind::Star);
+        assert!(tasks[3].params[2].exported);
+
+        // Expression defaults are kept as written
+        assert_eq!(
+            tasks[4].params[0].default,
+            Some(ParamDefault::Expression("(\"a\" + \"b\")".to_string()))
+        );
+        assert_eq!(
+            tasks[4].params[1].default,
+            Some(ParamDefault::Expression("`echo hi`".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_just_list_groups_and_aliases() {
+        let output = r#"Available recipes:
+    deploy # Deploy the app [alias: d]
+    release # [aliases: r, rel]
+
+    [ci]
+    lint # doc comment
+"#;
+
+        let tasks = parse_just_list_output(output).unwrap();
+        assert_eq!(tasks[0].description, Some("Deploy the app".to_string()));
+        assert_eq!(tasks[0].aliases, vec!["d"]);
+        assert_eq!(tasks[1].description, None);
+        assert_eq!(tasks[1].aliases, vec!["r", "rel"]);
+        assert!(tasks[1].groups.is_empty());
+        assert_eq!(tasks[2].name, "lint");
+        assert_eq!(tasks[2].groups, vec!["ci"]);
+    }
+
+    #[test]
+    fn test_parse_just_dump_basic() {
+        let output = include_str!("fixtures/just/basic.json");
+
+        let tasks = parse_just_dump(output).unwrap();
+
+        // The dump is sorted alphabetically
+        assert_eq!(tasks.len(), 3);
+
+        assert_eq!(tasks[0].name, "build");
+        assert_eq!(tasks[0].description, Some("Build the project".to_string()));
+        assert_eq!(tasks[0].body, vec!["cargo build"]);
+
+        assert_eq!(tasks[1].name, "deploy");
+        assert_eq!(
+            tasks[1].description,
+            Some("Deploy to production".to_string())
+        );
+        assert_eq!(tasks[1].dependencies, vec!["build", "test"]);
+
+        assert_eq!(tasks[2].name, "test");
+        assert_eq!(tasks[2].description, None);
+    }
+
+    #[test]
+    fn test_parse_just_dump_empty() {
+        let output = include_str!("fixtures/just/empty.json");
+
+        let result = parse_just_dump(output);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_just_dump_invalid() {
+        assert!(parse_just_dump("Available recipes:\n    build\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_just_dump_attributes() {
+        let output = include_str!("fixtures/just/attributes.json");
+
+        let tasks = parse_just_dump(output).unwrap();
+        let task = |name: &str| tasks.iter().find(|t| t.name == name).unwrap();
+
+        let helper = task("_helper");
+        assert!(helper.private);
+        assert!(helper.is_hidden());
+        assert!(helper.attributes.is_empty());
+
+        let lint = task("lint");
+        assert_eq!(lint.description, Some("doc comment".to_string()));
+        assert_eq!(lint.groups, vec!["ci"]);
+        assert_eq!(lint.attributes, vec!["confirm"]);
+        assert_eq!(lint.dependencies, vec!["build"]);
+        assert!(!lint.private);
+
+        let fmt = task("fmt");
+        assert_eq!(fmt.description, Some("Custom doc".to_string()));
+        assert_eq!(fmt.groups, vec!["ci"]);
+        assert_eq!(fmt.attributes, vec!["no-cd"]);
+
+        let deploy = task("deploy");
+        assert_eq!(deploy.aliases, vec!["d"]);
+        assert_eq!(deploy.body, vec!["echo {{env}} {{flags}}"]);
+    }
+
+    #[test]
+    fn test_parse_just_dump_parameters() {
+        let output = include_str!("fixtures/just/attributes.json");
+
+        let tasks = parse_just_dump(output).unwrap();
+        let task = |name: &str| tasks.iter().find(|t| t.name == name).unwrap();
+
+        // Same recipes as in test_parse_just_list_with_parameters
+        assert_eq!(
+            task("deploy").params,
+            vec![
+                TaskParam {
+                    name: "env".to_string(),
+                    default: Some(ParamDefault::Literal("staging".to_string())),
+                    kind: ParamKind::Singular,
+                    exported: false,
+                },
+                TaskParam {
+                    name: "flags".to_string(),
+                    default: None,
+                    kind: ParamKind::Star,
+                    exported: false,
+                },
+            ]
+        );
+
+        let build = task("build");
+        assert_eq!(
+            build.params[0].default,
+            Some(ParamDefault::Literal("debug".to_string()))
+        );
+        assert!(build.params[1].exported);
+        assert_eq!(
+            build.params[1].default,
+            Some(ParamDefault::Expression("version".to_string()))
+        );
+
+        assert_eq!(task("test").params[0].kind, ParamKind::Plus);
+
+        let greet = task("greet");
+        assert_eq!(
+            greet.params[1].default,
+            Some(ParamDefault::Literal(
+                "hello world # not comment".to_string()
+            ))
+        );
+        assert_eq!(greet.params[2].kind, ParamKind::Star);
+        assert!(greet.params[2].exported);
+
+        // Expression defaults are rendered back into just syntax
+        let expr = task("expr");
+        assert_eq!(
+            expr.params[0].default,
+            Some(ParamDefault::Expression("\"a\" + \"b\"".to_string()))
+        );
+        assert_eq!(
+            expr.params[1].default,
+            Some(ParamDefault::Expression("`echo hi`".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_sort_by_source_order() {
+        let output = include_str!("fixtures/just/attributes.json");
+        let mut tasks = parse_just_dump(output).unwrap();
+
+        sort_by_source_order(&mut tasks, "deploy build test greet expr lint fmt\n");
+
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["deploy", "build", "test", "greet", "expr", "lint", "fmt", "_helper"]
+        );
+        assert!(tasks.iter().enumerate().all(|(id, task)| task.id == id));
+    }
+
+    #[test]
+    fn test_parse_just_list_submodules() {
+        let output = r#"Available recipes:
+    build # Build it
+    lint  # Shared lint
+    docker:
+        up *args # Start containers
+        db:
+            migrate # Migrate db
+    release
+    compose ...
+"#;
+
+        let tasks = parse_just_list_output(output).unwrap();
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "build",
+                "lint",
+                "docker::up",
+                "docker::db::migrate",
+                "release"
+            ]
+        );
+
+        assert_eq!(tasks[2].module_path, vec!["docker"]);
+        assert_eq!(tasks[2].short_name(), "up");
+        assert_eq!(tasks[2].params[0].kind, ParamKind::Star);
+        assert_eq!(tasks[3].module_path, vec!["docker", "db"]);
+        assert_eq!(tasks[3].description, Some("Migrate db".to_string()));
+        assert!(tasks[4].module_path.is_empty());
+    }
+
+    #[test]
+    fn test_parse_just_dump_modules() {
+        let output = include_str!("fixtures/just/modules.json");
+
+        let mut tasks = parse_just_dump(output).unwrap();
+        sort_by_source_order(&mut tasks, "build lint docker::up docker::db::migrate\n");
+
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["build", "lint", "docker::up", "docker::db::migrate"]
+        );
+
+        // Imported recipes belong to the justfile itself
+        assert!(tasks[1].module_path.is_empty());
+        assert_eq!(tasks[1].description, Some("Shared lint".to_string()));
+
+        assert_eq!(tasks[2].module_path, vec!["docker"]);
+        assert_eq!(tasks[2].short_name(), "up");
+        assert_eq!(tasks[3].module_path, vec!["docker", "db"]);
+        assert_eq!(tasks[3].short_name(), "migrate");
+    }
+
+    #[test]
+    fn test_parse_includes() {
+        let source = r#"
+import 'ci/common.just'
+import? "local.just"
+mod docker
+mod? deploy 'ops/deploy.just'
+
+# import 'ignored.just'
+build:
+    mod not-a-module
+"#;
+        assert_eq!(
+            parse_includes(source),
+            vec![
+                PathBuf::from("ci/common.just"),
+                PathBuf::from("local.just"),
+                PathBuf::from("docker.just"),
+                PathBuf::from("docker/mod.just"),
+                PathBuf::from("docker/justfile"),
+                PathBuf::from("docker/.justfile"),
+                PathBuf::from("ops/deploy.just"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_justfile_sources() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        std::fs::create_dir_all(dir.join("db")).unwrap();
+        std::fs::create_dir_all(dir.join("nested")).unwrap();
+        std::fs::write(
+            dir.join("Justfile"),
+            "import 'common.just'\nmod db 'db/tasks.just'\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("common.just"), "lint:\n    cargo clippy\n").unwrap();
+        // Imports are relative to the file that imports them
+        std::fs::write(dir.join("db/tasks.just"), "import 'seed.just'\n").unwrap();
+
+        let justfile = find_justfile(&dir.join("nested")).unwrap();
+        assert_eq!(justfile.file_name().unwrap(), "Justfile");
+        let names: Vec<String> = justfile_sources(&justfile)
+            .iter()
+            .map(|path| {
+                path.strip_prefix(justfile.parent().unwrap())
+                    .unwrap()
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec!["Justfile", "common.just", "db/tasks.just", "db/seed.just"]
+        );
+    }
 }
@@ -2,20 +2,31 @@
 ///
 /// This module provides functionality to discover available Make targets
 /// in the current directory by running `make -qp` and parsing its output.
+/// Descriptions are read from the Makefiles themselves (`target: ## text` or a
+/// comment block directly above the rule).
 use crate::app::{Task, TaskRunner};
+use crate::config::Config;
+use crate::tasks::cache;
+use crate::tasks::registry::{defined_in, TaskSource};
 use color_eyre::eyre::{eyre, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-/// Discovers available Make targets in the current directory.
+/// Discovers available Make targets in the current directory, reading
+/// `makefile` if given instead of the makefile make finds. Also returns the
+/// makefiles make read (`MAKEFILE_LIST`), including ones it included through
+/// variables or wildcards.
 ///
 /// This function:
 /// 1. Checks if `make` is available on PATH
 /// 2. Runs `make -qp` to get all targets from the Makefile database
 /// 3. Parses the output to extract target names
+/// 4. Reads the Makefile and its included files for target descriptions
 ///
 /// # Returns
 ///
-/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if:
+/// Returns `Ok((tasks, makefiles))`, or an error if:
 /// - `make` is not installed or not on PATH
 /// - The Makefile doesn't exist or is invalid
 /// - `make -qp` returns an error
@@ -23,7 +34,7 @@ use std::process::Command;
 /// # Errors
 ///
 /// Returns descriptive errors that can be displayed to the user in the TUI.
-pub fn discover_tasks() -> Result<Vec<Task>> {
+pub fn discover_tasks(makefile: Option<&Path>) -> Result<(Vec<Task>, Vec<PathBuf>)> {
     // First check if make is available
     let make_check = Command::new("make").arg("--version").output();
 
@@ -45,7 +56,7 @@ pub fn discover_tasks() -> Result<Vec<Task>> {
     // -q: question mode (don't run commands)
     // -p: print database
     // We redirect stderr to suppress "No rule to make target" messages
-    let output = Command::new("make")
+    let output = make_command(makefile)
         .arg("-qp")
         .output()
         .map_err(|e| eyre!("Failed to execute make -qp: {}", e))?;
@@ -58,23 +69,187 @@ pub fn discover_tasks() -> Result<Vec<Task>> {
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    parse_make_database(&stdout)
+    let mut tasks = parse_make_database(&stdout)?;
+
+    // Descriptions only exist in the source, so read every makefile make loaded.
+    // The first makefile that describes a target wins.
+    let mut descriptions = HashMap::new();
+    let makefiles = parse_makefile_list(&stdout);
+    for path in &makefiles {
+        if let Ok(source) = std::fs::read_to_string(path) {
+            for (target, description) in parse_descriptions(&source) {
+                descriptions.entry(target).or_insert(description);
+            }
+        }
+    }
+    for task in &mut tasks {
+        if let Some(description) = descriptions.remove(&task.name) {
+            task.description = Some(description);
+        }
+    }
+
+    Ok((tasks, makefiles))
+}
+
+/// Marks the targets in `root` that are existing files as file targets.
+///
+/// make -q only checks the files it needs, so a target that is neither phony
+/// nor checked may still be an existing file. Files come and go without the
+/// makefiles changing, so this is checked again whenever the targets are
+/// loaded, including from the discovery cache.
+fn mark_file_targets(root: &Path, tasks: &mut [Task]) {
+    for task in tasks {
+        if !task.attributes.iter().any(|a| a == "phony") && root.join(&task.name).exists() {
+            task.file_target = true;
+        }
+    }
+}
+
+/// Extracts the makefiles make read (the main Makefile and every `include`d
+/// file) from the `MAKEFILE_LIST` variable in the `make -qp` database.
+fn parse_makefile_list(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .find_map(|line| {
+            let value = line.strip_prefix("MAKEFILE_LIST")?.trim_start();
+            value.strip_prefix(":=").or_else(|| value.strip_prefix('='))
+        })
+        .map(|files| files.split_whitespace().map(PathBuf::from).collect())
+        .unwrap_or_default()
+}
+
+/// Collects target descriptions from the source of a makefile.
+///
+/// Two conventions are recognised:
+/// ```text
+/// # Run the test suite
+/// # with coverage enabled
+/// test:
+///
+/// build: deps ## Compile the project
+/// ```
+///
+/// A `##` comment on the rule line takes precedence over the comment block
+/// directly above the rule. Blocks separated from the rule by a blank line are
+/// ignored, while `.PHONY` declarations between the comments and the rule are
+/// skipped over. `##@` section headings are never used as descriptions.
+fn parse_descriptions(source: &str) -> HashMap<String, String> {
+    let mut descriptions = HashMap::new();
+    let mut comments: Vec<String> = Vec::new();
+
+    for line in source.lines() {
+        // Recipe lines are indented with a tab and never describe anything
+        if line.starts_with('\t') || line.trim().is_empty() {
+            comments.clear();
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.starts_with("##@") {
+            comments.clear();
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            let text = trimmed.trim_start_matches('#').trim();
+            if !text.is_empty() {
+                comments.push(text.to_string());
+            }
+            continue;
+        }
+
+        let Some((targets, rest)) = split_rule(trimmed) else {
+            comments.clear();
+            continue;
+        };
+        if targets.starts_with(".PHONY") {
+            continue;
+        }
+
+        let (prerequisites, inline) = match rest.split_once("##") {
+            Some((prerequisites, comment)) => (prerequisites, Some(comment.trim())),
+            None => (rest, None),
+        };
+        // `target: VAR = value` sets a target-specific variable
+        if prerequisites.contains('=') {
+            comments.clear();
+            continue;
+        }
+
+        let description = match inline {
+            Some(comment) if !comment.is_empty() => Some(comment.to_string()),
+            _ if !comments.is_empty() => Some(comments.join("\n")),
+            _ => None,
+        };
+        if let Some(description) = description {
+            for target in targets.split_whitespace() {
+                descriptions
+                    .entry(target.to_string())
+                    .or_insert_with(|| description.clone());
+            }
+        }
+        comments.clear();
+    }
+
+    descriptions
+}
+
+/// Splits a rule line into its targets and the text after the colon, or
+/// returns `None` for lines that are not rules (variable assignments,
+/// directives, ...).
+fn split_rule(line: &str) -> Option<(&str, &str)> {
+    let colon = line.find(':')?;
+    let (targets, rest) = (&line[..colon], &line[colon + 1..]);
+    if targets.contains('=') || targets.trim().is_empty() {
+        return None;
+    }
+    // `VAR := value` and `VAR ::= value` are assignments, `a:: b` is a rule
+    let rest = rest.strip_prefix(':').unwrap_or(rest);
+    if rest.starts_with('=') || rest.starts_with(":=") {
+        return None;
+    }
+    Some((targets.trim(), rest))
+}
+
+/// How a target from the `make -qp` database is classified.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum TargetKind {
+    /// Declared `.PHONY`, so it always runs its recipe
+    Phony,
+    /// Neither phony nor known to be a file (e.g. `lint` without `.PHONY`)
+    Command,
+    /// Builds a file: an existing file or a pattern rule output
+    File,
+}
+
+/// A target entry of the `make -qp` database with its `#` annotations.
+struct DatabaseEntry<'a> {
+    name: &'a str,
+    annotations: Vec<&'a str>,
+    not_a_target: bool,
 }
 
 /// Parses the output of `make -qp` into a list of tasks.
 ///
-/// The format of `make -qp` includes lines like:
+/// The format of `make -qp` includes entries like:
 /// ```text
-/// target: dependencies
-/// .PHONY: clean
+/// # Not a target:
+/// main.c:
+/// #  Last modified 2024-01-01 00:00:00
+///
+/// clean:
+/// #  Phony target (prerequisite of .PHONY).
+/// #  recipe to execute (from 'Makefile', line 15):
+///     rm -rf target
 /// ```
 ///
 /// This function handles:
 /// - Extracting target names from lines with colons
+/// - Filtering out entries marked `# Not a target:` and built-in rules
 /// - Filtering out special targets (starting with .)
 /// - Filtering out implicit rules (containing %)
 /// - Filtering out variable assignments
-/// - Removing common automatic targets (all, clean, install, etc. are kept as valid targets)
+/// - Classifying targets as phony, command or file targets (file targets are hidden)
+/// - Ordering phony targets first, then by their position in the makefiles
 ///
 /// # Arguments
 ///
@@ -84,44 +259,61 @@ pub fn discover_tasks() -> Result<Vec<Task>> {
 ///
 /// A vector of discovered tasks with names.
 fn parse_make_database(output: &str) -> Result<Vec<Task>> {
-    let mut tasks = Vec::new();
-    let mut task_id = 0;
-    let mut seen_targets = std::collections::HashSet::new();
+    let makefiles = parse_makefile_list(output);
+    let mut phony = HashSet::new();
+    let mut entries: Vec<DatabaseEntry> = Vec::new();
+    let mut in_entry = false;
+    let mut not_a_target = false;
 
     for line in output.lines() {
-        let trimmed = line.trim_start();
-
-        // Skip empty lines
-        if trimmed.is_empty() {
-            continue;
-        }
-
-        // Skip comments
-        if trimmed.starts_with('#') {
+        // Skip empty lines, which end the current entry
+        if line.trim().is_empty() {
+            in_entry = false;
+            not_a_target = false;
             continue;
         }
 
-        // Skip lines that don't contain a colon (not a target definition)
-        if !trimmed.contains(':') {
+        // Skip recipe lines
+        if line.starts_with('\t') {
             continue;
         }
 
-        // Skip variable assignments (contain = before :)
-        if let Some(colon_pos) = trimmed.find(':') {
-            if trimmed[..colon_pos].contains('=') {
-                continue;
+        // Comments annotate the entry above them
+        if line.starts_with('#') {
+            if line.trim_end() == "# Not a target:" {
+                not_a_target = true;
+            } else if let Some(entry) = entries.last_mut().filter(|_| in_entry) {
+                entry.annotations.push(line.trim_start_matches('#').trim());
             }
+            continue;
         }
 
-        // Extract target name (everything before the first colon)
-        let target = if let Some(colon_pos) = trimmed.find(':') {
-            trimmed[..colon_pos].trim()
-        } else {
+        // Skip lines that are not target definitions (e.g. variable assignments)
+        let Some((target, rest)) = split_rule(line.trim_start()) else {
+            in_entry = false;
             continue;
         };
 
-        // Skip if target is empty
-        if target.is_empty() {
+        if target == ".PHONY" {
+            phony.extend(rest.split_whitespace());
+        }
+
+        entries.push(DatabaseEntry {
+            name: target,
+            annotations: Vec::new(),
+            not_a_target: std::mem::take(&mut not_a_target),
+        });
+        in_entry = true;
+    }
+
+    let mut targets = Vec::new();
+    let mut seen_targets = HashSet::new();
+
+    for entry in entries {
+        let target = entry.name;
+
+        // Skip files make only looked at and GNU make's built-in rules
+        if entry.not_a_target || entry.annotations.contains(&"Builtin rule") {
             continue;
         }
 
@@ -141,7 +333,7 @@ fn parse_make_database(output: &str) -> Result<Vec<Task>> {
         }
 
         // Skip if we've already seen this target
-        if !seen_targets.insert(target.to_string()) {
+        if !seen_targets.insert(target) {
             continue;
         }
 
@@ -176,15 +368,50 @@ fn parse_make_database(output: &str) -> Result<Vec<Task>> {
             continue;
         }
 
-        tasks.push(Task {
-            id: task_id,
+        let annotated = |prefix: &str| entry.annotations.iter().any(|a| a.starts_with(prefix));
+        let kind = if phony.contains(target) || annotated("Phony target") {
+            TargetKind::Phony
+        } else if annotated("Last modified") || annotated("Implicit/static pattern stem") {
+            // Names say nothing: `deploy.staging` or `docs/serve` may well be
+            // commands, so only what make found out about the file counts
+            TargetKind::File
+        } else {
+            TargetKind::Command
+        };
+
+        // Targets without a recipe have no location and go last within their kind
+        let location = entry
+            .annotations
+            .iter()
+            .find_map(|a| parse_recipe_location(a))
+            .map(|(file, line)| {
+                let file = makefiles.iter().position(|m| m.as_os_str() == file);
+                (file.unwrap_or(usize::MAX), line)
+            })
+            .unwrap_or((usize::MAX, usize::MAX));
+
+        targets.push((kind, location, target));
+    }
+
+    // Stable, so targets without a location keep the database order
+    targets.sort_by_key(|&(kind, location, _)| (kind, location));
+
+    let tasks: Vec<Task> = targets
+        .into_iter()
+        .enumerate()
+        .map(|(id, (kind, _, target))| Task {
+            id,
             name: target.to_string(),
             description: None,
             runner: TaskRunner::Make,
-        });
-
-        task_id += 1;
-    }
+            attributes: match kind {
+                TargetKind::Phony => vec!["phony".to_string()],
+                _ => Vec::new(),
+            },
+            file_target: kind == TargetKind::File,
+            ..Default::default()
+        })
+        .collect();
 
     if tasks.is_empty() {
         return Err(eyre!(
@@ -195,9 +422,147 @@ fn parse_make_database(output: &str) -> Result<Vec<Task>> {
     Ok(tasks)
 }
 
+/// Parses the makefile and line of a target's recipe from a database
+/// annotation like `recipe to execute (from 'Makefile', line 18):`.
+fn parse_recipe_location(annotation: &str) -> Option<(&str, usize)> {
+    let location = annotation.strip_prefix("recipe to execute (from '")?;
+    let (file, line) = location.rsplit_once("', line ")?;
+    let line = line.strip_suffix("):")?.parse().ok()?;
+    Some((file, line))
+}
+
+/// Returns the makefile of the project in `root`: the one chosen on the
+/// command line, or else the one make reads.
+fn makefile(root: &Path, config: &Config) -> Option<PathBuf> {
+    config
+        .files
+        .makefile
+        .clone()
+        .or_else(|| find_makefile(root))
+}
+
+/// Creates a `make` command reading `makefile` if given (with `-f`, so
+/// targets still run in the project directory), or else the makefile make
+/// finds.
+fn make_command(makefile: Option<&Path>) -> Command {
+    let mut command = Command::new("make");
+    if let Some(makefile) = makefile {
+        command.arg("-f").arg(makefile);
+    }
+    command
+}
+
+/// Finds the makefile make reads in `root`, in make's order of preference.
+fn find_makefile(root: &Path) -> Option<PathBuf> {
+    ["GNUmakefile", "makefile", "Makefile"]
+        .iter()
+        .map(|name| root.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Returns the makefile and, recursively, the files it `include`s (also
+/// `-include` and `sinclude`), which make looks up relative to `root`.
+///
+/// Includes named with variables or wildcards can't be followed without
+/// running make, so they are skipped here; discovery reports the makefiles
+/// make actually read instead.
+fn makefile_sources(root: &Path, makefile: PathBuf) -> Vec<PathBuf> {
+    let mut sources = vec![makefile];
+    let mut next = 0;
+    while let Some(path) = sources.get(next).cloned() {
+        next += 1;
+        let Ok(source) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        for included in parse_includes(&source) {
+            let included = root.join(included);
+            if !sources.contains(&included) {
+                sources.push(included);
+            }
+        }
+    }
+    sources
+}
+
+/// Extracts the files named by the `include` directives of a makefile.
+fn parse_includes(source: &str) -> Vec<&str> {
+    source
+        .lines()
+        .filter(|line| !line.starts_with('\t'))
+        .filter_map(|line| {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            ["include", "-include", "sinclude"]
+                .iter()
+                .find_map(|directive| line.strip_prefix(directive))
+                .filter(|rest| rest.starts_with(char::is_whitespace))
+        })
+        .flat_map(str::split_whitespace)
+        .filter(|file| !file.contains(['$', '*', '?', '[']))
+        .collect()
+}
+
+/// Targets of the Makefile.
+pub struct MakeSource;
+
+impl TaskSource for MakeSource {
+    fn name(&self) -> &'static str {
+        "make"
+    }
+
+    fn describe(&self) -> &'static str {
+        "Makefile targets"
+    }
+
+    fn detect(&self, root: &Path, config: &Config) -> bool {
+        makefile(root, config).is_some()
+    }
+
+    fn discover(&self, config: &Config) -> Result<Vec<Task>> {
+        self.discover_with_inputs(config).map(|(tasks, _)| tasks)
+    }
+
+    fn discover_with_inputs(&self, config: &Config) -> Result<(Vec<Task>, Vec<PathBuf>)> {
+        let makefile = makefile(Path::new("."), config);
+        let (tasks, makefiles) = discover_tasks(config.files.makefile.as_deref())?;
+        Ok((defined_in(tasks, makefile), makefiles))
+    }
+
+    fn cache_inputs(&self, root: &Path, config: &Config) -> Option<Vec<PathBuf>> {
+        makefile(root, config).map(|makefile| makefile_sources(root, makefile))
+    }
+
+    fn tool_version(&self) -> Option<String> {
+        cache::tool_version("make")
+    }
+
+    fn refresh(&self, root: &Path, tasks: &mut [Task]) {
+        mark_file_targets(root, tasks);
+    }
+
+    fn watched_files(&self, root: &Path, config: &Config) -> Vec<PathBuf> {
+        self.cache_inputs(root, config).unwrap_or_else(|| {
+            ["GNUmakefile", "makefile", "Makefile"]
+                .iter()
+                .map(|name| root.join(name))
+                .collect()
+        })
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::Make
+    }
+
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        let mut command = make_command(task.source.as_deref());
+        command.arg(&task.name).args(args);
+        command
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_util::scratch_dir;
 
     #[test]
     fn test_parse_make_database_basic() {
@@ -217,11 +582,15 @@ clean:
 
         let tasks = parse_make_database(output).unwrap();
 
+        // Phony targets are listed first
         assert_eq!(tasks.len(), 3);
-        assert_eq!(tasks[0].name, "build");
-        assert_eq!(tasks[1].name, "test");
-        assert_eq!(tasks[2].name, "clean");
+        assert_eq!(tasks[0].name, "test");
+        assert_eq!(tasks[1].name, "clean");
+        assert_eq!(tasks[2].name, "build");
         assert!(tasks.iter().all(|t| t.runner == TaskRunner::Make));
+        assert_eq!(tasks[0].attributes, vec!["phony"]);
+        assert!(tasks[2].attributes.is_empty());
+        assert!(tasks.iter().all(|t| !t.is_hidden()));
     }
 
     #[test]
@@ -288,4 +657,266 @@ build:
         assert_eq!(tasks.len(), 1);
         assert_eq!(tasks[0].name, "build");
     }
+
+    #[test]
+    fn test_parse_make_database_classifies_targets() {
+        let output = r#"# Make data base, printed on Sun Jan  1 00:00:00 2024
+
+MAKEFILE_LIST :=  Makefile rules.mk
+
+# Files
+
+prog: main.o
+#  Implicit rule search has not been done.
+#  File does not exist.
+#  recipe to execute (from 'Makefile', line 6):
+	cc -o prog main.o
+
+.c.o:
+#  Builtin rule
+#  recipe to execute (built-in):
+	$(COMPILE.c) $(OUTPUT_OPTION) $<
+
+# Not a target:
+main.c:
+#  Implicit rule search has been done.
+#  Last modified 2024-01-01 00:00:00.000000000
+
+lint:
+#  Modification time never checked.
+#  recipe to execute (from 'Makefile', line 18):
+	echo lint
+
+release:
+#  recipe to execute (from 'rules.mk', line 2):
+	./release.sh
+
+clean:
+#  Phony target (prerequisite of .PHONY).
+#  File does not exist.
+#  recipe to execute (from 'Makefile', line 15):
+	rm -f prog
+
+main.o: main.c
+#  Implicit/static pattern stem: 'main'
+#  File does not exist.
+
+out/:
+#  recipe to execute (from 'Makefile', line 12):
+	mkdir -p out
+
+deploy.staging:
+#  File does not exist.
+#  recipe to execute (from 'Makefile', line 20):
+	./deploy.sh staging
+
+release-1.2:
+#  Modification time never checked.
+#  recipe to execute (from 'Makefile', line 22):
+	./release.sh 1.2
+
+docs/serve:
+#  File does not exist.
+#  recipe to execute (from 'Makefile', line 24):
+	mkdocs serve
+
+notes.txt: main.c
+#  Last modified 2024-01-01 00:00:00.000000000
+#  recipe to execute (from 'Makefile', line 26):
+	cat main.c > notes.txt
+
+all: prog
+#  Phony target (prerequisite of .PHONY).
+
+build: prog
+#  recipe to execute (from 'Makefile', line 2):
+	echo build
+
+.PHONY: build clean all
+"#;
+
+        let tasks = parse_make_database(output).unwrap();
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        // Phony first, then commands, then files; each by makefile and line
+        assert_eq!(
+            names,
+            vec![
+                "build",
+                "clean",
+                "all",
+                "prog",
+                "out/",
+                "lint",
+                "deploy.staging",
+                "release-1.2",
+                "docs/serve",
+                "release",
+                "notes.txt",
+                "main.o"
+            ]
+        );
+        assert!(!names.contains(&"main.c"));
+
+        let hidden: Vec<&str> = tasks
+            .iter()
+            .filter(|t| t.is_hidden())
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(hidden, vec!["notes.txt", "main.o"]);
+        assert!(tasks[..3].iter().all(|t| t.attributes == vec!["phony"]));
+        assert!(tasks.iter().enumerate().all(|(i, t)| t.id == i));
+    }
+
+    #[test]
+    fn test_mark_file_targets() {
+        let scratch = scratch_dir();
+        let root = scratch.path();
+        let target = |name: &str, phony: bool| Task {
+            name: name.to_string(),
+            runner: TaskRunner::Make,
+            attributes: if phony {
+                vec!["phony".to_string()]
+            } else {
+                Vec::new()
+            },
+            ..Default::default()
+        };
+        let mut tasks = vec![
+            target("lint", false),
+            target("docs", false),
+            target("test", true),
+        ];
+
+        mark_file_targets(root, &mut tasks);
+        assert!(tasks.iter().all(|t| !t.file_target));
+
+        // Only existing files that aren't phony are file targets
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("test"), "").unwrap();
+        mark_file_targets(root, &mut tasks);
+        let file_targets: Vec<&str> = tasks
+            .iter()
+            .filter(|t| t.file_target)
+            .map(|t| t.name.as_str())
+            .collect();
+        assert_eq!(file_targets, vec!["docs"]);
+    }
+
+    #[test]
+    fn test_parse_recipe_location() {
+        assert_eq!(
+            parse_recipe_location("recipe to execute (from 'mk/rules.mk', line 42):"),
+            Some(("mk/rules.mk", 42))
+        );
+        assert_eq!(parse_recipe_location("recipe to execute (built-in):"), None);
+        assert_eq!(parse_recipe_location("File does not exist."), None);
+    }
+
+    #[test]
+    fn test_parse_makefile_list() {
+        let output = r#"
+# makefile (from 'inc.mk', line 1)
+MAKEFILE_LIST := Makefile inc.mk
+
+build:
+"#;
+
+        let files = parse_makefile_list(output);
+        assert_eq!(
+            files,
+            vec![PathBuf::from("Makefile"), PathBuf::from("inc.mk")]
+        );
+        assert!(parse_makefile_list("build:\n").is_empty());
+    }
+
+    #[test]
+    fn test_parse_descriptions_inline() {
+        let source = "build: deps ## Compile everything\n\tcargo build\n\nlint fmt: ## Style checks\n\techo lint\n\nclean:\n\trm -rf target\n";
+
+        let descriptions = parse_descriptions(source);
+        assert_eq!(descriptions["build"], "Compile everything");
+        assert_eq!(descriptions["lint"], "Style checks");
+        assert_eq!(descriptions["fmt"], "Style checks");
+        assert!(!descriptions.contains_key("clean"));
+    }
+
+    #[test]
+    fn test_parse_descriptions_comment_blocks() {
+        let source = r#"##@ Development
+
+# Build the project
+# in release mode
+.PHONY: build
+build:
+	cargo build --release
+
+# Not attached to anything
+
+test:
+	cargo test
+
+## Deploy to production
+deploy: build ## Ship it
+	./deploy.sh
+
+CC := gcc
+# Release notes
+release:: ; echo release
+"#;
+
+        let descriptions = parse_descriptions(source);
+        assert_eq!(descriptions["build"], "Build the project\nin release mode");
+        assert!(!descriptions.contains_key("test"));
+        // The inline comment wins over the block above the rule
+        assert_eq!(descriptions["deploy"], "Ship it");
+        assert_eq!(descriptions["release"], "Release notes");
+        assert!(!descriptions.contains_key("CC"));
+        assert_eq!(descriptions.len(), 3);
+    }
+
+    #[test]
+    fn test_parse_descriptions_skips_assignments() {
+        let source = "# Compiler\nCC ::= gcc\nbuild: CFLAGS = -O2 ## Not a description\n# Build it\nbuild:\n\tcc main.c\n";
+
+        let descriptions = parse_descriptions(source);
+        assert_eq!(descriptions.len(), 1);
+        assert_eq!(descriptions["build"], "Build it");
+    }
+
+    #[test]
+    fn test_parse_includes() {
+        let source = "include common.mk rules.mk # shared rules\n-include .env\nsinclude $(DEPS) local.mk\ninclude\t*.mk\nincluded: build\n\tinclude not-a-directive\n";
+        assert_eq!(
+            parse_includes(source),
+            vec!["common.mk", "rules.mk", ".env", "local.mk"]
+        );
+    }
+
+    #[test]
+    fn test_makefile_sources() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        std::fs::create_dir_all(dir.join("mk")).unwrap();
+        std::fs::write(
+            dir.join("Makefile"),
+            "include mk/common.mk\n-include local.mk\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("mk/common.mk"),
+            "include mk/rules.mk\ninclude Makefile\n",
+        )
+        .unwrap();
+
+        let makefile = find_makefile(dir).unwrap();
+        assert_eq!(
+            makefile_sources(dir, makefile),
+            vec![
+                dir.join("Makefile"),
+                dir.join("mk/common.mk"),
+                dir.join("local.mk"),
+                dir.join("mk/rules.mk"),
+            ]
+        );
+    }
 }
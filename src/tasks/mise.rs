@@ -0,0 +1,215 @@
+/// mise task discovery module.
+///
+/// This module discovers the tasks mise knows about in the current directory
+/// (`[tasks]` in `mise.toml` and executable scripts in `.mise/tasks/` and
+/// similar directories) by running `mise tasks ls --json`.
+use crate::app::{Task, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::TaskSource;
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Config files and task directories mise reads tasks from.
+const MISE_FILES: &[&str] = &[
+    "mise.toml",
+    ".mise.toml",
+    "mise.local.toml",
+    ".mise.local.toml",
+    "mise/config.toml",
+    ".mise/config.toml",
+    ".config/mise.toml",
+    ".config/mise/config.toml",
+    "mise-tasks",
+    ".mise-tasks",
+    "mise/tasks",
+    ".mise/tasks",
+    ".config/mise/tasks",
+];
+
+/// A task from `mise tasks ls --json`.
+#[derive(Debug, Deserialize)]
+struct MiseTask {
+    name: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    /// File the task is defined in (config file or task script)
+    #[serde(default)]
+    source: Option<PathBuf>,
+    #[serde(default)]
+    depends: Vec<String>,
+    #[serde(default)]
+    hide: bool,
+    /// Commands run by a config task (empty for script tasks)
+    #[serde(default)]
+    run: Vec<String>,
+}
+
+/// Discovers mise tasks in the current directory.
+///
+/// This function:
+/// 1. Checks for a mise config file or task directory in the current directory
+/// 2. Runs `mise tasks ls --json --hidden` to list the tasks, including hidden ones
+/// 3. Drops tasks defined outside the current directory (e.g. global tasks
+///    from `~/.config/mise`)
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if:
+/// - There is no mise config or task directory in the current directory
+/// - `mise` is not installed or not on PATH
+/// - `mise tasks ls` returns an error (e.g. the config is not trusted)
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks() -> Result<Vec<Task>> {
+    if !MISE_FILES.iter().any(|name| Path::new(name).exists()) {
+        return Err(eyre!("No mise config found in this directory."));
+    }
+
+    let list_tasks = |args: &[&str]| {
+        Command::new("mise")
+            .args(args)
+            .output()
+            .map_err(|_| eyre!("mise not found on PATH. Please install mise and try again."))
+    };
+
+    // Older versions of mise don't know `--hidden`
+    let mut output = list_tasks(&["tasks", "ls", "--json", "--hidden"])?;
+    if !output.status.success() {
+        output = list_tasks(&["tasks", "ls", "--json"])?;
+    }
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("mise tasks ls failed: {}", stderr.trim()));
+    }
+
+    let root = std::env::current_dir()
+        .map_err(|e| eyre!("Failed to read the current directory: {}", e))?;
+    let tasks = parse_mise_tasks(&String::from_utf8_lossy(&output.stdout), &root)?;
+
+    if tasks.is_empty() {
+        return Err(eyre!(
+            "No mise tasks discovered. Are there tasks in mise.toml or .mise/tasks?"
+        ));
+    }
+
+    Ok(tasks)
+}
+
+/// Parses the output of `mise tasks ls --json` into tasks defined below `root`.
+///
+/// Tasks named with `:` (e.g. `test:unit` from `.mise/tasks/test/unit`) are
+/// grouped by their prefix, and hidden tasks are private.
+fn parse_mise_tasks(output: &str, root: &Path) -> Result<Vec<Task>> {
+    let listed: Vec<MiseTask> = serde_json::from_str(output)
+        .map_err(|e| eyre!("Failed to parse mise tasks ls output: {}", e))?;
+
+    let tasks = listed
+        .into_iter()
+        .filter(|task| task.source.iter().all(|source| source.starts_with(root)))
+        .enumerate()
+        .map(|(id, task)| {
+            let mut module_path: Vec<String> = task.name.split(':').map(str::to_string).collect();
+            module_path.pop();
+
+            Task {
+                id,
+                description: (!task.description.is_empty()).then_some(task.description),
+                runner: TaskRunner::Mise,
+                source: task.source,
+                aliases: task.aliases,
+                private: task.hide,
+                dependencies: task.depends,
+                body: task.run,
+                module_path,
+                command: vec!["run".to_string(), task.name.clone()],
+                name: task.name,
+                ..Default::default()
+            }
+        })
+        .collect();
+
+    Ok(tasks)
+}
+
+/// Tasks known to mise.
+pub struct MiseSource;
+
+impl TaskSource for MiseSource {
+    fn name(&self) -> &'static str {
+        "mise"
+    }
+
+    fn describe(&self) -> &'static str {
+        "mise tasks"
+    }
+
+    fn detect(&self, root: &Path, _config: &Config) -> bool {
+        MISE_FILES.iter().any(|name| root.join(name).exists())
+    }
+
+    fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+        discover_tasks()
+    }
+
+    fn watched_files(&self, root: &Path, _config: &Config) -> Vec<PathBuf> {
+        MISE_FILES.iter().map(|name| root.join(name)).collect()
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::Mise
+    }
+
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        let mut command = Command::new("mise");
+        command.args(&task.command).args(args);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mise_tasks() {
+        let root = Path::new("/home/user/project");
+        let tasks = parse_mise_tasks(include_str!("fixtures/mise/tasks.json"), root).unwrap();
+
+        // The global task from ~/.config/mise is not part of the project
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["build", "lint", "release", "test:unit"]);
+        assert!(tasks.iter().enumerate().all(|(i, t)| t.id == i));
+        assert!(tasks.iter().all(|t| t.runner == TaskRunner::Mise));
+
+        let build = &tasks[0];
+        assert_eq!(build.description, Some("Build the project".to_string()));
+        assert_eq!(build.aliases, vec!["b"]);
+        assert_eq!(build.dependencies, vec!["lint"]);
+        assert_eq!(build.body, vec!["cargo build --release"]);
+        assert_eq!(build.command, vec!["run", "build"]);
+        assert!(!build.is_hidden());
+
+        assert_eq!(tasks[1].description, None);
+        assert!(tasks[2].is_hidden());
+
+        let unit = &tasks[3];
+        assert_eq!(unit.module_path, vec!["test"]);
+        assert_eq!(unit.short_name(), "unit");
+        assert_eq!(unit.command, vec!["run", "test:unit"]);
+        assert!(unit.body.is_empty());
+    }
+
+    #[test]
+    fn test_parse_mise_tasks_empty() {
+        let tasks = parse_mise_tasks("[]", Path::new("/home/user/project")).unwrap();
+        assert!(tasks.is_empty());
+        assert!(parse_mise_tasks("not json", Path::new("/")).is_err());
+    }
+}
@@ -1,54 +1,138 @@
 /// Task discovery modules.
 ///
 /// This module provides functionality for discovering tasks from various sources.
-/// Supports both Just recipes and Make targets.
-use crate::app::Task;
-use color_eyre::eyre::Result;
+/// Supports Just recipes, Make targets, Taskfile tasks, mise tasks,
+/// package.json scripts, Cargo tasks, Python project tasks, docker compose
+/// services, VS Code tasks, script directories, custom tasks from the
+/// Taskpad config and external `taskpad-source-<name>` executables. Each
+/// source implements [`registry::TaskSource`] and is registered in
+/// [`registry::SourceRegistry::builtin`]. Discovery runs on a background
+/// thread, so the TUI stays responsive while task runners are queried.
+use crate::config::{self, TaskFiles};
+use cache::DiscoveryCache;
+use color_eyre::eyre::{eyre, Result};
+use registry::{Discovery, SourceDiagnostic, SourceOutcome, SourceRegistry};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+use std::thread;
 
+/// Progress of a discovery running in the background.
+#[derive(Debug)]
+pub enum DiscoveryEvent {
+    /// The state of a source changed
+    Source(SourceDiagnostic),
+    /// Every source finished
+    Finished {
+        result: Result<Discovery>,
+        /// Files the tasks are read from, to watch for changes
+        watched: Vec<PathBuf>,
+    },
+}
+
+/// Starts discovering tasks from all available sources on a background thread
+/// (see [`discover_all_tasks`]) and returns the receiver of its progress.
+pub fn spawn_discovery(files: TaskFiles) -> Receiver<DiscoveryEvent> {
+    let (tx, rx) = channel();
+    thread::spawn(move || {
+        let source_tx = Mutex::new(tx.clone());
+        let result = discover_all_tasks(&files, &|diagnostic| {
+            if let Ok(tx) = source_tx.lock() {
+                let _ = tx.send(DiscoveryEvent::Source(diagnostic.clone()));
+            }
+        });
+        let mut watched = watched_files(&files);
+        if let Ok(discovery) = &result {
+            watched.extend(discovery.inputs.iter().cloned());
+        }
+        let _ = tx.send(DiscoveryEvent::Finished { result, watched });
+    });
+    rx
+}
+
+pub mod cache;
+pub mod cargo;
+pub mod compose;
+pub mod custom;
+pub mod external;
+pub mod go_task;
 pub mod just;
 pub mod make;
+pub mod mise;
+pub mod package_json;
+pub mod python;
+pub mod registry;
+pub mod scripts;
+pub mod vscode;
+
+/// Returns the files tasks are read from: the config files and the files
+/// of every enabled source, whether they exist or not. Files only discovery
+/// can find (see [`Discovery::inputs`]) are added to these.
+fn watched_files(files: &TaskFiles) -> Vec<PathBuf> {
+    // Watch the config files even if they are broken, to reload once fixed
+    let mut config = config::load().unwrap_or_default();
+    config.files = files.clone();
+    let mut files = config::config_files();
+    files.extend(SourceRegistry::builtin().watched_files(Path::new("."), &config));
+    files
+}
 
-/// Discovers tasks from all available sources (Just and Make).
+/// Discovers tasks from all available sources (custom tasks, Just, Make, Task, mise,
+/// package.json, Cargo, Python, docker compose, VS Code, scripts and external sources).
 ///
-/// This function attempts to discover tasks from:
+/// This function loads the Taskpad config and runs the built-in sources
+/// concurrently, listing their tasks in the default order (or the order set
+/// under `sources` in the config):
+/// 0. Custom tasks (defined in the global config or .taskpad.json5)
 /// 1. Just recipes (if justfile exists)
 /// 2. Make targets (if Makefile exists)
+/// 3. Task tasks (if Taskfile.yml exists)
+/// 4. mise tasks (if mise.toml or a mise task directory exists)
+/// 5. package.json scripts (if package.json exists)
+/// 6. Cargo aliases, binaries and examples (if Cargo.toml exists)
+/// 7. Python scripts, tox environments and nox sessions (if pyproject.toml,
+///    tox.ini or noxfile.py exists)
+/// 8. docker compose services (if compose.yaml or docker-compose.yml exists)
+/// 9. VS Code tasks (if .vscode/tasks.json exists)
+/// 10. Executables in scripts/ and bin/
+/// 11. External sources (`taskpad-source-<name>` on PATH or listed in the config)
 ///
-/// Tasks from both sources are combined into a single list with unique IDs.
-/// If both sources are available, tasks are prefixed with [just] or [make]
-/// in the UI (handled by the TaskRunner in the Task struct).
+/// Tasks from all sources are combined into a single list with unique IDs.
+/// Tasks are prefixed with [just], [make], [task], [mise], [cargo], the package
+/// manager (e.g. [pnpm]), the Python tool (e.g. [poetry], [tox]), [compose], [vscode],
+/// [script], [custom] or the external source's name in the UI (handled by the TaskRunner in the Task struct).
 ///
 /// # Returns
 ///
-/// Returns `Ok(Vec<Task>)` with all discovered tasks from available sources.
-/// Returns an error only if no tasks could be discovered from any source.
-pub fn discover_all_tasks() -> Result<Vec<Task>> {
-    let mut all_tasks = Vec::new();
-    let mut next_id = 0;
-
-    // Try to discover Just recipes
-    if let Ok(just_tasks) = just::discover_tasks() {
-        for mut task in just_tasks {
-            task.id = next_id;
-            next_id += 1;
-            all_tasks.push(task);
-        }
+/// Returns `Ok(Discovery)` with all discovered tasks and the diagnostics of
+/// every source, which are also passed to `progress` as sources start and
+/// finish. Returns an error if a config file is invalid, or if no tasks could
+/// be discovered from any source.
+///
+/// Sources that support it reuse the tasks cached in the data directory while
+/// the files they were read from are unchanged. Just and Make use the task
+/// `files` chosen on the command line, if any.
+pub fn discover_all_tasks(
+    files: &TaskFiles,
+    progress: &(dyn Fn(&SourceDiagnostic) + Sync),
+) -> Result<Discovery> {
+    let mut config = config::load()?;
+    config.files = files.clone();
+    let mut registry = SourceRegistry::builtin();
+    if let Some(cache) = DiscoveryCache::open() {
+        registry = registry.with_cache(cache);
     }
+    let discovery = registry.discover(Path::new("."), &config, progress);
 
-    // Try to discover Make targets
-    if let Ok(make_tasks) = make::discover_tasks() {
-        for mut task in make_tasks {
-            task.id = next_id;
-            next_id += 1;
-            all_tasks.push(task);
+    if discovery.tasks.is_empty() {
+        let mut message = "No tasks discovered. Please ensure you have a justfile, Makefile, Taskfile.yml, mise.toml, package.json, Cargo.toml, pyproject.toml, compose.yaml or .vscode/tasks.json in this directory, or define tasks in .taskpad.json5.".to_string();
+        for diagnostic in discovery.failures() {
+            if let SourceOutcome::Failed(error) = &diagnostic.outcome {
+                message.push_str(&format!("\n{}: {}", diagnostic.source, error));
+            }
         }
+        return Err(eyre!(message));
     }
 
-    if all_tasks.is_empty() {
-        return Err(color_eyre::eyre::eyre!(
-            "No tasks discovered. Please ensure you have either a justfile or Makefile in this directory."
-        ));
-    }
-
-    Ok(all_tasks)
+    Ok(discovery)
 }
@@ -0,0 +1,603 @@
+/// package.json script discovery module.
+///
+/// This module discovers the `scripts` of the package.json in the current
+/// directory and of its workspace packages. The package manager that runs them
+/// (npm, pnpm, yarn or bun) is detected from the lockfile.
+use crate::app::{PackageManager, Task, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::TaskSource;
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The parts of a package.json used for discovery.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PackageJson {
+    name: Option<String>,
+    /// Script names and command lines, in file order
+    scripts: Map<String, Value>,
+    workspaces: Option<Workspaces>,
+    /// Corepack's `name@version` declaration, e.g. `pnpm@9.1.0`
+    #[serde(rename = "packageManager")]
+    package_manager: Option<String>,
+}
+
+/// The `workspaces` field: a list of globs, or yarn's `{ "packages": [...] }` form.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Workspaces {
+    List(Vec<String>),
+    Config {
+        #[serde(default)]
+        packages: Vec<String>,
+    },
+}
+
+impl PackageJson {
+    /// Returns the workspace globs declared in package.json
+    fn workspace_patterns(&self) -> Vec<String> {
+        match &self.workspaces {
+            Some(Workspaces::List(patterns)) => patterns.clone(),
+            Some(Workspaces::Config { packages }) => packages.clone(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Lockfiles identifying the package manager, checked in order.
+const LOCKFILES: &[(&str, PackageManager)] = &[
+    ("pnpm-lock.yaml", PackageManager::Pnpm),
+    ("yarn.lock", PackageManager::Yarn),
+    ("bun.lockb", PackageManager::Bun),
+    ("bun.lock", PackageManager::Bun),
+    ("package-lock.json", PackageManager::Npm),
+    ("npm-shrinkwrap.json", PackageManager::Npm),
+];
+
+/// Discovers package.json scripts in the current directory.
+///
+/// This function:
+/// 1. Reads `package.json` in the current directory
+/// 2. Detects the package manager from the lockfile (falling back to the
+///    `packageManager` field, then npm)
+/// 3. Lists the root package's scripts
+/// 4. Lists the scripts of every workspace package (from the `workspaces` field
+///    or `pnpm-workspace.yaml`), grouped under the package name
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if:
+/// - There is no package.json in the current directory
+/// - The package.json is not valid JSON
+/// - Neither the package nor its workspaces define any scripts
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks() -> Result<Vec<Task>> {
+    let root = Path::new(".");
+    let source = fs::read_to_string(root.join("package.json"))
+        .map_err(|_| eyre!("No package.json found in this directory."))?;
+    let package = parse_package_json(&source)?;
+    let manager = detect_package_manager(root, &package);
+
+    let mut tasks = package_tasks(&package, manager, None, None);
+
+    for dir in workspace_dirs(root, &package) {
+        // A broken workspace package shouldn't hide the rest
+        let Ok(source) = fs::read_to_string(root.join(&dir).join("package.json")) else {
+            continue;
+        };
+        let Ok(member) = parse_package_json(&source) else {
+            continue;
+        };
+        let module = member
+            .name
+            .clone()
+            .unwrap_or_else(|| dir.display().to_string());
+        tasks.extend(package_tasks(&member, manager, Some(module), Some(dir)));
+    }
+
+    if tasks.is_empty() {
+        return Err(eyre!("No scripts found in package.json."));
+    }
+
+    for (id, task) in tasks.iter_mut().enumerate() {
+        task.id = id;
+    }
+    Ok(tasks)
+}
+
+/// Returns the directories of the workspace packages of `package` in `root`
+/// (from its `workspaces` field and `pnpm-workspace.yaml`), relative to `root`.
+fn workspace_dirs(root: &Path, package: &PackageJson) -> Vec<PathBuf> {
+    let mut patterns = package.workspace_patterns();
+    if let Ok(yaml) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        patterns.extend(parse_pnpm_workspace(&yaml));
+    }
+    expand_workspaces(root, &patterns)
+}
+
+/// Parses the contents of a package.json.
+fn parse_package_json(source: &str) -> Result<PackageJson> {
+    serde_json::from_str(source).map_err(|e| eyre!("Failed to parse package.json: {}", e))
+}
+
+/// Detects the package manager of the project in `dir`.
+///
+/// Lockfiles are checked first because they reflect what the project is
+/// actually installed with; the `packageManager` field comes next and npm is
+/// the default.
+fn detect_package_manager(dir: &Path, package: &PackageJson) -> PackageManager {
+    LOCKFILES
+        .iter()
+        .find(|(lockfile, _)| dir.join(lockfile).exists())
+        .map(|&(_, manager)| manager)
+        .or_else(|| {
+            package
+                .package_manager
+                .as_deref()
+                .and_then(manager_from_field)
+        })
+        .unwrap_or_default()
+}
+
+/// Parses a `packageManager` field like `yarn@4.1.0`.
+fn manager_from_field(field: &str) -> Option<PackageManager> {
+    let name = field.split('@').next().unwrap_or_default();
+    match name {
+        "npm" => Some(PackageManager::Npm),
+        "pnpm" => Some(PackageManager::Pnpm),
+        "yarn" => Some(PackageManager::Yarn),
+        "bun" => Some(PackageManager::Bun),
+        _ => None,
+    }
+}
+
+/// Turns the scripts of a package into tasks.
+///
+/// Scripts of workspace packages are named `<module>::<script>` and grouped in
+/// a module, so they show up as a tree in the task list and run in `cwd`.
+fn package_tasks(
+    package: &PackageJson,
+    manager: PackageManager,
+    module: Option<String>,
+    cwd: Option<PathBuf>,
+) -> Vec<Task> {
+    package
+        .scripts
+        .iter()
+        .map(|(script, command)| Task {
+            name: match &module {
+                Some(module) => format!("{}::{}", module, script),
+                None => script.clone(),
+            },
+            description: command.as_str().map(str::to_string),
+            runner: TaskRunner::Package(manager),
+            source: Some(cwd.as_deref().unwrap_or(Path::new("")).join("package.json")),
+            module_path: module.iter().cloned().collect(),
+            cwd: cwd.clone(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Extracts the package globs from a `pnpm-workspace.yaml`.
+///
+/// Only the block list form used in practice is understood:
+/// ```text
+/// packages:
+///   - 'packages/*'
+///   - "!**/test/**"
+/// ```
+fn parse_pnpm_workspace(yaml: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+
+    for line in yaml.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+
+        // A top-level key starts a new section
+        if !line.starts_with(char::is_whitespace) && !trimmed.starts_with('-') {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                let item = item.trim();
+                let item = item
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| item.strip_prefix('"').and_then(|s| s.strip_suffix('"')))
+                    .unwrap_or(item);
+                patterns.push(item.to_string());
+            }
+        }
+    }
+
+    patterns
+}
+
+/// Expands workspace globs into the package directories below `root`.
+///
+/// Supports `*` and `?` within a path segment, `**` for any number of
+/// directories and `!` to exclude matches. Only directories containing a
+/// package.json are returned, as paths relative to `root`, in pattern order.
+fn expand_workspaces(root: &Path, patterns: &[String]) -> Vec<PathBuf> {
+    let mut included = Vec::new();
+    let mut excluded = Vec::new();
+
+    for pattern in patterns {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern.as_str()),
+        };
+        let segments: Vec<&str> = pattern
+            .split('/')
+            .filter(|s| !s.is_empty() && *s != ".")
+            .collect();
+
+        let mut matches = Vec::new();
+        match_segments(root, &segments, &mut matches);
+        if negated {
+            excluded.extend(matches);
+        } else {
+            included.extend(matches);
+        }
+    }
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in included {
+        if excluded.contains(&dir) || dirs.contains(&dir) || !dir.join("package.json").is_file() {
+            continue;
+        }
+        if let Ok(relative) = dir.strip_prefix(root) {
+            if !relative.as_os_str().is_empty() {
+                dirs.push(relative.to_path_buf());
+            }
+        }
+    }
+    dirs
+}
+
+/// Collects the directories below `dir` matching the glob `segments`.
+fn match_segments(dir: &Path, segments: &[&str], matches: &mut Vec<PathBuf>) {
+    let Some((segment, rest)) = segments.split_first() else {
+        matches.push(dir.to_path_buf());
+        return;
+    };
+
+    if !segment.contains(['*', '?']) {
+        let next = dir.join(segment);
+        if next.is_dir() {
+            match_segments(&next, rest, matches);
+        }
+        return;
+    }
+
+    // `**` also matches no directory at all
+    if *segment == "**" {
+        match_segments(dir, rest, matches);
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut subdirs: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter(|path| {
+            let name = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or_default();
+            // Installed dependencies are never workspace packages
+            name != "node_modules" && !name.starts_with('.')
+        })
+        .collect();
+    subdirs.sort();
+
+    for subdir in subdirs {
+        let name = subdir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        if *segment == "**" {
+            match_segments(&subdir, segments, matches);
+        } else if wildcard_match(segment, name) {
+            match_segments(&subdir, rest, matches);
+        }
+    }
+}
+
+/// Matches `name` against a pattern where `*` matches any run of characters
+/// and `?` matches a single character.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Position to resume from after the last `*`: (pattern index, name index)
+    let mut star: Option<(usize, usize)> = None;
+    let (mut p, mut n) = (0, 0);
+
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p + 1, n));
+            p += 1;
+        } else if let Some((star_p, star_n)) = star {
+            // Let the last `*` swallow one more character
+            p = star_p;
+            n = star_n + 1;
+            star = Some((star_p, star_n + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Scripts of package.json and its workspace packages.
+pub struct PackageJsonSource;
+
+impl TaskSource for PackageJsonSource {
+    fn name(&self) -> &'static str {
+        "package-json"
+    }
+
+    fn describe(&self) -> &'static str {
+        "package.json scripts"
+    }
+
+    fn detect(&self, root: &Path, _config: &Config) -> bool {
+        root.join("package.json").is_file()
+    }
+
+    fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+        discover_tasks()
+    }
+
+    fn watched_files(&self, root: &Path, _config: &Config) -> Vec<PathBuf> {
+        // Lockfiles decide the package manager
+        let mut files: Vec<PathBuf> = ["package.json", "pnpm-workspace.yaml"]
+            .iter()
+            .chain(LOCKFILES.iter().map(|(name, _)| name))
+            .map(|name| root.join(name))
+            .collect();
+
+        // Scripts of workspace packages are read from their package.json
+        let package = fs::read_to_string(root.join("package.json"))
+            .ok()
+            .and_then(|source| parse_package_json(&source).ok());
+        if let Some(package) = package {
+            files.extend(
+                workspace_dirs(root, &package)
+                    .into_iter()
+                    .map(|dir| root.join(dir).join("package.json")),
+            );
+        }
+        files
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        matches!(runner, TaskRunner::Package(_))
+    }
+
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        let manager = match task.runner {
+            TaskRunner::Package(manager) => manager,
+            _ => PackageManager::default(),
+        };
+        let mut command = Command::new(manager.command());
+        command.arg("run").arg(task.short_name());
+        // npm only passes arguments after `--` on to the script
+        if manager == PackageManager::Npm && !args.is_empty() {
+            command.arg("--");
+        }
+        command.args(args);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_dir;
+
+    #[test]
+    fn test_package_tasks_keep_script_order() {
+        let package = parse_package_json(
+            r#"{
+                "name": "app",
+                "scripts": {
+                    "dev": "vite",
+                    "build": "tsc && vite build",
+                    "attest": "vitest run"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let tasks = package_tasks(&package, PackageManager::Pnpm, None, None);
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["dev", "build", "attest"]);
+        assert_eq!(tasks[1].description, Some("tsc && vite build".to_string()));
+        assert_eq!(tasks[1].runner, TaskRunner::Package(PackageManager::Pnpm));
+        assert!(tasks[1].module_path.is_empty());
+        assert_eq!(tasks[1].cwd, None);
+        assert_eq!(tasks[1].source, Some(PathBuf::from("package.json")));
+    }
+
+    #[test]
+    fn test_package_tasks_in_workspace_module() {
+        let package =
+            parse_package_json(r#"{ "name": "@acme/web", "scripts": { "lint": "eslint ." } }"#)
+                .unwrap();
+
+        let tasks = package_tasks(
+            &package,
+            PackageManager::Npm,
+            Some("@acme/web".to_string()),
+            Some(PathBuf::from("packages/web")),
+        );
+        assert_eq!(tasks[0].name, "@acme/web::lint");
+        assert_eq!(tasks[0].short_name(), "lint");
+        assert_eq!(tasks[0].module_path, vec!["@acme/web"]);
+        assert_eq!(tasks[0].cwd, Some(PathBuf::from("packages/web")));
+        assert_eq!(
+            tasks[0].source,
+            Some(PathBuf::from("packages/web/package.json"))
+        );
+    }
+
+    #[test]
+    fn test_parse_package_json_workspaces() {
+        let list = parse_package_json(r#"{ "workspaces": ["packages/*", "apps/web"] }"#).unwrap();
+        assert_eq!(list.workspace_patterns(), vec!["packages/*", "apps/web"]);
+
+        let config =
+            parse_package_json(r#"{ "workspaces": { "packages": ["libs/*"], "nohoist": [] } }"#)
+                .unwrap();
+        assert_eq!(config.workspace_patterns(), vec!["libs/*"]);
+
+        let none = parse_package_json("{}").unwrap();
+        assert!(none.workspace_patterns().is_empty());
+        assert!(none.scripts.is_empty());
+
+        assert!(parse_package_json("{ not json").is_err());
+    }
+
+    #[test]
+    fn test_detect_package_manager() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let declared = parse_package_json(r#"{ "packageManager": "yarn@4.1.0" }"#).unwrap();
+        let plain = PackageJson::default();
+
+        assert_eq!(detect_package_manager(dir, &plain), PackageManager::Npm);
+        assert_eq!(detect_package_manager(dir, &declared), PackageManager::Yarn);
+
+        // A lockfile wins over the declared package manager
+        fs::write(dir.join("bun.lockb"), "").unwrap();
+        assert_eq!(detect_package_manager(dir, &declared), PackageManager::Bun);
+        fs::write(dir.join("pnpm-lock.yaml"), "").unwrap();
+        assert_eq!(detect_package_manager(dir, &plain), PackageManager::Pnpm);
+    }
+
+    #[test]
+    fn test_manager_from_field() {
+        assert_eq!(manager_from_field("pnpm@9.1.0"), Some(PackageManager::Pnpm));
+        assert_eq!(
+            manager_from_field("npm@10.2.0+sha256.abc"),
+            Some(PackageManager::Npm)
+        );
+        assert_eq!(manager_from_field("bun"), Some(PackageManager::Bun));
+        assert_eq!(manager_from_field("deno@1.0.0"), None);
+    }
+
+    #[test]
+    fn test_parse_pnpm_workspace() {
+        let yaml = r#"# workspace
+packages:
+  - 'packages/*'
+  - "apps/**"
+  # not this one
+  - '!**/test/**'
+catalog:
+  react: ^18.0.0
+"#;
+
+        assert_eq!(
+            parse_pnpm_workspace(yaml),
+            vec!["packages/*", "apps/**", "!**/test/**"]
+        );
+        assert!(parse_pnpm_workspace("catalog:\n  - nope\n").is_empty());
+    }
+
+    #[test]
+    fn test_expand_workspaces() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        for package in [
+            "packages/web",
+            "packages/api",
+            "packages/internal",
+            "apps/site/docs",
+            "apps/node_modules/dep",
+            "tools/cli",
+        ] {
+            fs::create_dir_all(dir.join(package)).unwrap();
+            fs::write(dir.join(package).join("package.json"), "{}").unwrap();
+        }
+        // Directories without a package.json are not packages
+        fs::create_dir_all(dir.join("packages/empty")).unwrap();
+
+        let patterns: Vec<String> = ["packages/*", "!packages/internal", "apps/**", "./tools/cli"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        let packages = expand_workspaces(dir, &patterns);
+        assert_eq!(
+            packages,
+            vec![
+                PathBuf::from("packages/api"),
+                PathBuf::from("packages/web"),
+                PathBuf::from("apps/site/docs"),
+                PathBuf::from("tools/cli"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_watched_files_include_workspace_packages() {
+        let scratch = scratch_dir();
+        let root = scratch.path();
+        fs::write(
+            root.join("package.json"),
+            r#"{ "workspaces": ["packages/*"] }"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("pnpm-workspace.yaml"),
+            "packages:\n  - tools/cli\n",
+        )
+        .unwrap();
+        for package in ["packages/web", "tools/cli"] {
+            fs::create_dir_all(root.join(package)).unwrap();
+            fs::write(root.join(package).join("package.json"), "{}").unwrap();
+        }
+
+        let files = PackageJsonSource.watched_files(root, &Config::default());
+        for file in [
+            "package.json",
+            "pnpm-workspace.yaml",
+            "pnpm-lock.yaml",
+            "packages/web/package.json",
+            "tools/cli/package.json",
+        ] {
+            assert!(files.contains(&root.join(file)), "{} is not watched", file);
+        }
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("*", "web"));
+        assert!(wildcard_match("pkg-*", "pkg-web"));
+        assert!(wildcard_match("*-app", "web-app"));
+        assert!(wildcard_match("a*b*c", "aXXbYYbc"));
+        assert!(wildcard_match("we?", "web"));
+        assert!(!wildcard_match("pkg-*", "web"));
+        assert!(!wildcard_match("we?", "webs"));
+    }
+}
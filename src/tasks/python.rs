@@ -0,0 +1,594 @@
+/// Python project task discovery module.
+///
+/// This module discovers the commands Python projects define:
+/// - scripts in `pyproject.toml` (`[project.scripts]`, `[tool.poetry.scripts]`,
+///   `[tool.pdm.scripts]` and `[tool.hatch.envs.<env>.scripts]`)
+/// - environments in `tox.ini`
+/// - sessions in `noxfile.py`, listed with `nox --list --json`
+use crate::app::{PythonTool, Task, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::TaskSource;
+use color_eyre::eyre::{eyre, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use toml::{Table, Value};
+
+/// A session from `nox --list --json`.
+#[derive(Debug, Deserialize)]
+struct NoxSession {
+    /// Name selecting the session, including its parametrization (`tests-3.11`)
+    session: String,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Discovers Python project tasks in the current directory.
+///
+/// This function:
+/// 1. Reads the scripts from `pyproject.toml`, if it exists
+/// 2. Reads the environments from `tox.ini`, if it exists
+/// 3. Runs `nox --list --json` if a `noxfile.py` exists
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if none of
+/// these files exist or none of them define any tasks.
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks() -> Result<Vec<Task>> {
+    let root = Path::new(".");
+    let mut tasks = Vec::new();
+    let mut errors = Vec::new();
+
+    if let Ok(source) = std::fs::read_to_string(root.join("pyproject.toml")) {
+        match parse_pyproject(&source, detect_tool(root)) {
+            Ok(scripts) => tasks.extend(scripts),
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+
+    if let Ok(source) = std::fs::read_to_string(root.join("tox.ini")) {
+        tasks.extend(parse_tox_ini(&source));
+    }
+
+    if root.join("noxfile.py").is_file() {
+        match list_nox_sessions() {
+            Ok(sessions) => tasks.extend(sessions),
+            Err(e) => errors.push(e.to_string()),
+        }
+    }
+
+    if tasks.is_empty() {
+        return Err(match errors.is_empty() {
+            true => eyre!("No pyproject.toml scripts, tox.ini environments or nox sessions found."),
+            false => eyre!(errors.join("\n")),
+        });
+    }
+
+    for (id, task) in tasks.iter_mut().enumerate() {
+        task.id = id;
+    }
+    Ok(tasks)
+}
+
+/// Picks the tool that runs `[project.scripts]` from the project's lockfile,
+/// defaulting to uv, which can run the scripts of any standard project.
+fn detect_tool(root: &Path) -> PythonTool {
+    [
+        ("poetry.lock", PythonTool::Poetry),
+        ("pdm.lock", PythonTool::Pdm),
+        ("uv.lock", PythonTool::Uv),
+    ]
+    .into_iter()
+    .find(|(lockfile, _)| root.join(lockfile).exists())
+    .map(|(_, tool)| tool)
+    .unwrap_or(PythonTool::Uv)
+}
+
+/// Parses the scripts of a `pyproject.toml`.
+///
+/// `[tool.poetry.scripts]`, `[tool.pdm.scripts]` and hatch scripts run through
+/// their tool. `[project.scripts]` entry points run through `tool` (the tool
+/// owning the project's lockfile), unless they were already listed as poetry
+/// scripts. Hatch scripts of environments other than `default` are grouped
+/// under the environment.
+fn parse_pyproject(source: &str, tool: PythonTool) -> Result<Vec<Task>> {
+    let pyproject: Table =
+        toml::from_str(source).map_err(|e| eyre!("Failed to parse pyproject.toml: {}", e))?;
+    let section = |path: &[&str]| -> Option<&Table> {
+        path.iter()
+            .try_fold(&pyproject, |table, key| table.get(*key)?.as_table())
+    };
+
+    let mut tasks = Vec::new();
+
+    for (name, target) in section(&["tool", "poetry", "scripts"])
+        .into_iter()
+        .flatten()
+    {
+        tasks.push(python_task(
+            PythonTool::Poetry,
+            name,
+            &["run", name],
+            script_description(target),
+        ));
+    }
+
+    for (name, target) in section(&["project", "scripts"]).into_iter().flatten() {
+        if tasks.iter().any(|t| t.name == *name) {
+            continue;
+        }
+        tasks.push(python_task(
+            tool,
+            name,
+            &["run", name],
+            script_description(target),
+        ));
+    }
+
+    for (name, script) in section(&["tool", "pdm", "scripts"]).into_iter().flatten() {
+        // `_` holds options shared by all scripts
+        if name == "_" {
+            continue;
+        }
+        tasks.push(python_task(
+            PythonTool::Pdm,
+            name,
+            &["run", name],
+            pdm_description(script),
+        ));
+    }
+
+    for (env, config) in section(&["tool", "hatch", "envs"]).into_iter().flatten() {
+        let Some(scripts) = config.get("scripts").and_then(Value::as_table) else {
+            continue;
+        };
+        for (name, script) in scripts {
+            let (task_name, module_path) = match env.as_str() {
+                "default" => (name.clone(), Vec::new()),
+                _ => (format!("{}:{}", env, name), vec![env.clone()]),
+            };
+            let mut task = python_task(
+                PythonTool::Hatch,
+                &task_name,
+                &["run", &task_name],
+                script_description(script),
+            );
+            task.module_path = module_path;
+            tasks.push(task);
+        }
+    }
+
+    Ok(tasks)
+}
+
+/// Describes a script given as a command (or entry point) or a list of commands.
+fn script_description(script: &Value) -> Option<String> {
+    match script {
+        Value::String(command) => Some(command.clone()),
+        Value::Array(commands) => Some(
+            commands
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        _ => None,
+    }
+}
+
+/// Describes a pdm script: its `help`, or the command it runs.
+///
+/// pdm scripts are a command string or a table with `cmd`, `shell`, `call` or
+/// `composite` and an optional `help`.
+fn pdm_description(script: &Value) -> Option<String> {
+    let Value::Table(table) = script else {
+        return script_description(script);
+    };
+
+    if let Some(help) = table.get("help").and_then(Value::as_str) {
+        return Some(help.to_string());
+    }
+    ["cmd", "shell", "call", "composite"]
+        .iter()
+        .find_map(|key| table.get(*key))
+        .and_then(|command| match command {
+            // `cmd` may also be given as a list of arguments
+            Value::Array(args) if table.contains_key("cmd") => Some(
+                args.iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ),
+            other => script_description(other),
+        })
+}
+
+/// Parses the environments of a `tox.ini`.
+///
+/// Environments come from `envlist` in `[tox]` (with generative names like
+/// `py{311,312}-django` expanded) followed by any other `[testenv:<name>]`
+/// sections. The `description` of an environment's section is its description.
+fn parse_tox_ini(source: &str) -> Vec<Task> {
+    let sections = parse_ini(source);
+    let value = |section: &str, key: &str| {
+        sections
+            .iter()
+            .find(|(name, _)| name == section)
+            .and_then(|(_, entries)| entries.iter().find(|(k, _)| k == key))
+            .map(|(_, value)| value.as_str())
+    };
+
+    let mut envs: Vec<String> = Vec::new();
+    if let Some(envlist) = value("tox", "envlist") {
+        for env in split_envlist(envlist) {
+            for env in expand_braces(&env) {
+                if !envs.contains(&env) {
+                    envs.push(env);
+                }
+            }
+        }
+    }
+    for (section, _) in &sections {
+        if let Some(env) = section.strip_prefix("testenv:") {
+            // Sections may share settings between several environments
+            for env in split_envlist(env).iter().flat_map(|env| expand_braces(env)) {
+                if !envs.contains(&env) {
+                    envs.push(env);
+                }
+            }
+        }
+    }
+
+    envs.into_iter()
+        .map(|env| {
+            let description = value(&format!("testenv:{}", env), "description")
+                .or_else(|| value("testenv", "description"))
+                .map(str::to_string);
+            python_task(PythonTool::Tox, &env, &["-e", &env], description)
+        })
+        .collect()
+}
+
+/// Parses an INI file into its sections and their entries, in file order.
+///
+/// Indented lines continue the previous value (joined with newlines), and
+/// `#`/`;` lines are comments.
+fn parse_ini(source: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+
+    for line in source.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+            continue;
+        }
+
+        if let Some(name) = trimmed.strip_prefix('[').and_then(|s| s.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), Vec::new()));
+            continue;
+        }
+
+        let Some((_, entries)) = sections.last_mut() else {
+            continue;
+        };
+        if line.starts_with(char::is_whitespace) {
+            if let Some((_, value)) = entries.last_mut() {
+                if !value.is_empty() {
+                    value.push('\n');
+                }
+                value.push_str(trimmed);
+            }
+        } else if let Some((key, value)) = trimmed.split_once('=') {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    sections
+}
+
+/// Splits a tox env list on commas and newlines, keeping commas inside braces.
+fn split_envlist(envlist: &str) -> Vec<String> {
+    let mut envs = Vec::new();
+    let mut env = String::new();
+    let mut depth = 0;
+
+    for ch in envlist.chars() {
+        match ch {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' | '\n' if depth == 0 => {
+                envs.push(std::mem::take(&mut env));
+                continue;
+            }
+            _ => {}
+        }
+        env.push(ch);
+    }
+    envs.push(env);
+
+    envs.into_iter()
+        .map(|env| env.trim().to_string())
+        .filter(|env| !env.is_empty())
+        .collect()
+}
+
+/// Expands tox's generative names: `py{311,312}-{a,b}` becomes
+/// `py311-a`, `py311-b`, `py312-a` and `py312-b`.
+fn expand_braces(env: &str) -> Vec<String> {
+    let (Some(open), Some(close)) = (env.find('{'), env.find('}')) else {
+        return vec![env.to_string()];
+    };
+    if close < open {
+        return vec![env.to_string()];
+    }
+
+    let (prefix, rest) = (&env[..open], &env[close + 1..]);
+    let mut expanded = Vec::new();
+    for choice in env[open + 1..close].split(',') {
+        for suffix in expand_braces(rest) {
+            expanded.push(format!("{}{}{}", prefix, choice.trim(), suffix));
+        }
+    }
+    expanded
+}
+
+/// Lists the sessions of `noxfile.py` with `nox --list --json`.
+fn list_nox_sessions() -> Result<Vec<Task>> {
+    let output = Command::new("nox")
+        .args(["--list", "--json"])
+        .output()
+        .map_err(|_| eyre!("nox not found on PATH. Please install nox and try again."))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(eyre!("nox --list failed: {}", stderr.trim()));
+    }
+
+    parse_nox_sessions(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parses the output of `nox --list --json`.
+fn parse_nox_sessions(output: &str) -> Result<Vec<Task>> {
+    let sessions: Vec<NoxSession> = serde_json::from_str(output)
+        .map_err(|e| eyre!("Failed to parse nox --list output: {}", e))?;
+
+    Ok(sessions
+        .into_iter()
+        .map(|session| {
+            let description = session.description.filter(|d| !d.is_empty());
+            python_task(
+                PythonTool::Nox,
+                &session.session,
+                &["-s", &session.session],
+                description,
+            )
+        })
+        .collect())
+}
+
+/// Creates a task run with `<tool> <command>`.
+fn python_task(
+    tool: PythonTool,
+    name: &str,
+    command: &[&str],
+    description: Option<String>,
+) -> Task {
+    let source = match tool {
+        PythonTool::Tox => "tox.ini",
+        PythonTool::Nox => "noxfile.py",
+        _ => "pyproject.toml",
+    };
+    Task {
+        name: name.to_string(),
+        description,
+        runner: TaskRunner::Python(tool),
+        source: Some(PathBuf::from(source)),
+        command: command.iter().map(|arg| arg.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+/// Python project scripts, tox environments and nox sessions.
+pub struct PythonSource;
+
+impl TaskSource for PythonSource {
+    fn name(&self) -> &'static str {
+        "python"
+    }
+
+    fn describe(&self) -> &'static str {
+        "pyproject.toml scripts, tox environments and nox sessions"
+    }
+
+    fn detect(&self, root: &Path, _config: &Config) -> bool {
+        ["pyproject.toml", "tox.ini", "noxfile.py"]
+            .iter()
+            .any(|name| root.join(name).is_file())
+    }
+
+    fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+        discover_tasks()
+    }
+
+    fn watched_files(&self, root: &Path, _config: &Config) -> Vec<PathBuf> {
+        // Lockfiles decide the tool running `[project.scripts]`
+        [
+            "pyproject.toml",
+            "tox.ini",
+            "noxfile.py",
+            "poetry.lock",
+            "pdm.lock",
+            "uv.lock",
+        ]
+        .iter()
+        .map(|name| root.join(name))
+        .collect()
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        matches!(runner, TaskRunner::Python(_))
+    }
+
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        let program = match task.runner {
+            TaskRunner::Python(tool) => tool.command(),
+            _ => "python3",
+        };
+        let mut command = Command::new(program);
+        command.args(&task.command).args(args);
+        command
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pyproject_scripts() {
+        let source = r#"
+[project]
+name = "app"
+
+[project.scripts]
+app = "app.cli:main"
+serve = "app.server:run"
+
+[tool.poetry.scripts]
+app = "app.cli:main"
+
+[tool.pdm.scripts]
+_.env_file = ".env"
+lint = "ruff check ."
+test = { cmd = ["pytest", "-x"], help = "Run the tests" }
+fmt = { shell = "ruff format . && ruff check --fix ." }
+all = { composite = ["lint", "test"] }
+
+[tool.hatch.envs.default.scripts]
+cov = ["coverage run -m pytest", "coverage report"]
+
+[tool.hatch.envs.docs.scripts]
+build = "mkdocs build"
+"#;
+
+        let tasks = parse_pyproject(source, PythonTool::Uv).unwrap();
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "app",
+                "serve",
+                "lint",
+                "test",
+                "fmt",
+                "all",
+                "cov",
+                "docs:build"
+            ]
+        );
+
+        // The poetry script wins over the identical entry point
+        assert_eq!(tasks[0].runner, TaskRunner::Python(PythonTool::Poetry));
+        assert_eq!(tasks[0].command, vec!["run", "app"]);
+        assert_eq!(tasks[0].description, Some("app.cli:main".to_string()));
+        assert_eq!(tasks[1].runner, TaskRunner::Python(PythonTool::Uv));
+
+        assert_eq!(tasks[2].runner, TaskRunner::Python(PythonTool::Pdm));
+        assert_eq!(tasks[2].description, Some("ruff check .".to_string()));
+        assert_eq!(tasks[3].description, Some("Run the tests".to_string()));
+        assert_eq!(
+            tasks[4].description,
+            Some("ruff format . && ruff check --fix .".to_string())
+        );
+        assert_eq!(tasks[5].description, Some("lint\ntest".to_string()));
+
+        assert_eq!(tasks[6].runner, TaskRunner::Python(PythonTool::Hatch));
+        assert_eq!(tasks[6].command, vec!["run", "cov"]);
+        assert!(tasks[6].module_path.is_empty());
+        assert_eq!(tasks[7].command, vec!["run", "docs:build"]);
+        assert_eq!(tasks[7].module_path, vec!["docs"]);
+        assert_eq!(tasks[7].short_name(), "build");
+    }
+
+    #[test]
+    fn test_parse_pyproject_without_scripts() {
+        let tasks = parse_pyproject("[project]\nname = \"app\"\n", PythonTool::Uv).unwrap();
+        assert!(tasks.is_empty());
+        assert!(parse_pyproject("[project", PythonTool::Uv).is_err());
+    }
+
+    #[test]
+    fn test_parse_tox_ini() {
+        let source = r#"
+[tox]
+envlist =
+    py{311,312}-django{4,5}
+    lint, docs
+
+[testenv]
+description = Run the test suite
+deps = pytest
+commands = pytest {posargs}
+
+[testenv:lint]
+description = Check code style
+commands =
+    ruff check .
+
+; not part of envlist
+[testenv:release]
+commands = twine upload dist/*
+"#;
+
+        let tasks = parse_tox_ini(source);
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "py311-django4",
+                "py311-django5",
+                "py312-django4",
+                "py312-django5",
+                "lint",
+                "docs",
+                "release",
+            ]
+        );
+        assert!(tasks
+            .iter()
+            .all(|t| t.runner == TaskRunner::Python(PythonTool::Tox)));
+        assert_eq!(tasks[0].command, vec!["-e", "py311-django4"]);
+        assert_eq!(tasks[0].description, Some("Run the test suite".to_string()));
+        assert_eq!(tasks[4].description, Some("Check code style".to_string()));
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(expand_braces("lint"), vec!["lint"]);
+        assert_eq!(expand_braces("py{39, 310}"), vec!["py39", "py310"]);
+        assert_eq!(
+            expand_braces("{a,b}-{c,d}"),
+            vec!["a-c", "a-d", "b-c", "b-d"]
+        );
+    }
+
+    #[test]
+    fn test_parse_nox_sessions() {
+        let tasks = parse_nox_sessions(include_str!("fixtures/python/nox.json")).unwrap();
+
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["tests-3.11", "tests-3.12", "lint"]);
+        assert_eq!(tasks[0].command, vec!["-s", "tests-3.11"]);
+        assert_eq!(
+            tasks[0].description,
+            Some("Run the test suite.".to_string())
+        );
+        assert_eq!(tasks[2].description, None);
+        assert!(tasks
+            .iter()
+            .all(|t| t.runner == TaskRunner::Python(PythonTool::Nox)));
+    }
+}
@@ -0,0 +1,786 @@
+/// Task source registry.
+///
+/// Every task source (just, Make, package.json, ...) implements [`TaskSource`]
+/// and is registered in a [`SourceRegistry`]. The registry runs the enabled
+/// sources concurrently, reusing cached results where it can, records how each
+/// of them did as a [`SourceDiagnostic`], and finds the source that builds the
+/// command of a task.
+use crate::app::{Task, TaskRunner};
+use crate::config::{Config, SourcesConfig};
+use crate::tasks::cache::DiscoveryCache;
+use color_eyre::eyre::Result;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// A source of tasks, like a task runner's config file.
+///
+/// Sources discover their tasks on separate threads.
+pub trait TaskSource: Send + Sync {
+    /// Name of the source in the config and in diagnostics (e.g. `just`)
+    fn name(&self) -> &'static str;
+
+    /// Describes what the source discovers (e.g. `justfile recipes`)
+    fn describe(&self) -> &'static str;
+
+    /// Returns true if the project in `root` uses this source, i.e. if
+    /// discovery should run. This only checks for files, so it is cheap.
+    fn detect(&self, root: &Path, config: &Config) -> bool;
+
+    /// Returns true if `dir` itself has a file this source is detected by,
+    /// marking it as a project directory when Taskpad is started in a
+    /// subdirectory (see [`crate::project::find_root`]). The project config
+    /// isn't known yet, so by default this is [`TaskSource::detect`] with the
+    /// default config. Sources detected by something other than a project
+    /// file return false.
+    fn marks_project(&self, dir: &Path) -> bool {
+        self.detect(dir, &Config::default())
+    }
+
+    /// Discovers the source's tasks in the current directory.
+    fn discover(&self, config: &Config) -> Result<Vec<Task>>;
+
+    /// Discovers the source's tasks in the current directory, along with the
+    /// files they turned out to be read from beyond the cache inputs (e.g.
+    /// makefiles included through a variable, which only make can resolve),
+    /// relative to the current directory. Cached tasks are discovered again
+    /// once one of these files changes.
+    fn discover_with_inputs(&self, config: &Config) -> Result<(Vec<Task>, Vec<PathBuf>)> {
+        self.discover(config).map(|tasks| (tasks, Vec::new()))
+    }
+
+    /// Returns the files the source's tasks in `root` are read from, if the
+    /// tasks only depend on them. The tasks are then cached, and reused as
+    /// long as these files don't change.
+    fn cache_inputs(&self, _root: &Path, _config: &Config) -> Option<Vec<PathBuf>> {
+        None
+    }
+
+    /// Returns the version of the program that lists the source's tasks (e.g.
+    /// `just --version`), if the source runs one. It is part of the cache
+    /// key, so upgrading the program discovers the tasks again.
+    fn tool_version(&self) -> Option<String> {
+        None
+    }
+
+    /// Updates what the tasks in `root` know about the project's current
+    /// state beyond the cache inputs (e.g. whether a make target is an
+    /// existing file). Runs after the tasks are discovered or loaded from the
+    /// cache, so it must be cheap.
+    fn refresh(&self, _root: &Path, _tasks: &mut [Task]) {}
+
+    /// Returns the files (or directories) the source reads its tasks in
+    /// `root` from, including files that don't exist yet but would be read.
+    /// Tasks are reloaded when one of them changes. By default these are the
+    /// cache inputs.
+    fn watched_files(&self, root: &Path, config: &Config) -> Vec<PathBuf> {
+        self.cache_inputs(root, config).unwrap_or_default()
+    }
+
+    /// Returns true if this source runs tasks of `runner`.
+    fn runs(&self, runner: &TaskRunner) -> bool;
+
+    /// Builds the command running `task` with the given arguments.
+    ///
+    /// By default this runs the task's command as a program and its arguments.
+    fn command(&self, task: &Task, args: &[String]) -> Command {
+        argv_command(&task.command, args)
+    }
+}
+
+/// Builds a command running `argv` (a program and its arguments) followed by `args`.
+pub fn argv_command(argv: &[String], args: &[String]) -> Command {
+    let (program, argv) = argv.split_first().map_or(("", &[][..]), |(p, a)| (p, a));
+    let mut command = Command::new(program);
+    command.args(argv).args(args);
+    command
+}
+
+/// Records `file` as the file `tasks` are defined in, for sources reading
+/// all their tasks from one file.
+pub fn defined_in(mut tasks: Vec<Task>, file: Option<PathBuf>) -> Vec<Task> {
+    for task in &mut tasks {
+        task.source = file.clone();
+    }
+    tasks
+}
+
+/// How discovery went for one source.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceOutcome {
+    /// Disabled in the config
+    Disabled,
+    /// Discovery is running
+    Discovering,
+    /// The project doesn't use the source
+    NotDetected,
+    /// Discovery found this many tasks
+    Found(usize),
+    /// Discovery failed with this error
+    Failed(String),
+}
+
+/// Diagnostics of one source's discovery.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceDiagnostic {
+    /// Name of the source (or of an unknown source named in the config)
+    pub source: String,
+    /// What the source discovers (empty for unknown sources)
+    pub description: &'static str,
+    pub outcome: SourceOutcome,
+    /// Time discovery took
+    pub elapsed: Duration,
+    /// Whether the tasks came from the cache
+    pub cached: bool,
+}
+
+impl SourceDiagnostic {
+    /// Creates the diagnostic of a source that didn't take any time.
+    fn new(source: String, description: &'static str, outcome: SourceOutcome) -> Self {
+        Self {
+            source,
+            description,
+            outcome,
+            elapsed: Duration::ZERO,
+            cached: false,
+        }
+    }
+}
+
+/// Tasks found by the registry, with the diagnostics of every source.
+#[derive(Debug, Default)]
+pub struct Discovery {
+    pub tasks: Vec<Task>,
+    pub diagnostics: Vec<SourceDiagnostic>,
+    /// Files the sources found their tasks to be read from during discovery
+    /// (see [`TaskSource::discover_with_inputs`]), to watch along with the
+    /// sources' watched files
+    pub inputs: Vec<PathBuf>,
+}
+
+impl Discovery {
+    /// Returns the diagnostics of the sources that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &SourceDiagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| matches!(d.outcome, SourceOutcome::Failed(_)))
+    }
+}
+
+/// The registered task sources, in their default order.
+#[derive(Default)]
+pub struct SourceRegistry {
+    sources: Vec<Box<dyn TaskSource>>,
+    /// Cache of the sources' tasks, if results are cached
+    cache: Option<DiscoveryCache>,
+}
+
+impl SourceRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry with all built-in sources.
+    pub fn builtin() -> Self {
+        use crate::tasks::*;
+
+        let mut registry = Self::new();
+        registry.register(Box::new(custom::CustomSource));
+        registry.register(Box::new(just::JustSource));
+        registry.register(Box::new(make::MakeSource));
+        registry.register(Box::new(go_task::GoTaskSource));
+        registry.register(Box::new(mise::MiseSource));
+        registry.register(Box::new(package_json::PackageJsonSource));
+        registry.register(Box::new(cargo::CargoSource));
+        registry.register(Box::new(python::PythonSource));
+        registry.register(Box::new(compose::ComposeSource));
+        registry.register(Box::new(vscode::VsCodeSource));
+        registry.register(Box::new(scripts::ScriptsSource));
+        registry.register(Box::new(external::ExternalSource));
+        registry
+    }
+
+    /// Caches the tasks of the sources that support it in `cache`.
+    pub fn with_cache(mut self, cache: DiscoveryCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Adds a source after the registered ones.
+    pub fn register(&mut self, source: Box<dyn TaskSource>) {
+        self.sources.push(source);
+    }
+
+    /// Returns the registered sources.
+    pub fn sources(&self) -> impl Iterator<Item = &dyn TaskSource> {
+        self.sources.iter().map(|source| source.as_ref())
+    }
+
+    /// Runs discovery for the sources enabled in `config`, in the configured
+    /// order, numbering the tasks of all sources consecutively.
+    ///
+    /// Sources that aren't detected in `root` are skipped; the others run
+    /// concurrently, and their errors are recorded in the diagnostics.
+    /// `progress` is called with the diagnostic of each source as its state
+    /// changes: first for every source in order (sources being discovered are
+    /// [`SourceOutcome::Discovering`]), then for each source as it finishes.
+    pub fn discover(
+        &self,
+        root: &Path,
+        config: &Config,
+        progress: &(dyn Fn(&SourceDiagnostic) + Sync),
+    ) -> Discovery {
+        let mut diagnostics = Vec::new();
+
+        let (order, unknown) = self.ordered(&config.sources);
+        for name in unknown {
+            let outcome = SourceOutcome::Failed("unknown task source in the config".to_string());
+            diagnostics.push(SourceDiagnostic::new(name, "", outcome));
+        }
+
+        // Detection only checks for files, so it runs up front
+        let mut pending = Vec::new();
+        for (source, enabled) in order {
+            let outcome = if !enabled {
+                SourceOutcome::Disabled
+            } else if !source.detect(root, config) {
+                SourceOutcome::NotDetected
+            } else {
+                pending.push((diagnostics.len(), source));
+                SourceOutcome::Discovering
+            };
+            diagnostics.push(SourceDiagnostic::new(
+                source.name().to_string(),
+                source.describe(),
+                outcome,
+            ));
+        }
+        for diagnostic in &diagnostics {
+            progress(diagnostic);
+        }
+
+        let mut found: Vec<Vec<Task>> = vec![Vec::new(); diagnostics.len()];
+        let mut inputs = Vec::new();
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = pending
+                .into_iter()
+                .map(|(index, source)| {
+                    let handle = scope.spawn(move || {
+                        let result = self.discover_source(source, root, config);
+                        progress(&result.0);
+                        result
+                    });
+                    (index, handle)
+                })
+                .collect();
+
+            for (index, handle) in handles {
+                match handle.join() {
+                    Ok((diagnostic, tasks, source_inputs)) => {
+                        diagnostics[index] = diagnostic;
+                        found[index] = tasks;
+                        inputs.extend(source_inputs);
+                    }
+                    Err(_) => {
+                        diagnostics[index].outcome =
+                            SourceOutcome::Failed("discovery panicked".to_string());
+                        progress(&diagnostics[index]);
+                    }
+                }
+            }
+        });
+
+        let mut tasks: Vec<Task> = found.into_iter().flatten().collect();
+        for (id, task) in tasks.iter_mut().enumerate() {
+            task.id = id;
+        }
+        Discovery {
+            tasks,
+            diagnostics,
+            inputs,
+        }
+    }
+
+    /// Discovers the tasks of one source, from the cache if they are in it.
+    /// Also returns the files discovery found them to be read from.
+    fn discover_source(
+        &self,
+        source: &dyn TaskSource,
+        root: &Path,
+        config: &Config,
+    ) -> (SourceDiagnostic, Vec<Task>, Vec<PathBuf>) {
+        let start = Instant::now();
+        let mut diagnostic = SourceDiagnostic::new(
+            source.name().to_string(),
+            source.describe(),
+            SourceOutcome::Discovering,
+        );
+
+        let cache = self.cache.as_ref().and_then(|cache| {
+            let inputs = source.cache_inputs(root, config)?;
+            let tool_version = source.tool_version();
+            Some((cache, DiscoveryCache::key(&inputs, tool_version.as_deref())))
+        });
+        if let Some((cache, key)) = &cache {
+            if let Some((mut tasks, inputs)) = cache.load(source.name(), root, key) {
+                source.refresh(root, &mut tasks);
+                diagnostic.outcome = SourceOutcome::Found(tasks.len());
+                diagnostic.elapsed = start.elapsed();
+                diagnostic.cached = true;
+                return (diagnostic, tasks, inputs);
+            }
+        }
+
+        let (tasks, inputs) = match source.discover_with_inputs(config) {
+            Ok((mut tasks, inputs)) => {
+                let inputs: Vec<PathBuf> = inputs.iter().map(|path| root.join(path)).collect();
+                if let Some((cache, key)) = &cache {
+                    cache.store(source.name(), root, key, &tasks, &inputs);
+                }
+                source.refresh(root, &mut tasks);
+                diagnostic.outcome = SourceOutcome::Found(tasks.len());
+                (tasks, inputs)
+            }
+            Err(e) => {
+                diagnostic.outcome = SourceOutcome::Failed(e.to_string());
+                (Vec::new(), Vec::new())
+            }
+        };
+        diagnostic.elapsed = start.elapsed();
+        (diagnostic, tasks, inputs)
+    }
+
+    /// Orders the sources as configured and tells which are enabled. Also
+    /// returns the names in the config that match no source.
+    ///
+    /// Sources listed in `order` come first, in that order, followed by the
+    /// others in registration order. If `enabled` is given, only the listed
+    /// sources are enabled; sources in `disabled` are always disabled.
+    fn ordered(&self, config: &SourcesConfig) -> (Vec<(&dyn TaskSource, bool)>, Vec<String>) {
+        let mut unknown: Vec<String> = Vec::new();
+        let named = [&config.order, &config.disabled, &config.enabled];
+        for name in named.into_iter().flatten().flatten() {
+            if !self.sources().any(|s| s.name() == name) && !unknown.contains(name) {
+                unknown.push(name.clone());
+            }
+        }
+
+        let position = |source: &dyn TaskSource| {
+            config
+                .order
+                .iter()
+                .flatten()
+                .position(|name| name == source.name())
+                .unwrap_or(usize::MAX)
+        };
+        let mut sources: Vec<&dyn TaskSource> = self.sources().collect();
+        // Stable, so unlisted sources keep their registration order
+        sources.sort_by_key(|source| position(*source));
+
+        let ordered = sources
+            .into_iter()
+            .map(|source| {
+                let name = source.name().to_string();
+                let listed = match &config.enabled {
+                    Some(enabled) => enabled.contains(&name),
+                    None => true,
+                };
+                let enabled = listed && !config.disabled.iter().flatten().any(|d| *d == name);
+                (source, enabled)
+            })
+            .collect();
+
+        (ordered, unknown)
+    }
+
+    /// Returns true if one of the sources marks `dir` as a project directory
+    /// (see [`TaskSource::marks_project`]).
+    pub fn marks_project(&self, dir: &Path) -> bool {
+        self.sources().any(|source| source.marks_project(dir))
+    }
+
+    /// Returns the files the sources enabled in `config` read their tasks in
+    /// `root` from (see [`TaskSource::watched_files`]).
+    pub fn watched_files(&self, root: &Path, config: &Config) -> Vec<PathBuf> {
+        let (order, _) = self.ordered(&config.sources);
+        order
+            .into_iter()
+            .filter(|(_, enabled)| *enabled)
+            .flat_map(|(source, _)| source.watched_files(root, config))
+            .collect()
+    }
+
+    /// Builds the command running `task` with `args`, through the source
+    /// that runs the task's runner.
+    pub fn command(&self, task: &Task, args: &[String]) -> Command {
+        match self.sources().find(|source| source.runs(&task.runner)) {
+            Some(source) => source.command(task, args),
+            None => argv_command(&task.command, args),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::PackageManager;
+    use crate::test_util::scratch_dir;
+    use color_eyre::eyre::eyre;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    /// A source with canned results.
+    struct FakeSource {
+        name: &'static str,
+        detected: bool,
+        tasks: std::result::Result<Vec<&'static str>, &'static str>,
+    }
+
+    impl TaskSource for FakeSource {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn describe(&self) -> &'static str {
+            "fake tasks"
+        }
+
+        fn detect(&self, _root: &Path, _config: &Config) -> bool {
+            self.detected
+        }
+
+        fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+            match &self.tasks {
+                Ok(names) => Ok(names
+                    .iter()
+                    .map(|name| Task {
+                        id: 99,
+                        name: name.to_string(),
+                        ..Default::default()
+                    })
+                    .collect()),
+                Err(e) => Err(eyre!("{}", e)),
+            }
+        }
+
+        fn runs(&self, _runner: &TaskRunner) -> bool {
+            false
+        }
+    }
+
+    fn fake_registry() -> SourceRegistry {
+        let mut registry = SourceRegistry::new();
+        for (name, detected, tasks) in [
+            ("a", true, Ok(vec!["a1", "a2"])),
+            ("b", false, Ok(vec!["b1"])),
+            ("c", true, Err("c is broken")),
+            ("d", true, Ok(vec!["d1"])),
+        ] {
+            registry.register(Box::new(FakeSource {
+                name,
+                detected,
+                tasks,
+            }));
+        }
+        registry
+    }
+
+    fn outcomes(discovery: &Discovery) -> Vec<(&str, &SourceOutcome)> {
+        discovery
+            .diagnostics
+            .iter()
+            .map(|d| (d.source.as_str(), &d.outcome))
+            .collect()
+    }
+
+    #[test]
+    fn test_discover_records_diagnostics() {
+        let discovery = fake_registry().discover(Path::new("."), &Config::default(), &|_| {});
+
+        let names: Vec<&str> = discovery.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["a1", "a2", "d1"]);
+        assert!(discovery.tasks.iter().enumerate().all(|(i, t)| t.id == i));
+
+        assert_eq!(
+            outcomes(&discovery),
+            vec![
+                ("a", &SourceOutcome::Found(2)),
+                ("b", &SourceOutcome::NotDetected),
+                ("c", &SourceOutcome::Failed("c is broken".to_string())),
+                ("d", &SourceOutcome::Found(1)),
+            ]
+        );
+        assert_eq!(discovery.failures().count(), 1);
+    }
+
+    #[test]
+    fn test_discover_orders_and_disables_sources() {
+        let mut config = Config::default();
+        config.sources.order = Some(vec!["d".to_string(), "c".to_string()]);
+        config.sources.disabled = Some(vec!["c".to_string(), "zz".to_string()]);
+
+        let discovery = fake_registry().discover(Path::new("."), &config, &|_| {});
+        let names: Vec<&str> = discovery.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["d1", "a1", "a2"]);
+        assert_eq!(
+            outcomes(&discovery),
+            vec![
+                (
+                    "zz",
+                    &SourceOutcome::Failed("unknown task source in the config".to_string())
+                ),
+                ("d", &SourceOutcome::Found(1)),
+                ("c", &SourceOutcome::Disabled),
+                ("a", &SourceOutcome::Found(2)),
+                ("b", &SourceOutcome::NotDetected),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_discover_only_enabled_sources() {
+        let mut config = Config::default();
+        config.sources.enabled = Some(vec!["d".to_string()]);
+
+        let discovery = fake_registry().discover(Path::new("."), &config, &|_| {});
+        let names: Vec<&str> = discovery.tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["d1"]);
+        assert_eq!(discovery.diagnostics[0].outcome, SourceOutcome::Disabled);
+    }
+
+    #[test]
+    fn test_discover_reports_progress() {
+        let events = std::sync::Mutex::new(Vec::new());
+        fake_registry().discover(Path::new("."), &Config::default(), &|diagnostic| {
+            let event = (diagnostic.source.clone(), diagnostic.outcome.clone());
+            events.lock().unwrap().push(event);
+        });
+        let events = events.into_inner().unwrap();
+
+        // Every source is reported in order before discovery starts
+        let discovering = |name: &str| (name.to_string(), SourceOutcome::Discovering);
+        assert_eq!(
+            events[..4],
+            [
+                discovering("a"),
+                ("b".to_string(), SourceOutcome::NotDetected),
+                discovering("c"),
+                discovering("d"),
+            ]
+        );
+        // Then each discovered source as it finishes, in any order
+        assert_eq!(events.len(), 7);
+        assert!(events.contains(&("a".to_string(), SourceOutcome::Found(2))));
+        assert!(events.contains(&(
+            "c".to_string(),
+            SourceOutcome::Failed("c is broken".to_string())
+        )));
+    }
+
+    /// A source whose tasks depend on one file and its tool's version,
+    /// counting its discoveries. Discovery also reports a file only it knows
+    /// about, and tasks named like an existing file are file targets.
+    struct CachedSource {
+        input: PathBuf,
+        discovered_input: PathBuf,
+        version: Arc<Mutex<&'static str>>,
+        runs: Arc<AtomicUsize>,
+    }
+
+    impl TaskSource for CachedSource {
+        fn name(&self) -> &'static str {
+            "cached"
+        }
+
+        fn describe(&self) -> &'static str {
+            "cached tasks"
+        }
+
+        fn detect(&self, _root: &Path, _config: &Config) -> bool {
+            true
+        }
+
+        fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            let name = std::fs::read_to_string(&self.input)?;
+            Ok(vec![Task {
+                name,
+                ..Default::default()
+            }])
+        }
+
+        fn discover_with_inputs(&self, config: &Config) -> Result<(Vec<Task>, Vec<PathBuf>)> {
+            let tasks = self.discover(config)?;
+            Ok((tasks, vec![self.discovered_input.clone()]))
+        }
+
+        fn cache_inputs(&self, _root: &Path, _config: &Config) -> Option<Vec<PathBuf>> {
+            Some(vec![self.input.clone()])
+        }
+
+        fn tool_version(&self) -> Option<String> {
+            Some(self.version.lock().unwrap().to_string())
+        }
+
+        fn refresh(&self, root: &Path, tasks: &mut [Task]) {
+            for task in tasks {
+                task.file_target = root.join(&task.name).exists();
+            }
+        }
+
+        fn runs(&self, _runner: &TaskRunner) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_discover_uses_cache_until_inputs_change() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        let input = dir.join("tasks.txt");
+        std::fs::write(&input, "build").unwrap();
+
+        let runs = Arc::new(AtomicUsize::new(0));
+        let version = Arc::new(Mutex::new("fake 1.0"));
+        let mut registry = SourceRegistry::new().with_cache(DiscoveryCache::new(dir.join("cache")));
+        let discovered_input = dir.join("included.txt");
+        registry.register(Box::new(CachedSource {
+            input: input.clone(),
+            discovered_input: discovered_input.clone(),
+            version: version.clone(),
+            runs: runs.clone(),
+        }));
+        let discover = || registry.discover(dir, &Config::default(), &|_| {});
+
+        let first = discover();
+        assert_eq!(first.tasks[0].name, "build");
+        assert!(!first.diagnostics[0].cached);
+
+        let second = discover();
+        assert_eq!(second.tasks[0].name, "build");
+        assert_eq!(second.diagnostics[0].outcome, SourceOutcome::Found(1));
+        assert!(second.diagnostics[0].cached);
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+
+        // Cached tasks are refreshed with the project's current state
+        assert!(!second.tasks[0].file_target);
+        std::fs::write(dir.join("build"), "").unwrap();
+        let refreshed = discover();
+        assert!(refreshed.diagnostics[0].cached);
+        assert!(refreshed.tasks[0].file_target);
+
+        std::fs::write(&input, "test").unwrap();
+        let third = discover();
+        assert_eq!(third.tasks[0].name, "test");
+        assert!(!third.diagnostics[0].cached);
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+
+        // Upgrading the tool invalidates the cache
+        *version.lock().unwrap() = "fake 2.0";
+        let upgraded = discover();
+        assert!(!upgraded.diagnostics[0].cached);
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        // So does a file that only discovery knew the tasks were read from,
+        // which is reported for watching whether the tasks were cached or not
+        assert_eq!(upgraded.inputs, vec![discovered_input.clone()]);
+        let cached = discover();
+        assert!(cached.diagnostics[0].cached);
+        assert_eq!(cached.inputs, vec![discovered_input.clone()]);
+        std::fs::write(&discovered_input, "changed").unwrap();
+        assert!(!discover().diagnostics[0].cached);
+        assert!(discover().diagnostics[0].cached);
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_builtin_sources_have_unique_names() {
+        let registry = SourceRegistry::builtin();
+        let mut names: Vec<&str> = registry.sources().map(|s| s.name()).collect();
+        let count = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), count);
+    }
+
+    #[test]
+    fn test_builtin_commands() {
+        let registry = SourceRegistry::builtin();
+        let args = vec!["--watch".to_string()];
+        let command_line = |task: &Task| {
+            let command = registry.command(task, &args);
+            std::iter::once(command.get_program())
+                .chain(command.get_args())
+                .map(|arg| arg.to_string_lossy().into_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let just = Task {
+            name: "docker::build".to_string(),
+            runner: TaskRunner::Just,
+            module_path: vec!["docker".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&just),
+            vec!["just", "docker::build", "--watch"]
+        );
+
+        // just and make are pointed at the file the task is defined in
+        let just = Task {
+            source: Some(PathBuf::from("ci/justfile")),
+            ..just
+        };
+        assert_eq!(
+            command_line(&just),
+            vec![
+                "just",
+                "--justfile",
+                "ci/justfile",
+                "docker::build",
+                "--watch"
+            ]
+        );
+        let make = Task {
+            name: "release".to_string(),
+            runner: TaskRunner::Make,
+            source: Some(PathBuf::from("build/release.mk")),
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&make),
+            vec!["make", "-f", "build/release.mk", "release", "--watch"]
+        );
+
+        let npm = Task {
+            name: "web::dev".to_string(),
+            runner: TaskRunner::Package(PackageManager::Npm),
+            module_path: vec!["web".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&npm),
+            vec!["npm", "run", "dev", "--", "--watch"]
+        );
+
+        let cargo = Task {
+            name: "bin::server".to_string(),
+            runner: TaskRunner::Cargo,
+            command: vec!["run".to_string(), "--bin".to_string(), "server".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            command_line(&cargo),
+            vec!["cargo", "run", "--bin", "server", "--watch"]
+        );
+
+        let script = Task {
+            name: "scripts::deploy.sh".to_string(),
+            runner: TaskRunner::Script,
+            command: vec!["scripts/deploy.sh".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(command_line(&script), vec!["scripts/deploy.sh", "--watch"]);
+    }
+}
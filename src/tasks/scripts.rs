@@ -0,0 +1,345 @@
+/// Script directory task discovery module.
+///
+/// This module lists the executable files in helper script directories
+/// (`scripts/` and `bin/` unless `script_dirs` is configured) as tasks that run the file directly,
+/// described by a comment at the top of the script.
+use crate::app::{Task, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::TaskSource;
+use color_eyre::eyre::{eyre, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How many lines at the top of a script are searched for its description.
+const DESCRIPTION_LINES: usize = 20;
+
+/// Discovers the executables in the configured script directories of the
+/// current directory.
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if none of the
+/// script directories exist or they contain no executable files.
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks(config: &Config) -> Result<Vec<Task>> {
+    let dirs = config.script_dirs();
+    let tasks = script_tasks(Path::new("."), &dirs);
+
+    if tasks.is_empty() {
+        let dirs: Vec<String> = dirs
+            .iter()
+            .map(|dir| format!("{}/", dir.display()))
+            .collect();
+        return Err(eyre!(
+            "No executable scripts found in {}.",
+            dirs.join(" or ")
+        ));
+    }
+
+    Ok(tasks)
+}
+
+/// Lists the executable files directly inside each of `dirs` (relative to
+/// `root`), sorted by name and grouped in a module per directory
+/// (`scripts::deploy.sh`).
+fn script_tasks(root: &Path, dirs: &[PathBuf]) -> Vec<Task> {
+    let mut tasks = Vec::new();
+
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(root.join(dir)) else {
+            continue;
+        };
+        let mut files: Vec<String> = entries
+            .flatten()
+            .filter(|entry| is_executable(&entry.path()))
+            .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+            .filter(|name| !name.starts_with('.'))
+            .collect();
+        files.sort();
+
+        let module_path: Vec<String> = dir
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+
+        for file in files {
+            let path = dir.join(&file);
+            let description = fs::read(root.join(&path))
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .and_then(|source| parse_description(&source));
+
+            tasks.push(Task {
+                id: tasks.len(),
+                name: format!("{}::{}", module_path.join("::"), file),
+                description,
+                runner: TaskRunner::Script,
+                source: Some(path.clone()),
+                module_path: module_path.clone(),
+                command: vec![path.display().to_string()],
+                ..Default::default()
+            });
+        }
+    }
+
+    tasks
+}
+
+/// Returns true if `path` is a file that can be run directly.
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path)
+        .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Returns true if `path` is a file that can be run directly.
+#[cfg(not(unix))]
+pub fn is_executable(path: &Path) -> bool {
+    let runnable = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ["exe", "bat", "cmd"].contains(&ext.to_ascii_lowercase().as_str()))
+        .unwrap_or(false);
+    runnable && path.is_file()
+}
+
+/// Extracts a script's description from the comments at its top.
+///
+/// A `Description:` comment (`# Description: Deploy to staging`) anywhere in
+/// the first lines wins; otherwise the first comment line after the shebang
+/// is used. `#`, `//` and `--` comments are recognized, and tool directives
+/// like `# shellcheck disable=...` or `# -*- coding: utf-8 -*-` are skipped.
+fn parse_description(source: &str) -> Option<String> {
+    let comments: Vec<&str> = source
+        .lines()
+        .take(DESCRIPTION_LINES)
+        .filter(|line| !line.starts_with("#!"))
+        .filter_map(|line| {
+            let line = line.trim();
+            ["#", "//", "--"]
+                .iter()
+                .find_map(|marker| line.strip_prefix(marker))
+                .map(|comment| comment.trim_start_matches(['#', '/', '-']).trim())
+        })
+        .collect();
+
+    let tagged = comments.iter().find_map(|comment| {
+        let (key, value) = comment.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("description")
+            .then(|| value.trim())
+    });
+    let first = || {
+        // Only the comment block directly after the shebang describes the script
+        source
+            .lines()
+            .filter(|line| !line.starts_with("#!"))
+            .map(str::trim)
+            .skip_while(|line| line.is_empty())
+            .take_while(|line| ["#", "//", "--"].iter().any(|m| line.starts_with(m)))
+            .map(|line| line.trim_start_matches(['#', '/', '-']).trim())
+            .find(|comment| !comment.is_empty() && !is_directive(comment))
+    };
+
+    tagged
+        .or_else(first)
+        .filter(|description| !description.is_empty())
+        .map(str::to_string)
+}
+
+/// Returns true for comments addressed to tools rather than readers.
+fn is_directive(comment: &str) -> bool {
+    comment.starts_with("shellcheck ")
+        || comment.starts_with("-*-")
+        || comment.starts_with("vim:")
+        || comment.starts_with("eslint")
+        || comment.starts_with("@ts-")
+}
+
+/// Executables in the script directories.
+pub struct ScriptsSource;
+
+impl TaskSource for ScriptsSource {
+    fn name(&self) -> &'static str {
+        "scripts"
+    }
+
+    fn describe(&self) -> &'static str {
+        "executables in the script directories"
+    }
+
+    fn detect(&self, root: &Path, config: &Config) -> bool {
+        config
+            .script_dirs()
+            .iter()
+            .any(|dir| root.join(dir).is_dir())
+    }
+
+    fn marks_project(&self, _dir: &Path) -> bool {
+        // Directories like bin/ are too common to tell a project apart
+        false
+    }
+
+    fn discover(&self, config: &Config) -> Result<Vec<Task>> {
+        discover_tasks(config)
+    }
+
+    fn watched_files(&self, root: &Path, config: &Config) -> Vec<PathBuf> {
+        config
+            .script_dirs()
+            .iter()
+            .map(|dir| root.join(dir))
+            .collect()
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::Script
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::{AppState, TaskRow};
+    use crate::test_util::scratch_dir;
+
+    #[test]
+    fn test_parse_description() {
+        assert_eq!(
+            parse_description("#!/bin/sh\n# Deploy the app\n# to staging\nset -e\n"),
+            Some("Deploy the app".to_string())
+        );
+        assert_eq!(
+            parse_description(
+                "#!/usr/bin/env bash\n# shellcheck disable=SC2086\n#\n# Usage: seed.sh [count]\n# Description: Seed the database\nset -e\n"
+            ),
+            Some("Seed the database".to_string())
+        );
+        assert_eq!(
+            parse_description("#!/usr/bin/env node\n// Generate the changelog\n"),
+            Some("Generate the changelog".to_string())
+        );
+        assert_eq!(
+            parse_description(
+                "#!/usr/bin/env python3\n# -*- coding: utf-8 -*-\n## Lint everything\n"
+            ),
+            Some("Lint everything".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_description_without_leading_comment() {
+        assert_eq!(
+            parse_description("#!/bin/sh\nset -e\n# later comment\n"),
+            None
+        );
+        assert_eq!(parse_description("#!/bin/sh\n#\n"), None);
+        assert_eq!(parse_description(""), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_script_tasks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let scratch = scratch_dir();
+        let root = scratch.path();
+        fs::create_dir_all(root.join("scripts")).unwrap();
+        fs::create_dir_all(root.join("tools/ci")).unwrap();
+        for (path, source, mode) in [
+            ("scripts/deploy.sh", "#!/bin/sh\n# Deploy the app\n", 0o755),
+            ("scripts/build", "#!/bin/sh\necho build\n", 0o700),
+            ("scripts/README.md", "# Scripts\n", 0o644),
+            ("scripts/.hidden", "#!/bin/sh\n", 0o755),
+            (
+                "tools/ci/check.sh",
+                "#!/bin/sh\n# Description: Run the CI checks\n",
+                0o755,
+            ),
+        ] {
+            let file = root.join(path);
+            fs::write(&file, source).unwrap();
+            fs::set_permissions(&file, fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        let dirs = ["scripts", "bin", "tools/ci"].map(PathBuf::from);
+        let tasks = script_tasks(root, &dirs);
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "scripts::build",
+                "scripts::deploy.sh",
+                "tools::ci::check.sh"
+            ]
+        );
+        assert!(tasks.iter().enumerate().all(|(i, t)| t.id == i));
+        assert!(tasks.iter().all(|t| t.runner == TaskRunner::Script));
+
+        assert_eq!(tasks[0].description, None);
+        assert_eq!(tasks[1].description, Some("Deploy the app".to_string()));
+        assert_eq!(tasks[1].command, vec!["scripts/deploy.sh"]);
+        assert_eq!(tasks[1].short_name(), "deploy.sh");
+        assert_eq!(tasks[2].module_path, vec!["tools", "ci"]);
+        assert_eq!(tasks[2].short_name(), "check.sh");
+    }
+
+    #[test]
+    fn test_source_reads_configured_script_dirs() {
+        let scratch = scratch_dir();
+        let root = scratch.path();
+        fs::create_dir_all(root.join("bin")).unwrap();
+        let source = ScriptsSource;
+
+        let default = Config::default();
+        assert!(source.detect(root, &default));
+        assert_eq!(
+            source.watched_files(root, &default),
+            vec![root.join("scripts"), root.join("bin")]
+        );
+
+        let config = Config {
+            script_dirs: Some(vec![PathBuf::from("tools")]),
+            ..Default::default()
+        };
+        assert!(!source.detect(root, &config));
+        fs::create_dir_all(root.join("tools")).unwrap();
+        assert!(source.detect(root, &config));
+        assert_eq!(
+            source.watched_files(root, &config),
+            vec![root.join("tools")]
+        );
+    }
+
+    #[test]
+    fn test_script_modules_stay_apart_from_just_modules() {
+        let script = Task {
+            name: "scripts::deploy.sh".to_string(),
+            runner: TaskRunner::Script,
+            module_path: vec!["scripts".to_string()],
+            ..Default::default()
+        };
+        let just = Task {
+            name: "scripts::lint".to_string(),
+            runner: TaskRunner::Just,
+            ..script.clone()
+        };
+
+        let app = AppState::new(vec![just, script]);
+        let runners: Vec<TaskRunner> = app
+            .task_rows
+            .iter()
+            .filter_map(|row| match row {
+                TaskRow::Module { module, .. } => Some(module.runner.clone()),
+                TaskRow::Task(_) => None,
+            })
+            .collect();
+        assert_eq!(runners, vec![TaskRunner::Just, TaskRunner::Script]);
+    }
+}
@@ -0,0 +1,691 @@
+/// VS Code task discovery module.
+///
+/// This module reads the `shell` and `process` tasks of `.vscode/tasks.json`
+/// (JSON with comments) and runs them the way VS Code does: shell tasks as a
+/// command line through a shell, process tasks by executing the command
+/// directly, in the task's `options.cwd` with its `options.env`.
+use crate::app::{Task, TaskRunner};
+use crate::config::Config;
+use crate::tasks::registry::TaskSource;
+use color_eyre::eyre::{eyre, Result};
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Location of the tasks file, relative to the workspace folder.
+const TASKS_FILE: &str = ".vscode/tasks.json";
+
+/// The parts of `tasks.json` used by Taskpad.
+#[derive(Debug, Default, Deserialize)]
+struct TasksFile {
+    #[serde(default)]
+    tasks: Vec<TaskConfig>,
+    /// Options shared by all tasks
+    #[serde(default)]
+    options: Options,
+}
+
+/// A task from `tasks.json`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TaskConfig {
+    label: Option<String>,
+    /// `shell`, `process`, or a task provider like `npm` (not supported)
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    command: Option<ShellString>,
+    #[serde(default)]
+    args: Vec<ShellString>,
+    #[serde(default)]
+    options: Options,
+    depends_on: Option<DependsOn>,
+    /// `parallel` (the default) or `sequence`
+    depends_order: Option<String>,
+    detail: Option<String>,
+    #[serde(default)]
+    hide: bool,
+    group: Option<Group>,
+    linux: Option<PlatformConfig>,
+    osx: Option<PlatformConfig>,
+    windows: Option<PlatformConfig>,
+}
+
+/// Properties of a task overridden on one platform.
+#[derive(Debug, Default, Deserialize)]
+struct PlatformConfig {
+    command: Option<ShellString>,
+    args: Option<Vec<ShellString>>,
+    options: Option<Options>,
+}
+
+/// `options` of a task or of the whole file.
+#[derive(Debug, Clone, Default, Deserialize)]
+struct Options {
+    cwd: Option<String>,
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+    shell: Option<ShellOptions>,
+}
+
+/// The shell running a shell task (`options.shell`).
+#[derive(Debug, Clone, Deserialize)]
+struct ShellOptions {
+    executable: String,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+/// A command or argument: a string, a list of strings joined with spaces,
+/// or a value with explicit quoting (`strong`, `weak` or `escape`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum ShellString {
+    Plain(String),
+    Parts(Vec<String>),
+    Quoted {
+        value: String,
+        quoting: Option<String>,
+    },
+}
+
+/// `dependsOn`: one task or a list of tasks, referenced by label.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DependsOn {
+    Many(Vec<TaskReference>),
+    One(TaskReference),
+}
+
+/// A reference to another task: its label, or a provider task (not supported).
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TaskReference {
+    Label(String),
+    Other(IgnoredAny),
+}
+
+/// `group`: a kind (`build`, `test`) or `{ "kind": "build", "isDefault": true }`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Group {
+    Kind(String),
+    Detailed { kind: String },
+}
+
+/// How a task runs, with variables substituted.
+#[derive(Debug, Clone, Default)]
+struct Invocation {
+    /// Program and arguments (empty for tasks only running their dependencies)
+    argv: Vec<String>,
+    /// The command line as shown to the user
+    line: String,
+    cwd: Option<PathBuf>,
+    env: Vec<(String, String)>,
+}
+
+/// Discovers the tasks of `.vscode/tasks.json` in the current directory.
+///
+/// # Returns
+///
+/// Returns `Ok(Vec<Task>)` with discovered tasks, or an error if:
+/// - There is no `.vscode/tasks.json` in the current directory
+/// - The file can't be parsed
+/// - It defines no shell or process tasks
+///
+/// # Errors
+///
+/// Returns descriptive errors that can be displayed to the user in the TUI.
+pub fn discover_tasks() -> Result<Vec<Task>> {
+    let source = std::fs::read_to_string(TASKS_FILE)
+        .map_err(|_| eyre!("No {} found in this directory.", TASKS_FILE))?;
+    let root = std::env::current_dir()
+        .map_err(|e| eyre!("Failed to read the current directory: {}", e))?;
+
+    let tasks = parse_tasks_json(&source, &root)?;
+    if tasks.is_empty() {
+        return Err(eyre!("No shell or process tasks found in {}.", TASKS_FILE));
+    }
+
+    Ok(tasks)
+}
+
+/// Parses `tasks.json` into tasks for the workspace folder `root`.
+///
+/// Tasks with dependencies run them first through `sh`, at the same time
+/// unless `dependsOrder` is `sequence`; a task without a command of its own
+/// only runs its dependencies.
+fn parse_tasks_json(source: &str, root: &Path) -> Result<Vec<Task>> {
+    let file: TasksFile =
+        json5::from_str(source).map_err(|e| eyre!("Failed to parse {}: {}", TASKS_FILE, e))?;
+
+    let configs: Vec<(&str, &TaskConfig)> = file
+        .tasks
+        .iter()
+        .filter(|task| matches!(task.kind.as_deref(), None | Some("shell" | "process")))
+        .filter_map(|task| Some((task.label.as_deref()?, task)))
+        .collect();
+    let invocations: BTreeMap<&str, Invocation> = configs
+        .iter()
+        .map(|(label, task)| (*label, invocation(task, &file.options, root)))
+        .collect();
+
+    let mut tasks = Vec::new();
+    for (label, config) in &configs {
+        let dependencies = dependency_labels(config);
+
+        let mut steps = Vec::new();
+        let Some(script) = task_script(
+            label,
+            &configs,
+            &invocations,
+            root,
+            &mut Vec::new(),
+            &mut steps,
+        ) else {
+            continue;
+        };
+
+        let own = &invocations[label];
+        let (command, cwd, env) = match steps.as_slice() {
+            [step] if dependencies.is_empty() => {
+                (step.argv.clone(), own.cwd.clone(), own.env.clone())
+            }
+            _ => {
+                let command = vec!["sh".to_string(), "-c".to_string(), script];
+                (command, own.cwd.clone(), Vec::new())
+            }
+        };
+
+        tasks.push(Task {
+            id: tasks.len(),
+            name: label.to_string(),
+            description: config.detail.clone().filter(|d| !d.is_empty()),
+            runner: TaskRunner::VsCode,
+            source: Some(PathBuf::from(TASKS_FILE)),
+            groups: config
+                .group
+                .iter()
+                .map(|group| match group {
+                    Group::Kind(kind) | Group::Detailed { kind } => kind.clone(),
+                })
+                .collect(),
+            private: config.hide,
+            dependencies,
+            body: steps.iter().map(|step| step.line.clone()).collect(),
+            cwd,
+            env,
+            command,
+            ..Default::default()
+        });
+    }
+
+    Ok(tasks)
+}
+
+/// Returns the labels of the tasks a task depends on.
+fn dependency_labels(task: &TaskConfig) -> Vec<String> {
+    let references = match &task.depends_on {
+        Some(DependsOn::One(reference)) => std::slice::from_ref(reference),
+        Some(DependsOn::Many(references)) => references.as_slice(),
+        None => &[],
+    };
+    references
+        .iter()
+        .filter_map(|reference| match reference {
+            TaskReference::Label(label) => Some(label.clone()),
+            TaskReference::Other(_) => None,
+        })
+        .collect()
+}
+
+/// Renders the `sh` script running a task: its dependencies (at the same
+/// time, or one after another with `dependsOrder: sequence`) followed by its
+/// own command. Returns `None` if there is nothing to run.
+///
+/// Each task runs once, where it is first reached depth first, so a task
+/// shared by parallel dependencies only runs in the first of them. `visited`
+/// also guards against cycles; `steps` collects the commands in that order.
+fn task_script<'a>(
+    label: &'a str,
+    configs: &[(&'a str, &TaskConfig)],
+    invocations: &'a BTreeMap<&str, Invocation>,
+    root: &Path,
+    visited: &mut Vec<&'a str>,
+    steps: &mut Vec<&'a Invocation>,
+) -> Option<String> {
+    let (label, config) = configs.iter().find(|(l, _)| *l == label)?;
+    if visited.contains(label) {
+        return None;
+    }
+    visited.push(label);
+
+    let dependencies: Vec<String> = dependency_labels(config)
+        .iter()
+        .filter_map(|dependency| {
+            let (dependency, _) = configs.iter().find(|(l, _)| l == dependency)?;
+            task_script(dependency, configs, invocations, root, visited, steps)
+        })
+        .collect();
+    let dependencies = match dependencies.as_slice() {
+        [] => None,
+        [dependency] => Some(dependency.clone()),
+        _ if config.depends_order.as_deref() == Some("sequence") => Some(dependencies.join(" && ")),
+        _ => Some(parallel_script(&dependencies)),
+    };
+
+    let invocation = &invocations[label];
+    let own = (!invocation.argv.is_empty()).then(|| {
+        steps.push(invocation);
+        step_script(invocation, root)
+    });
+
+    match (dependencies, own) {
+        (Some(dependencies), Some(own)) => Some(format!("{} && {}", dependencies, own)),
+        (dependencies, own) => dependencies.or(own),
+    }
+}
+
+/// Renders a `sh` script running `scripts` at the same time, which fails
+/// once all of them finished if one of them failed.
+fn parallel_script(scripts: &[String]) -> String {
+    let start = scripts
+        .iter()
+        .enumerate()
+        .map(|(i, script)| format!("{{ {}; }} & p{}=$!", script, i));
+    let wait = (0..scripts.len()).map(|i| format!("wait $p{} || s=1", i));
+    let parts: Vec<String> = std::iter::once("s=0".to_string())
+        .chain(start)
+        .chain(wait)
+        .chain(std::iter::once("exit $s".to_string()))
+        .collect();
+    format!("({})", parts.join("; "))
+}
+
+/// Resolves how a task runs, applying the current platform's overrides and
+/// the file-wide options.
+///
+/// Shell tasks run their command line (the command followed by its
+/// arguments, quoted where needed) with `options.shell` or `sh -c`
+/// (`cmd /C` on Windows). Process tasks execute the command directly.
+fn invocation(task: &TaskConfig, defaults: &Options, root: &Path) -> Invocation {
+    let platform = if cfg!(windows) {
+        task.windows.as_ref()
+    } else if cfg!(target_os = "macos") {
+        task.osx.as_ref()
+    } else {
+        task.linux.as_ref()
+    };
+    let no_overrides = PlatformConfig::default();
+    let platform = platform.unwrap_or(&no_overrides);
+
+    let Some(command) = platform.command.as_ref().or(task.command.as_ref()) else {
+        return Invocation::default();
+    };
+    let args = platform.args.as_ref().unwrap_or(&task.args);
+    let options = platform.options.as_ref().unwrap_or(&task.options);
+    let substitute = |value: &str| substitute_variables(value, root);
+
+    let cwd = options
+        .cwd
+        .as_ref()
+        .or(defaults.cwd.as_ref())
+        .map(|cwd| root.join(substitute(cwd)));
+    let mut env = defaults.env.clone();
+    env.extend(options.env.clone());
+    let env = env
+        .into_iter()
+        .map(|(key, value)| (key, substitute(&value)))
+        .collect();
+
+    let argv = match task.kind.as_deref() {
+        Some("shell") => {
+            // A command without arguments is a whole command line
+            let mut parts = vec![match args.is_empty() {
+                true => command.substitute(root),
+                false => shell_quote(&command.substitute(root), command.quoting()),
+            }];
+            parts.extend(
+                args.iter()
+                    .map(|arg| shell_quote(&arg.substitute(root), arg.quoting())),
+            );
+            let line = parts.join(" ");
+            let shell = options.shell.as_ref().or(defaults.shell.as_ref());
+            let mut argv = match shell {
+                Some(shell) if shell.args.is_empty() => {
+                    vec![substitute(&shell.executable), "-c".to_string()]
+                }
+                Some(shell) => std::iter::once(substitute(&shell.executable))
+                    .chain(shell.args.iter().cloned())
+                    .collect(),
+                None if cfg!(windows) => vec!["cmd".to_string(), "/C".to_string()],
+                None => vec!["sh".to_string(), "-c".to_string()],
+            };
+            argv.push(line);
+            argv
+        }
+        _ => std::iter::once(command)
+            .chain(args)
+            .map(|part| part.substitute(root))
+            .collect(),
+    };
+
+    let line = match task.kind.as_deref() {
+        Some("shell") => argv.last().cloned().unwrap_or_default(),
+        _ => argv
+            .iter()
+            .map(|arg| shell_quote(arg, None))
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+
+    Invocation {
+        argv,
+        line,
+        cwd,
+        env,
+    }
+}
+
+impl ShellString {
+    /// Returns the value with variables substituted.
+    fn substitute(&self, root: &Path) -> String {
+        let value = match self {
+            ShellString::Plain(value) | ShellString::Quoted { value, .. } => value.clone(),
+            ShellString::Parts(parts) => parts.join(" "),
+        };
+        substitute_variables(&value, root)
+    }
+
+    /// Returns the explicit quoting of the value, if any.
+    fn quoting(&self) -> Option<&str> {
+        match self {
+            ShellString::Quoted { quoting, .. } => quoting.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Quotes part of a shell task's command line.
+///
+/// Like VS Code, values are only quoted when they contain whitespace, unless
+/// a quoting is given: `strong` (single quotes), `weak` (double quotes) or
+/// `escape` (backslashes).
+fn shell_quote(value: &str, quoting: Option<&str>) -> String {
+    match quoting {
+        Some("weak") => format!("\"{}\"", value.replace('"', "\\\"")),
+        Some("escape") => value
+            .chars()
+            .flat_map(|c| match c.is_whitespace() || "\\'\"".contains(c) {
+                true => vec!['\\', c],
+                false => vec![c],
+            })
+            .collect(),
+        Some("strong") => format!("'{}'", value.replace('\'', "'\\''")),
+        _ if value.is_empty() || value.contains(char::is_whitespace) => {
+            format!("'{}'", value.replace('\'', "'\\''"))
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Renders an invocation as a step of a `sh` script running several tasks.
+fn step_script(step: &Invocation, root: &Path) -> String {
+    let cwd = step.cwd.as_deref().unwrap_or(root);
+    let mut parts = vec![format!(
+        "cd {}",
+        shell_quote(&cwd.display().to_string(), Some("strong"))
+    )];
+
+    let mut command = Vec::new();
+    if !step.env.is_empty() {
+        command.push("env".to_string());
+        for (key, value) in &step.env {
+            command.push(shell_quote(&format!("{}={}", key, value), Some("strong")));
+        }
+    }
+    command.extend(step.argv.iter().map(|arg| shell_quote(arg, Some("strong"))));
+    parts.push(command.join(" "));
+
+    format!("({})", parts.join(" && "))
+}
+
+/// Substitutes the VS Code variables that make sense outside the editor:
+/// `${workspaceFolder}`, `${workspaceFolderBasename}`, `${cwd}`,
+/// `${pathSeparator}` and `${env:NAME}`. Other variables are left as written.
+fn substitute_variables(value: &str, root: &Path) -> String {
+    let mut result = String::new();
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        result.push_str(&rest[..start]);
+        let variable = &rest[start + 2..start + end];
+        let replacement = match variable {
+            "workspaceFolder" | "workspaceRoot" | "cwd" => Some(root.display().to_string()),
+            "workspaceFolderBasename" => Some(
+                root.file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            ),
+            "pathSeparator" => Some(std::path::MAIN_SEPARATOR.to_string()),
+            _ => variable
+                .strip_prefix("env:")
+                .map(|name| std::env::var(name).unwrap_or_default()),
+        };
+        match replacement {
+            Some(replacement) => result.push_str(&replacement),
+            None => result.push_str(&rest[start..start + end + 1]),
+        }
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+
+    result
+}
+
+/// Tasks of `.vscode/tasks.json`.
+pub struct VsCodeSource;
+
+impl TaskSource for VsCodeSource {
+    fn name(&self) -> &'static str {
+        "vscode"
+    }
+
+    fn describe(&self) -> &'static str {
+        "VS Code tasks"
+    }
+
+    fn detect(&self, root: &Path, _config: &Config) -> bool {
+        root.join(TASKS_FILE).is_file()
+    }
+
+    fn discover(&self, _config: &Config) -> Result<Vec<Task>> {
+        discover_tasks()
+    }
+
+    fn watched_files(&self, root: &Path, _config: &Config) -> Vec<PathBuf> {
+        vec![root.join(TASKS_FILE)]
+    }
+
+    fn runs(&self, runner: &TaskRunner) -> bool {
+        *runner == TaskRunner::VsCode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_tasks() -> Vec<Task> {
+        parse_tasks_json(
+            include_str!("fixtures/vscode/tasks.json"),
+            Path::new("/home/user/app"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_tasks_json() {
+        let tasks = fixture_tasks();
+
+        // The npm provider task is left out
+        let names: Vec<&str> = tasks.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["Build", "Serve docs", "Test", "Deploy", "Clean"]
+        );
+        assert!(tasks.iter().enumerate().all(|(i, t)| t.id == i));
+        assert!(tasks.iter().all(|t| t.runner == TaskRunner::VsCode));
+
+        let build = &tasks[0];
+        assert_eq!(build.description, Some("Compile the project".to_string()));
+        assert_eq!(build.groups, vec!["build"]);
+        assert_eq!(
+            build.command,
+            vec!["sh", "-c", "cargo build --message-format 'short json'"]
+        );
+        assert_eq!(build.cwd, None);
+        assert_eq!(
+            build.env,
+            vec![("RUST_LOG".to_string(), "info".to_string())]
+        );
+
+        let docs = &tasks[1];
+        assert_eq!(
+            docs.command,
+            vec![
+                "python3",
+                "-m",
+                "http.server",
+                "--directory",
+                "/home/user/app/site"
+            ]
+        );
+        assert_eq!(docs.cwd, Some(PathBuf::from("/home/user/app/docs")));
+        assert_eq!(
+            docs.env,
+            vec![
+                ("PORT".to_string(), "8000".to_string()),
+                ("RUST_LOG".to_string(), "info".to_string()),
+            ]
+        );
+        assert_eq!(
+            docs.body,
+            vec!["python3 -m http.server --directory /home/user/app/site"]
+        );
+        assert!(docs.is_hidden());
+    }
+
+    #[test]
+    fn test_parse_tasks_json_runs_dependencies_first() {
+        let tasks = fixture_tasks();
+
+        let test = &tasks[2];
+        assert_eq!(test.dependencies, vec!["Build"]);
+        assert_eq!(test.groups, vec!["test"]);
+        assert_eq!(
+            test.body,
+            vec![
+                "cargo build --message-format 'short json'",
+                "cargo test -- \"--test-threads 1\"",
+            ]
+        );
+        assert_eq!(test.command[..2], ["sh", "-c"]);
+        assert_eq!(
+            test.command[2],
+            "(cd '/home/user/app' && env 'RUST_LOG=info' 'sh' '-c' 'cargo build --message-format '\\''short json'\\''') && \
+             (cd '/home/user/app' && env 'RUST_LOG=info' 'sh' '-c' 'cargo test -- \"--test-threads 1\"')"
+        );
+
+        // A task without a command runs its dependencies, each once
+        let deploy = &tasks[3];
+        assert_eq!(deploy.dependencies, vec!["Test", "Build"]);
+        assert_eq!(deploy.body.len(), 2);
+        assert!(deploy.env.is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_tasks_json_depends_order() {
+        let scratch = crate::test_util::scratch_dir();
+        let root = scratch.path();
+        let source = r#"{
+            "tasks": [
+                // Only finishes once "Signal" ran, so it needs to run alongside
+                { "label": "Wait", "type": "shell", "command": "for i in $(seq 20); do [ -f signal ] && exit 0; sleep 0.05; done; exit 1" },
+                { "label": "Signal", "type": "shell", "command": "touch signal" },
+                { "label": "Fail", "type": "shell", "command": "exit 3" },
+                { "label": "Both", "dependsOn": ["Wait", "Signal"], "type": "shell", "command": "touch both" },
+                { "label": "In order", "dependsOn": ["Wait", "Signal"], "dependsOrder": "sequence" },
+                { "label": "Failing", "dependsOn": ["Fail", "Signal"], "type": "shell", "command": "touch failing" },
+            ],
+        }"#;
+        let tasks = parse_tasks_json(source, root).unwrap();
+        let run = |name: &str| {
+            let task = tasks.iter().find(|t| t.name == name).unwrap();
+            let _ = std::fs::remove_file(root.join("signal"));
+            std::process::Command::new(&task.command[0])
+                .args(&task.command[1..])
+                .current_dir(root)
+                .status()
+                .unwrap()
+                .success()
+        };
+
+        // Dependencies run at the same time unless they are in sequence
+        assert!(run("Both"));
+        assert!(root.join("both").exists());
+        assert!(!run("In order"));
+
+        // A failing dependency fails the task once all of them finished
+        assert!(!run("Failing"));
+        assert!(root.join("signal").exists());
+        assert!(!root.join("failing").exists());
+    }
+
+    #[test]
+    fn test_parse_tasks_json_platform_overrides() {
+        let clean = &fixture_tasks()[4];
+        let expected = if cfg!(windows) {
+            "del /q target"
+        } else {
+            "rm -rf target"
+        };
+        assert_eq!(clean.command.last().map(String::as_str), Some(expected));
+    }
+
+    #[test]
+    fn test_parse_tasks_json_errors() {
+        assert!(parse_tasks_json("{ tasks: [", Path::new("/")).is_err());
+        assert!(parse_tasks_json("{}", Path::new("/")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let root = Path::new("/home/user/app");
+        assert_eq!(
+            substitute_variables("${workspaceFolder}/bin:${workspaceFolderBasename}", root),
+            "/home/user/app/bin:app"
+        );
+        assert_eq!(
+            substitute_variables("${env:TASKPAD_UNSET_VARIABLE}x", root),
+            "x"
+        );
+        assert_eq!(
+            substitute_variables("${input:target} ${file", root),
+            "${input:target} ${file"
+        );
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("build", None), "build");
+        assert_eq!(shell_quote("a b", None), "'a b'");
+        assert_eq!(shell_quote("it's", Some("strong")), "'it'\\''s'");
+        assert_eq!(shell_quote("a \"b\"", Some("weak")), "\"a \\\"b\\\"\"");
+        assert_eq!(shell_quote("a b", Some("escape")), "a\\ b");
+    }
+}
@@ -0,0 +1,11 @@
+/// Helpers shared by the unit tests.
+use tempfile::TempDir;
+
+/// Creates an empty scratch directory, removed again when the returned
+/// `TempDir` is dropped.
+pub fn scratch_dir() -> TempDir {
+    tempfile::Builder::new()
+        .prefix("taskpad-")
+        .tempdir()
+        .expect("Failed to create a scratch directory")
+}
@@ -2,16 +2,19 @@
 ///
 /// This module contains all layout and drawing logic for the TUI.
 /// Rendering is a pure function of the AppState.
+use crate::ansi;
 use crate::app::{
-    display_col_to_byte_idx, str_display_width, AppState, FocusedPane, HistoryEntry, TaskStatus,
+    display_col_to_byte_idx, str_display_width, AppState, ArgsForm, FocusedPane, HistoryEntry,
+    ParamKind, TaskRow, TaskStatus,
 };
+use crate::tasks::registry::{SourceDiagnostic, SourceOutcome};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
-        Block, Borders, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation, ScrollbarState,
-        Wrap,
+        Block, Borders, Clear, List, ListItem, Paragraph, Scrollbar, ScrollbarOrientation,
+        ScrollbarState, Wrap,
     },
     Frame,
 };
@@ -92,44 +95,66 @@ pub fn render(frame: &mut Frame, app: &AppState) {
 
     // Render bottom key hints bar
     render_key_hints(frame, chunks[2]);
+
+    // Render the arguments form on top of everything else
+    if let Some(form) = &app.args_form {
+        render_args_form(frame, form, size);
+    }
+
+    if app.show_diagnostics {
+        render_diagnostics(frame, &app.diagnostics, size);
+    }
 }
 
 /// Renders the top status bar showing app name and current status.
 fn render_status_bar(frame: &mut Frame, app: &AppState, area: Rect) {
+    let running_count = app.running_count();
+
     let status_text = if let Some(ref msg) = app.message {
         format!("Taskpad | {}", msg)
-    } else if let Some(ref running) = app.running_task {
-        match running.status {
+    } else if let Some(running) = app.status_run() {
+        let summary = match running.status {
             TaskStatus::Running => format!(
-                "Taskpad | Running: {} {}",
+                "Running: {} {}",
                 running.task.runner.prefix(),
                 running.task.name
             ),
             TaskStatus::Success(code) => format!(
-                "Taskpad | Last: {} {} (exit={})",
+                "Last: {} {} (exit={})",
                 running.task.runner.prefix(),
                 running.task.name,
                 code
             ),
             TaskStatus::Failed(code) => format!(
-                "Taskpad | Failed: {} {} (exit={})",
+                "Failed: {} {} (exit={})",
                 running.task.runner.prefix(),
                 running.task.name,
                 code
             ),
+            TaskStatus::Cancelled => format!(
+                "Cancelled: {} {}",
+                running.task.runner.prefix(),
+                running.task.name
+            ),
+            TaskStatus::Killed(signal) => format!(
+                "Killed: {} {} (signal={})",
+                running.task.runner.prefix(),
+                running.task.name,
+                signal
+            ),
+        };
+
+        if running_count > 1 {
+            format!("Taskpad | {} | {} running", summary, running_count)
+        } else {
+            format!("Taskpad | {}", summary)
         }
     } else {
         "Taskpad | Idle".to_string()
     };
 
-    let style = if app.is_task_running() {
-        Style::default().fg(Color::Yellow)
-    } else if let Some(ref running) = app.running_task {
-        match running.status {
-            TaskStatus::Success(_) => Style::default().fg(Color::Green),
-            TaskStatus::Failed(_) => Style::default().fg(Color::Red),
-            _ => Style::default(),
-        }
+    let style = if let Some(running) = app.status_run() {
+        status_style(&running.status)
     } else {
         Style::default()
     };
@@ -138,6 +163,27 @@ fn render_status_bar(frame: &mut Frame, app: &AppState, area: Rect) {
     frame.render_widget(status, area);
 }
 
+/// Returns the color used for a run's status in the status bar and tabs
+fn status_style(status: &TaskStatus) -> Style {
+    match status {
+        TaskStatus::Running => Style::default().fg(Color::Yellow),
+        TaskStatus::Success(_) => Style::default().fg(Color::Green),
+        TaskStatus::Failed(_) | TaskStatus::Killed(_) => Style::default().fg(Color::Red),
+        TaskStatus::Cancelled => Style::default().fg(Color::Magenta),
+    }
+}
+
+/// Returns the symbol shown for a status in the history list and run tabs
+fn status_symbol(status: &TaskStatus) -> &'static str {
+    match status {
+        TaskStatus::Running => "⋯",
+        TaskStatus::Success(_) => "✓",
+        TaskStatus::Failed(_) => "✗",
+        TaskStatus::Cancelled => "⊘",
+        TaskStatus::Killed(_) => "☠",
+    }
+}
+
 /// Renders the task list pane on the left side.
 fn render_task_list(frame: &mut Frame, app: &AppState, area: Rect) {
     let border_color = if app.focused_pane == FocusedPane::Tasks {
@@ -152,7 +198,11 @@ fn render_task_list(frame: &mut Frame, app: &AppState, area: Rect) {
         .border_style(Style::default().fg(border_color));
 
     if app.tasks.is_empty() {
-        let message = if app.message.is_some() {
+        let message = if app.discovering {
+            Paragraph::new("Discovering tasks...\nPress 'D' to see the task sources.")
+                .block(block)
+                .wrap(Wrap { trim: true })
+        } else if app.message.is_some() {
             // Error message is shown in status bar
             Paragraph::new("No tasks available.\nPress 'q' to quit.")
                 .block(block)
@@ -169,23 +219,19 @@ fn render_task_list(frame: &mut Frame, app: &AppState, area: Rect) {
     // Calculate visible range based on scroll offset
     let inner_height = area.height.saturating_sub(2) as usize; // Subtract borders
     let start = app.task_scroll_offset;
-    let end = (start + inner_height).min(app.tasks.len());
+    let end = (start + inner_height).min(app.task_rows.len());
 
-    // Create list items for visible tasks
-    let items: Vec<ListItem> = app.tasks[start..end]
+    // Create list items for visible rows
+    let items: Vec<ListItem> = app.task_rows[start..end]
         .iter()
         .enumerate()
-        .map(|(idx, task)| {
+        .map(|(idx, row)| {
             let actual_idx = start + idx;
             let is_selected =
                 actual_idx == app.selected_index && app.focused_pane == FocusedPane::Tasks;
 
-            // Check if this task is the currently running one
-            let is_running = app
-                .running_task
-                .as_ref()
-                .map(|rt| rt.task.name == task.name && rt.status == TaskStatus::Running)
-                .unwrap_or(false);
+            // Check if this task (or a task of this module) has a run in progress
+            let is_running = app.is_row_running(row);
 
             let prefix = if is_running {
                 "▶ "
@@ -195,6 +241,26 @@ fn render_task_list(frame: &mut Frame, app: &AppState, area: Rect) {
                 "  "
             };
 
+            // Modules and their tasks are indented by depth
+            let indent = "  ".repeat(row.depth(&app.tasks));
+            let (task, label) = match row {
+                TaskRow::Task(index) => {
+                    let task = &app.tasks[*index];
+                    (task, format!("{}{}", indent, task.short_name()))
+                }
+                TaskRow::Module { module, expanded } => {
+                    let task = app
+                        .tasks
+                        .iter()
+                        .find(|t| module.contains(t))
+                        .expect("module rows are built from their tasks");
+                    let arrow = if *expanded { "▾" } else { "▸" };
+                    let name = module.path.last().map(String::as_str).unwrap_or_default();
+                    (task, format!("{}{} {}", indent, arrow, name))
+                }
+            };
+            let is_module = matches!(row, TaskRow::Module { .. });
+
             // Create styled line with bold runner prefix
             let spans = vec![
                 Span::raw(prefix),
@@ -204,7 +270,11 @@ fn render_task_list(frame: &mut Frame, app: &AppState, area: Rect) {
                         .add_modifier(Modifier::BOLD)
                         .fg(Color::Cyan),
                 ),
-                Span::raw(&task.name),
+                if is_module {
+                    Span::styled(label, Style::default().add_modifier(Modifier::BOLD))
+                } else {
+                    Span::raw(label)
+                },
             ];
 
             let line = Line::from(spans);
@@ -213,6 +283,12 @@ fn render_task_list(frame: &mut Frame, app: &AppState, area: Rect) {
                 Style::default().bg(Color::White).fg(Color::Black)
             } else if is_running {
                 Style::default().fg(Color::Yellow)
+            } else if !is_module && app.is_flashing(task) {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else if !is_module && task.is_hidden() {
+                Style::default().fg(Color::DarkGray)
             } else {
                 Style::default()
             };
@@ -225,7 +301,7 @@ fn render_task_list(frame: &mut Frame, app: &AppState, area: Rect) {
     frame.render_widget(list, area);
 
     // Render scrollbar if there are more tasks than can fit
-    let total_tasks = app.tasks.len();
+    let total_tasks = app.task_rows.len();
     if total_tasks > inner_height {
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(Some("↑"))
@@ -253,12 +329,50 @@ fn render_info_box(frame: &mut Frame, app: &AppState, area: Rect) {
         .border_style(Style::default().fg(Color::White));
 
     if let Some(task) = app.selected_task() {
-        let content = if let Some(ref desc) = task.description {
+        let mut content = if let Some(ref desc) = task.description {
             desc.clone()
         } else {
             "No description available.".to_string()
         };
 
+        if !task.params.is_empty() {
+            let signatures: Vec<String> = task.params.iter().map(|p| p.signature()).collect();
+            content.push_str(&format!("\nParameters: {}", signatures.join(" ")));
+        }
+        for (label, values) in [
+            ("Aliases", &task.aliases),
+            ("Groups", &task.groups),
+            ("Attributes", &task.attributes),
+            ("Depends on", &task.dependencies),
+        ] {
+            if !values.is_empty() {
+                content.push_str(&format!("\n{}: {}", label, values.join(", ")));
+            }
+        }
+        if task.private {
+            content.push_str("\nPrivate");
+        }
+        if task.file_target {
+            content.push_str("\nFile target");
+        }
+        if let Some(ref cwd) = task.cwd {
+            content.push_str(&format!("\nDirectory: {}", cwd.display()));
+        }
+        if !task.env.is_empty() {
+            let vars: Vec<String> = task
+                .env
+                .iter()
+                .map(|(k, v)| format!("{}={}", k, v))
+                .collect();
+            content.push_str(&format!("\nEnvironment: {}", vars.join(" ")));
+        }
+        if !task.body.is_empty() {
+            content.push('\n');
+            for line in &task.body {
+                content.push_str(&format!("\n    {}", line));
+            }
+        }
+
         // Split content into wrapped lines based on available width
         let inner_width = area.width.saturating_sub(2) as usize; // Subtract borders
         let inner_height = area.height.saturating_sub(2) as usize;
@@ -376,11 +490,8 @@ fn render_history_container(frame: &mut Frame, app: &AppState, area: Rect) {
             let timestamp_str = format_timestamp(&entry.timestamp);
 
             // Format status with color
-            let status_span = match entry.status {
-                TaskStatus::Success(_) => Span::styled("✓", Style::default().fg(Color::Green)),
-                TaskStatus::Failed(_) => Span::styled("✗", Style::default().fg(Color::Red)),
-                TaskStatus::Running => Span::styled("⋯", Style::default().fg(Color::Yellow)),
-            };
+            let status_span =
+                Span::styled(status_symbol(&entry.status), status_style(&entry.status));
 
             // Create the line with timestamp, status, runner, and task name
             let prefix = if is_selected { "> " } else { "  " };
@@ -394,12 +505,12 @@ fn render_history_container(frame: &mut Frame, app: &AppState, area: Rect) {
                 status_span,
                 Span::raw(" "),
                 Span::styled(
-                    format!("{} ", entry.runner.prefix()),
+                    format!("{} ", entry.task.runner.prefix()),
                     Style::default()
                         .add_modifier(Modifier::BOLD)
                         .fg(Color::Cyan),
                 ),
-                Span::raw(&entry.task_name),
+                Span::raw(&entry.task.name),
             ];
 
             let line = Line::from(spans);
@@ -470,30 +581,94 @@ fn format_timestamp(time: &SystemTime) -> String {
     }
 }
 
+/// Returns the segments of the log pane title when run tabs are shown.
+///
+/// Each segment is paired with the index of the run tab it belongs to (if any),
+/// so the same layout can be used for rendering and for mouse hit-testing.
+fn run_tab_segments(app: &AppState) -> Vec<(String, Option<usize>)> {
+    let mut segments = vec![("Logs ".to_string(), None)];
+
+    for (idx, run) in app.runs.iter().enumerate() {
+        segments.push((
+            format!(
+                " {} {} {} ",
+                status_symbol(&run.status),
+                run.task.runner.prefix(),
+                run.task.name
+            ),
+            Some(idx),
+        ));
+        segments.push(("│".to_string(), None));
+    }
+
+    segments
+}
+
+/// Returns the index of the run tab at the given column of the log pane title.
+///
+/// `column` is relative to the first character of the title (just right of the
+/// top-left border corner).
+pub fn run_tab_at(app: &AppState, column: u16) -> Option<usize> {
+    if app.is_history_focused() || app.runs.is_empty() {
+        return None;
+    }
+
+    let mut start = 0;
+    for (text, tab) in run_tab_segments(app) {
+        let end = start + str_display_width(&text);
+        if (start..end).contains(&(column as usize)) {
+            return tab;
+        }
+        start = end;
+    }
+    None
+}
+
 /// Renders the log pane on the right side showing task output.
 fn render_log_pane(frame: &mut Frame, app: &AppState, area: Rect) {
     let title = if app.is_history_focused() {
         if let Some(entry) = app.selected_history_entry() {
             let timestamp_str = format_timestamp(&entry.timestamp);
-            format!(
+            Line::from(format!(
                 "Logs (History) - {} {} - {}",
-                entry.runner.prefix(),
-                entry.task_name,
+                entry.task.runner.prefix(),
+                entry.task.name,
                 timestamp_str
-            )
+            ))
         } else {
-            "Logs (History)".to_string()
+            Line::from("Logs (History)")
         }
+    } else if !app.runs.is_empty() {
+        // One tab per run; the selected task's run is highlighted
+        let active_id = app.active_run().map(|run| run.id);
+        let spans: Vec<Span> = run_tab_segments(app)
+            .into_iter()
+            .map(|(text, tab)| match tab.map(|idx| &app.runs[idx]) {
+                Some(run) if Some(run.id) == active_id => Span::styled(
+                    text,
+                    status_style(&run.status).add_modifier(Modifier::REVERSED | Modifier::BOLD),
+                ),
+                Some(run) => Span::styled(text, status_style(&run.status)),
+                None => Span::raw(text),
+            })
+            .collect();
+        Line::from(spans)
     } else if let Some(task) = app.selected_task() {
-        format!("Logs - {} {}", task.runner.prefix(), task.name)
+        Line::from(format!("Logs - {} {}", task.runner.prefix(), task.name))
     } else {
-        "Logs".to_string()
+        Line::from("Logs")
+    };
+
+    let border_color = if app.is_logs_focused() {
+        Color::Cyan
+    } else {
+        Color::White
     };
 
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(Color::White));
+        .border_style(Style::default().fg(border_color));
 
     // Get logs based on focus: history logs if history focused, otherwise current task logs
     let log_lines = if app.is_history_focused() {
@@ -531,86 +706,52 @@ fn render_log_pane(frame: &mut Frame, app: &AppState, area: Rect) {
         let visible_lines = &log_lines[start..end];
 
         // Convert log lines to Text with appropriate styling and selection highlighting
+        let selection = app.current_task_selection().map(|s| s.normalized());
         let lines: Vec<Line> = visible_lines
             .iter()
             .enumerate()
             .map(|(visible_idx, line)| {
                 let actual_line_idx = start + visible_idx;
 
+                // Escape sequences are either rendered as styles or dropped
+                let segments = if app.render_ansi {
+                    ansi::parse_line(line)
+                } else {
+                    vec![(ansi::plain_text(line), Style::default())]
+                };
+                let plain: String = segments.iter().map(|(text, _)| text.as_str()).collect();
+
                 // Get base style for the line
-                let base_style = if line.starts_with("[stderr]") {
+                let base_style = if plain.starts_with("[stderr]") {
                     Style::default().fg(Color::Red)
-                } else if line.starts_with("ERROR") {
+                } else if plain.starts_with("ERROR") {
                     Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
-                } else if line.starts_with("Starting task:") || line.starts_with("Task exited") {
+                } else if plain.starts_with("Starting task:") || plain.starts_with("Task exited") {
                     Style::default().fg(Color::Cyan)
                 } else {
                     Style::default()
                 };
 
-                // Check if this line has any selection for the current task
-                if let Some(selection) = app.current_task_selection() {
-                    let (sel_start, sel_end) = selection.normalized();
-
-                    // Check if this line is within the selection range
-                    if actual_line_idx >= sel_start.line && actual_line_idx <= sel_end.line {
-                        // Build spans with selection highlighting
-                        // Convert display columns to byte indices for safe UTF-8 slicing
-                        let mut spans = Vec::new();
-                        let line_len = line.len();
-
-                        if actual_line_idx == sel_start.line && actual_line_idx == sel_end.line {
-                            // Single line selection
-                            let start_byte = display_col_to_byte_idx(line, sel_start.col);
-                            let end_byte = display_col_to_byte_idx(line, sel_end.col);
-
-                            if start_byte > 0 {
-                                spans
-                                    .push(Span::styled(line[..start_byte].to_string(), base_style));
-                            }
-                            if end_byte > start_byte {
-                                spans.push(Span::styled(
-                                    line[start_byte..end_byte].to_string(),
-                                    base_style.bg(Color::DarkGray),
-                                ));
-                            }
-                            if end_byte < line_len {
-                                spans.push(Span::styled(line[end_byte..].to_string(), base_style));
-                            }
-                        } else if actual_line_idx == sel_start.line {
-                            // First line of multi-line selection
-                            let start_byte = display_col_to_byte_idx(line, sel_start.col);
-                            if start_byte > 0 {
-                                spans
-                                    .push(Span::styled(line[..start_byte].to_string(), base_style));
-                            }
-                            spans.push(Span::styled(
-                                line[start_byte..].to_string(),
-                                base_style.bg(Color::DarkGray),
-                            ));
-                        } else if actual_line_idx == sel_end.line {
-                            // Last line of multi-line selection
-                            let end_byte = display_col_to_byte_idx(line, sel_end.col);
-                            if end_byte > 0 {
-                                spans.push(Span::styled(
-                                    line[..end_byte].to_string(),
-                                    base_style.bg(Color::DarkGray),
-                                ));
-                            }
-                            if end_byte < line_len {
-                                spans.push(Span::styled(line[end_byte..].to_string(), base_style));
-                            }
-                        } else {
-                            // Middle line - entire line is selected
-                            spans.push(Span::styled(line.clone(), base_style.bg(Color::DarkGray)));
-                        }
-
-                        return Line::from(spans);
+                // Byte range of the plain text covered by the selection (if any)
+                let selected = selection.and_then(|(sel_start, sel_end)| {
+                    if actual_line_idx < sel_start.line || actual_line_idx > sel_end.line {
+                        return None;
                     }
-                }
+                    // Convert display columns to byte indices for safe UTF-8 slicing
+                    let start_byte = if actual_line_idx == sel_start.line {
+                        display_col_to_byte_idx(&plain, sel_start.col)
+                    } else {
+                        0
+                    };
+                    let end_byte = if actual_line_idx == sel_end.line {
+                        display_col_to_byte_idx(&plain, sel_end.col)
+                    } else {
+                        plain.len()
+                    };
+                    Some(start_byte..end_byte)
+                });
 
-                // No selection on this line, use regular styling
-                Line::from(Span::styled(line.clone(), base_style))
+                Line::from(styled_spans(segments, base_style, selected))
             })
             .collect();
 
@@ -649,6 +790,236 @@ fn render_log_pane(frame: &mut Frame, app: &AppState, area: Rect) {
     }
 }
 
+/// Builds the spans of a log line from its styled segments.
+///
+/// Segment styles are patched onto `base_style`, and the bytes of the line's
+/// plain text within `selected` are highlighted.
+fn styled_spans(
+    segments: Vec<(String, Style)>,
+    base_style: Style,
+    selected: Option<std::ops::Range<usize>>,
+) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+
+    for (text, style) in segments {
+        let style = base_style.patch(style);
+        let seg_start = offset;
+        let seg_end = offset + text.len();
+        offset = seg_end;
+
+        // Split the segment at the selection boundaries that fall inside it
+        let (sel_start, sel_end) = match &selected {
+            Some(range) if range.start < seg_end && range.end > seg_start => (
+                range.start.max(seg_start) - seg_start,
+                range.end.min(seg_end) - seg_start,
+            ),
+            _ => {
+                spans.push(Span::styled(text, style));
+                continue;
+            }
+        };
+
+        if sel_start > 0 {
+            spans.push(Span::styled(text[..sel_start].to_string(), style));
+        }
+        spans.push(Span::styled(
+            text[sel_start..sel_end].to_string(),
+            style.bg(Color::DarkGray),
+        ));
+        if sel_end < text.len() {
+            spans.push(Span::styled(text[sel_end..].to_string(), style));
+        }
+    }
+
+    spans
+}
+
+/// Renders the form for entering a task's arguments as a centered popup.
+fn render_args_form(frame: &mut Frame, form: &ArgsForm, area: Rect) {
+    let labels: Vec<String> = form
+        .task
+        .params
+        .iter()
+        .map(|param| {
+            let mut label = match param.kind {
+                ParamKind::Singular => String::new(),
+                ParamKind::Star => "*".to_string(),
+                ParamKind::Plus => "+".to_string(),
+            };
+            if param.exported {
+                label.push('$');
+            }
+            label.push_str(&param.name);
+            label
+        })
+        .collect();
+    let label_width = labels
+        .iter()
+        .map(|l| str_display_width(l))
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line> = labels
+        .iter()
+        .zip(&form.task.params)
+        .zip(&form.values)
+        .enumerate()
+        .map(|(idx, ((label, param), value))| {
+            let focused = idx == form.focused;
+            let padding = " ".repeat(label_width - str_display_width(label));
+            let label_style = if focused {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Cyan)
+            };
+
+            let mut spans = vec![
+                Span::styled(if focused { "> " } else { "  " }, label_style),
+                Span::styled(format!("{}{} ", label, padding), label_style),
+                Span::raw("= "),
+                Span::raw(value.clone()),
+            ];
+            if focused {
+                spans.push(Span::styled(" ", Style::default().bg(Color::White)));
+            }
+            if let Some(expression) = param.default_expression().filter(|_| value.is_empty()) {
+                // Left empty, the task runner evaluates the default itself
+                spans.push(Span::styled(
+                    format!("  (default: {})", expression),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            if param.is_required() {
+                spans.push(Span::styled(
+                    "  (required)",
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            Line::from(spans)
+        })
+        .collect();
+
+    lines.push(Line::raw(""));
+    lines.push(Line::from(vec![
+        Span::raw("Enter:"),
+        Span::styled(" run ", Style::default().fg(Color::Cyan)),
+        Span::raw("│ Tab:"),
+        Span::styled(" next field ", Style::default().fg(Color::Cyan)),
+        Span::raw("│ Esc:"),
+        Span::styled(" cancel", Style::default().fg(Color::Cyan)),
+    ]));
+
+    // Center the popup, leaving room for long values
+    let width = 70.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let block = Block::default()
+        .title(format!(
+            " Arguments: {} {} ",
+            form.task.runner.prefix(),
+            form.task.name
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(Paragraph::new(lines).block(block), popup);
+}
+
+/// Renders how discovery went for each task source as a centered popup.
+fn render_diagnostics(frame: &mut Frame, diagnostics: &[SourceDiagnostic], area: Rect) {
+    let name_width = diagnostics
+        .iter()
+        .map(|d| str_display_width(&d.source))
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let (outcome, style) = match &diagnostic.outcome {
+                SourceOutcome::Found(count) if diagnostic.cached => (
+                    format!("{} task(s) (cached)", count),
+                    Style::default().fg(Color::Green),
+                ),
+                SourceOutcome::Found(count) => (
+                    format!("{} task(s)", count),
+                    Style::default().fg(Color::Green),
+                ),
+                SourceOutcome::Discovering => (
+                    "discovering...".to_string(),
+                    Style::default().fg(Color::Yellow),
+                ),
+                SourceOutcome::NotDetected => (
+                    "not detected".to_string(),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                SourceOutcome::Disabled => {
+                    ("disabled".to_string(), Style::default().fg(Color::DarkGray))
+                }
+                SourceOutcome::Failed(error) => (error.clone(), Style::default().fg(Color::Red)),
+            };
+            let padding = " ".repeat(name_width - str_display_width(&diagnostic.source));
+
+            Line::from(vec![
+                Span::styled(
+                    format!("{}{} ", diagnostic.source, padding),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(
+                    format!("{:>5}ms ", diagnostic.elapsed.as_millis()),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(outcome, style),
+                Span::styled(
+                    format!("  {}", diagnostic.description),
+                    Style::default().fg(Color::DarkGray),
+                ),
+            ])
+        })
+        .collect();
+
+    if lines.is_empty() {
+        lines.push(Line::raw("No task sources ran."));
+    }
+    lines.push(Line::raw(""));
+    lines.push(Line::from(vec![
+        Span::raw("Esc/D:"),
+        Span::styled(" close", Style::default().fg(Color::Cyan)),
+    ]));
+
+    let width = 80.min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    };
+
+    let block = Block::default()
+        .title(" Task sources ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        popup,
+    );
+}
+
 /// Renders the bottom key hints bar.
 fn render_key_hints(frame: &mut Frame, area: Rect) {
     let hints = vec![
@@ -658,8 +1029,18 @@ fn render_key_hints(frame: &mut Frame, area: Rect) {
         Span::styled(" focus ", Style::default().fg(Color::Cyan)),
         Span::raw("│ Enter:"),
         Span::styled(" run ", Style::default().fg(Color::Cyan)),
+        Span::raw("│ s:"),
+        Span::styled(" stop ", Style::default().fg(Color::Cyan)),
+        Span::raw("│ I:"),
+        Span::styled(" type ", Style::default().fg(Color::Cyan)),
+        Span::raw("│ Tab/x:"),
+        Span::styled(" switch/close tab ", Style::default().fg(Color::Cyan)),
         Span::raw("│ y/Ctrl+C:"),
         Span::styled(" copy ", Style::default().fg(Color::Cyan)),
+        Span::raw("│ a:"),
+        Span::styled(" colors ", Style::default().fg(Color::Cyan)),
+        Span::raw("│ p/P:"),
+        Span::styled(" pty ", Style::default().fg(Color::Cyan)),
         Span::raw("│ h:"),
         Span::styled(" history ", Style::default().fg(Color::Cyan)),
         Span::raw("│ i:"),
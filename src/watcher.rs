@@ -0,0 +1,169 @@
+/// Task file watching module.
+///
+/// This module watches the files tasks are defined in (justfile, Makefile,
+/// package.json, the Taskpad config, ...) so the task list can be reloaded
+/// when one of them changes. Files are watched through their parent
+/// directories, so files that don't exist yet and editors that save by
+/// replacing the file are handled too.
+use color_eyre::eyre::{eyre, Result};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
+
+/// How long the files must be quiet before a change is reported, so a save
+/// touching several files (or writing one in several steps) reloads once.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Watches task definition files for changes.
+pub struct TaskWatcher {
+    /// Kept alive to keep watching
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    /// Watched files and directories (changes inside a watched directory count too)
+    paths: HashSet<PathBuf>,
+    /// Time of the last change not reported yet
+    pending: Option<Instant>,
+}
+
+impl TaskWatcher {
+    /// Starts watching `paths`, which may be relative to the current
+    /// directory and may not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the platform's file watcher can't be started.
+    pub fn new(paths: &[PathBuf]) -> Result<Self> {
+        let (tx, events) = channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(|e| eyre!("Failed to watch task files: {}", e))?;
+
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let paths: HashSet<PathBuf> = paths.iter().map(|path| normalize(&cwd, path)).collect();
+
+        let mut dirs: HashSet<&Path> = HashSet::new();
+        for path in &paths {
+            dirs.extend(path.parent());
+            if path.is_dir() {
+                dirs.insert(path);
+            }
+        }
+        for dir in dirs {
+            // Directories that don't exist can't be watched; creating them
+            // doesn't define any tasks by itself
+            let _ = watcher.watch(dir, RecursiveMode::NonRecursive);
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            paths,
+            pending: None,
+        })
+    }
+
+    /// Returns true once a watched file changed and things have been quiet
+    /// for a moment since.
+    pub fn changed(&mut self) -> bool {
+        while let Ok(event) = self.events.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            if event.paths.iter().any(|path| self.is_watched(path)) {
+                self.pending = Some(Instant::now());
+            }
+        }
+
+        match self.pending {
+            Some(since) if since.elapsed() >= DEBOUNCE => {
+                self.pending = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if `path` is a watched file or directory, or inside a
+    /// watched directory.
+    fn is_watched(&self, path: &Path) -> bool {
+        self.paths.contains(path) || path.parent().is_some_and(|dir| self.paths.contains(dir))
+    }
+}
+
+/// Makes `path` absolute (relative to `cwd`) and removes `.` components, so
+/// it compares equal to the paths in file events.
+fn normalize(cwd: &Path, path: &Path) -> PathBuf {
+    cwd.join(path)
+        .components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::scratch_dir;
+    use std::fs;
+
+    /// Waits until the watcher reports a change, up to a few seconds.
+    fn wait_for_change(watcher: &mut TaskWatcher) -> bool {
+        let start = Instant::now();
+        while start.elapsed() < Duration::from_secs(5) {
+            if watcher.changed() {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn test_normalize() {
+        let cwd = Path::new("/home/user/project");
+        assert_eq!(
+            normalize(cwd, Path::new("./justfile")),
+            PathBuf::from("/home/user/project/justfile")
+        );
+        assert_eq!(
+            normalize(cwd, Path::new("/etc/taskpad/config.json5")),
+            PathBuf::from("/etc/taskpad/config.json5")
+        );
+    }
+
+    #[test]
+    fn test_reports_changes_to_watched_files() {
+        let scratch = scratch_dir();
+        let dir = scratch.path();
+        fs::create_dir_all(dir.join("scripts")).unwrap();
+        fs::write(dir.join("justfile"), "build:\n").unwrap();
+
+        let mut watcher = TaskWatcher::new(&[
+            dir.join("justfile"),
+            dir.join("Makefile"),
+            dir.join("scripts"),
+        ])
+        .unwrap();
+        assert!(!watcher.changed());
+
+        fs::write(dir.join("justfile"), "build:\ntest:\n").unwrap();
+        assert!(wait_for_change(&mut watcher));
+        assert!(!watcher.changed());
+
+        // Files that didn't exist and files in watched directories count
+        fs::write(dir.join("Makefile"), "all:\n").unwrap();
+        assert!(wait_for_change(&mut watcher));
+        fs::write(dir.join("scripts/deploy.sh"), "#!/bin/sh\n").unwrap();
+        assert!(wait_for_change(&mut watcher));
+
+        // Other files in the same directory don't
+        fs::write(dir.join("notes.txt"), "unrelated").unwrap();
+        std::thread::sleep(DEBOUNCE * 2);
+        assert!(!watcher.changed());
+    }
+}